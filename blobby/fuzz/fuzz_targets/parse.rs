@@ -0,0 +1,39 @@
+#![no_main]
+use blobby::{BlobIterator, BlobReader, BlobStream, BlobWriter, Error};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let iter_res: Option<Vec<&[u8]>> = BlobIterator::new(data)
+        .ok()
+        .and_then(|iter| iter.collect::<Result<_, _>>().ok());
+    let stream_res: Option<Vec<Vec<u8>>> = BlobStream::new(data)
+        .ok()
+        .and_then(|stream| stream.collect::<Result<_, _>>().ok());
+    let reader = BlobReader::new(data);
+
+    match &reader {
+        Ok(_) => assert!(iter_res.is_some()),
+        // number of blobs is checked against schema only by `BlobReader`
+        Err(Error::NotEnoughElements) => {}
+        Err(_) => assert!(iter_res.is_none()),
+    }
+    assert_eq!(
+        iter_res
+            .as_ref()
+            .map(|v| v.iter().map(|b| b.to_vec()).collect::<Vec<_>>()),
+        stream_res,
+    );
+
+    // successfully parsed data must survive re-encoding
+    if let Ok(r) = reader {
+        let mut w = BlobWriter::with_format(r.format());
+        if let Some(fields) = r.fields() {
+            w.set_fields(fields);
+        }
+        w.extend(r.iter());
+        let buf = w.encode();
+        let r2 = BlobReader::new(&buf).unwrap();
+        assert_eq!(r.as_slice(), r2.as_slice());
+        assert_eq!(r.fields(), r2.fields());
+    }
+});
@@ -0,0 +1,64 @@
+//! CRC-32 checksum (ISO-HDLC variant, as used by zlib and gzip).
+
+const POLY: u32 = 0xEDB8_8320;
+
+const TABLE: [u32; 256] = gen_table();
+
+const fn gen_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut c = i as u32;
+        let mut j = 0;
+        while j < 8 {
+            c = if c & 1 != 0 { POLY ^ (c >> 1) } else { c >> 1 };
+            j += 1;
+        }
+        table[i] = c;
+        i += 1;
+    }
+    table
+}
+
+/// Incremental CRC-32 hasher.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct Crc32(u32);
+
+impl Crc32 {
+    pub(crate) fn new() -> Self {
+        Crc32(!0)
+    }
+
+    pub(crate) fn update(&mut self, data: &[u8]) {
+        for &b in data {
+            self.0 = TABLE[((self.0 ^ b as u32) & 0xFF) as usize] ^ (self.0 >> 8);
+        }
+    }
+
+    pub(crate) fn finalize(self) -> u32 {
+        !self.0
+    }
+}
+
+/// Compute CRC-32 checksum of `data`.
+pub(crate) fn crc32(data: &[u8]) -> u32 {
+    let mut h = Crc32::new();
+    h.update(data);
+    h.finalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{crc32, Crc32};
+
+    #[test]
+    fn check_value() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+
+        let mut h = Crc32::new();
+        h.update(b"1234");
+        h.update(b"56789");
+        assert_eq!(h.finalize(), 0xCBF4_3926);
+    }
+}
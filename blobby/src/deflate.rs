@@ -0,0 +1,114 @@
+//! DEFLATE compression of the data section.
+use crate::{
+    crc32::crc32, parse_header, push_vlq, read_vlq_n, Error, Format, CHECKSUM_LEN, FLAG_CHECKSUM,
+    FLAG_DEFLATE, V3_MAGIC,
+};
+use alloc::{borrow::Cow, vec::Vec};
+
+pub(crate) fn compress(data: &[u8], level: u8) -> Vec<u8> {
+    miniz_oxide::deflate::compress_to_vec(data, level)
+}
+
+/// Decompress data section of the encoded `data`.
+///
+/// Returns equivalent data with uncompressed data section, which can
+/// be processed by [`BlobIterator`][crate::BlobIterator] and other readers.
+/// If the data section is not compressed, `data` is returned as-is.
+/// Checksum footer (if present) is verified and recomputed for
+/// the decompressed data.
+///
+/// # Examples
+/// ```
+/// let mut w = blobby::BlobWriter::new();
+/// w.extend(["hello", "world", "hello"].iter());
+/// w.set_compression(Some(6));
+/// let buf = w.encode();
+/// assert_eq!(blobby::BlobIterator::new(&buf).err(), Some(blobby::Error::Compressed));
+///
+/// let buf = blobby::decompress(&buf).unwrap();
+/// let blobs: Vec<_> = blobby::BlobIterator::new(&buf).unwrap().collect();
+/// assert_eq!(blobs, [Ok(&b"hello"[..]), Ok(b"world"), Ok(b"hello")]);
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "deflate")))]
+pub fn decompress(data: &[u8]) -> Result<Cow<'_, [u8]>, Error> {
+    let header = parse_header(data)?;
+    if header.flags & FLAG_DEFLATE == 0 {
+        return Ok(Cow::Borrowed(data));
+    }
+    let mut pos = 0;
+    let len = read_vlq_n(header.data, &mut pos, Format::V3.vlq_len())?;
+    let records = miniz_oxide::inflate::decompress_to_vec_with_limit(&header.data[pos..], len)
+        .map_err(|_| Error::InvalidCompressedData)?;
+    if records.len() != len {
+        return Err(Error::InvalidCompressedData);
+    }
+
+    let out_len = V3_MAGIC.len() + header.index.len() + len + CHECKSUM_LEN + 1;
+    let mut out = Vec::with_capacity(out_len);
+    out.extend_from_slice(V3_MAGIC);
+    push_vlq(&mut out, header.flags & !FLAG_DEFLATE, Format::V3);
+    out.extend_from_slice(header.index);
+    out.extend_from_slice(&records);
+    if header.flags & FLAG_CHECKSUM != 0 {
+        let crc = crc32(&out);
+        out.extend_from_slice(&crc.to_le_bytes());
+    }
+    Ok(Cow::Owned(out))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::decompress;
+    use crate::{BlobIterator, BlobWriter, Error, Format};
+    use alloc::{borrow::Cow, vec::Vec};
+
+    #[test]
+    fn roundtrip() {
+        let blobs: [&[u8]; 5] = [b"hello", b"", b"hello world", b"world", b"hello world"];
+        let mut w: BlobWriter = blobs.iter().collect();
+        w.set_compression(Some(10));
+        assert_eq!(w.format(), Format::V3);
+        let compressed = w.encode();
+
+        w.set_compression(None);
+        let plain = w.encode();
+        assert!(matches!(decompress(&plain), Ok(Cow::Borrowed(_))));
+
+        let decompressed = decompress(&compressed).unwrap();
+        assert_eq!(&decompressed[..], &plain[..]);
+        let res: Vec<&[u8]> = BlobIterator::new(&decompressed)
+            .unwrap()
+            .map(|v| v.unwrap())
+            .collect();
+        assert_eq!(res, blobs);
+    }
+
+    #[test]
+    fn checksum() {
+        let mut w = BlobWriter::new();
+        w.extend(["hello", "world", "hello"].iter());
+        w.set_checksum(true);
+        let plain = w.encode();
+        w.set_compression(Some(6));
+        let compressed = w.encode();
+
+        let decompressed = decompress(&compressed).unwrap();
+        assert_eq!(&decompressed[..], &plain[..]);
+
+        let mut buf = compressed;
+        let n = buf.len();
+        buf[n - 1] ^= 1;
+        assert_eq!(decompress(&buf), Err(Error::InvalidChecksum));
+    }
+
+    #[test]
+    fn invalid_length() {
+        let mut w = BlobWriter::new();
+        w.push(b"hello");
+        w.set_compression(Some(6));
+        let mut buf = w.encode();
+        // index is empty, so data section length is stored at position 10
+        buf[10] += 1;
+        assert_eq!(decompress(&buf), Err(Error::InvalidCompressedData));
+    }
+}
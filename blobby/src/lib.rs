@@ -15,6 +15,48 @@
 //! number is followed by `n >> 1` bytes, representing a stored binary blob.
 //! Otherwise the entry references a de-duplicated entry number `n >> 1`.
 //!
+//! ## Version 3
+//! The format described above (version 2) supports VLQ values of up to 4 bytes,
+//! which limits size of stored blobs to ~256 MiB. Version 3 of the format lifts
+//! this restriction and extends de-duplication capabilities. Both versions are
+//! accepted by the readers in this crate, [`BlobWriter`] can produce either
+//! version (see [`Format`]).
+//!
+//! Version 3 data starts with the `ff ff ff ff 62 6c 62 33` magic bytes (four
+//! `0xFF` bytes followed by `"blb3"`), which can not start valid version 2
+//! data, since it would be a VLQ value longer than 4 bytes. The magic bytes are
+//! followed by a number representing format flags (see below). The rest of
+//! the data has the same structure as in version 2 with the following changes:
+//!
+//! - VLQ values can be up to 10 bytes long, i.e. they can represent any
+//!   64-bit value.
+//! - The two least significant bits of the entry number `n` are used as flags.
+//!   If the least significant bit is equal to 0, then the number is followed by
+//!   `n >> 1` bytes of a stored blob. If the flags are equal to `0b01`, then the
+//!   entry references the de-duplicated entry number `n >> 2`. If the flags are
+//!   equal to `0b11`, then the number is followed by two numbers `o` and `l` and
+//!   the entry references bytes `o..o + l` of the de-duplicated entry
+//!   number `n >> 2`.
+//!
+//! The following format flags are defined:
+//!
+//! - `0b1`: the data section following the index is compressed. The index is
+//!   followed by a number `u` representing length of the uncompressed data
+//!   section, which is followed by the raw DEFLATE stream of the data section.
+//!   Such data has to be decompressed first using `decompress` (requires
+//!   the `deflate` feature).
+//! - `0b10`: the data is followed by a 4 byte footer containing little-endian
+//!   CRC-32 checksum (the variant used by zlib) of all preceding bytes,
+//!   starting with the magic bytes. Readers verify the checksum before
+//!   parsing the data.
+//! - `0b100`: the index is preceded by a schema section naming fields of
+//!   the stored records. The section starts with a number of fields `k`
+//!   (which must not be zero), followed by `k` entries. Each entry starts
+//!   with an integer `m`, immediately followed by `m` bytes of the UTF-8
+//!   encoded field name. The number of stored blobs must be a multiple of `k`.
+//!
+//! All other flags are reserved and must be equal to 0.
+//!
 //! # Examples
 //! ```
 //! let buf = b"\x02\x05hello\x06world!\x01\x02 \x00\x03\x06:::\x03\x01\x00";
@@ -44,13 +86,16 @@
 //!
 //! [0]: https://en.wikipedia.org/wiki/Variable-length_quantity
 #![no_std]
+#![cfg_attr(docsrs, feature(doc_cfg))]
 #![doc(
     html_logo_url = "https://raw.githubusercontent.com/RustCrypto/media/6ee8e381/logo.svg",
     html_favicon_url = "https://raw.githubusercontent.com/RustCrypto/media/6ee8e381/logo.svg"
 )]
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
-use alloc::{boxed::Box, collections::BTreeMap, vec, vec::Vec};
+use alloc::{boxed::Box, collections::BTreeMap, string::String, vec::Vec};
 use core::iter::Iterator;
 
 /// Iterator over binary blobs
@@ -58,6 +103,39 @@ pub struct BlobIterator<'a> {
     data: &'a [u8],
     dedup: Box<[&'a [u8]]>,
     pos: usize,
+    format: Format,
+    fields: Option<Box<[&'a str]>>,
+}
+
+/// Version of the storage format
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Format {
+    /// Version 2 of the format with VLQ values of up to 4 bytes
+    V2,
+    /// Version 3 of the format with 64-bit VLQ values and references to
+    /// slices of de-duplicated blobs
+    V3,
+}
+
+impl Default for Format {
+    fn default() -> Self {
+        Format::V2
+    }
+}
+
+impl Format {
+    /// Maximum length of VLQ values in bytes.
+    fn vlq_len(self) -> usize {
+        match self {
+            Format::V2 => 4,
+            Format::V3 => MAX_VLQ_LEN,
+        }
+    }
 }
 
 /// `blobby` error type
@@ -71,72 +149,220 @@ pub enum Error {
     UnexpectedEnd,
     /// Not enough elements for `BlobNIterator`
     NotEnoughElements,
+    /// Unsupported format flags
+    UnsupportedFlags,
+    /// Data section is compressed and has to be decompressed first
+    Compressed,
+    /// Invalid compressed data section
+    InvalidCompressedData,
+    /// Checksum of the data does not match the stored value
+    InvalidChecksum,
+    /// Invalid schema section
+    InvalidSchema,
+    /// Record field is not present in the schema
+    MissingField,
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            Error::InvalidVlq => "invalid VLQ number",
+            Error::InvalidIndex => "invalid de-duplicated blob index",
+            Error::UnexpectedEnd => "unexpected end of data",
+            Error::NotEnoughElements => "not enough elements",
+            Error::UnsupportedFlags => "unsupported format flags",
+            Error::Compressed => "data section is compressed",
+            Error::InvalidCompressedData => "invalid compressed data section",
+            Error::InvalidChecksum => "checksum mismatch",
+            Error::InvalidSchema => "invalid schema section",
+            Error::MissingField => "record field is not present in the schema",
+        })
+    }
 }
 
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
 const NEXT_MASK: u8 = 0b1000_0000;
 const VAL_MASK: u8 = 0b0111_1111;
+/// Maximum length of VLQ values (version 3 of the format).
+const MAX_VLQ_LEN: usize = 10;
+/// Magic bytes of the version 3 format.
+const V3_MAGIC: &[u8; 8] = b"\xff\xff\xff\xffblb3";
+/// Format flag marking DEFLATE-compressed data section.
+const FLAG_DEFLATE: usize = 0b1;
+/// Format flag marking data followed by a checksum footer.
+const FLAG_CHECKSUM: usize = 0b10;
+/// Format flag marking data with the schema section.
+const FLAG_SCHEMA: usize = 0b100;
+/// Mask of format flags known to this crate.
+const KNOWN_FLAGS: usize = FLAG_DEFLATE | FLAG_CHECKSUM | FLAG_SCHEMA;
+/// Length of the checksum footer.
+const CHECKSUM_LEN: usize = 4;
+
+mod crc32;
 
-/// Read a git-flavoured VLQ value from `&data[*pos..]`.
+#[cfg(feature = "deflate")]
+mod deflate;
+#[cfg(feature = "deflate")]
+pub use deflate::decompress;
+mod ops;
+pub use ops::{diff, merge, select, RecordDiff};
+#[cfg(feature = "serde")]
+mod list;
+#[cfg(feature = "serde")]
+pub use list::BlobList;
+#[cfg(feature = "mmap")]
+mod mmap;
+#[cfg(feature = "mmap")]
+pub use mmap::BlobFile;
+#[cfg(feature = "std")]
+mod stream;
+#[cfg(feature = "std")]
+pub use stream::BlobStream;
+
+/// Read a git-flavoured VLQ value of up to `max_len` bytes from `&data[*pos..]`.
 /// Increments `pos` to a number of read bytes.
 ///
-/// This function returns `None` if buffer does not contain enough bytes
-/// or if VLQ is bigger than 4 bytes.
+/// This function returns an error if buffer does not contain enough bytes,
+/// if VLQ is bigger than `max_len` bytes, or if the value does not fit
+/// into `usize`.
 ///
 /// See the test submodule for example values.
-fn read_vlq(data: &[u8], pos: &mut usize) -> Result<usize, Error> {
-    let b = data.get(*pos).ok_or(Error::UnexpectedEnd)?;
-    *pos += 1;
-    let mut next = b & NEXT_MASK;
-    let mut val = (b & VAL_MASK) as usize;
-
-    macro_rules! step {
-        () => {
-            if next == 0 {
-                return Ok(val);
-            }
-            let b = data.get(*pos).ok_or(Error::UnexpectedEnd)?;
-            *pos += 1;
-            next = b & NEXT_MASK;
-            let t = (b & VAL_MASK) as usize;
-            val = ((val + 1) << 7) + t;
+fn read_vlq_n(data: &[u8], pos: &mut usize, max_len: usize) -> Result<usize, Error> {
+    let mut val = 0usize;
+    for i in 0..max_len {
+        let b = data.get(*pos).ok_or(Error::UnexpectedEnd)?;
+        *pos += 1;
+        let t = (b & VAL_MASK) as usize;
+        val = if i == 0 {
+            t
+        } else {
+            val.checked_add(1)
+                .and_then(|v| v.checked_mul(1 << 7))
+                .and_then(|v| v.checked_add(t))
+                .ok_or(Error::InvalidVlq)?
         };
+        if b & NEXT_MASK == 0 {
+            return Ok(val);
+        }
     }
+    Err(Error::InvalidVlq)
+}
 
-    step!();
-    step!();
-    step!();
+/// Write a git-flavoured VLQ value into `buf`.
+///
+/// Returns the slice within `buf` that holds the value.
+fn encode_vlq(mut val: usize, buf: &mut [u8; MAX_VLQ_LEN]) -> &[u8] {
+    let mut n = MAX_VLQ_LEN - 1;
+    buf[n] = (val & (VAL_MASK as usize)) as u8;
+    val >>= 7;
+    while val != 0 {
+        val -= 1;
+        n -= 1;
+        buf[n] = NEXT_MASK | (val & (VAL_MASK as usize)) as u8;
+        val >>= 7;
+    }
+    &buf[n..]
+}
 
-    if next != 0 {
-        return Err(Error::InvalidVlq);
+/// Append VLQ encoding of `val` to `out_buf`.
+///
+/// # Panics
+/// If `val` can not be represented in the given format.
+fn push_vlq(out_buf: &mut Vec<u8>, val: usize, format: Format) {
+    let mut buf = [0u8; MAX_VLQ_LEN];
+    let v = encode_vlq(val, &mut buf);
+    if v.len() > format.vlq_len() {
+        panic!("integer is too big");
     }
+    out_buf.extend_from_slice(v);
+}
 
-    Ok(val)
+/// Length of VLQ encoding of `val`.
+fn vlq_len(val: usize) -> usize {
+    encode_vlq(val, &mut [0u8; MAX_VLQ_LEN]).len()
 }
 
-/// Write a git-flavoured VLQ value into `buf`.
-///
-/// Returns the slice within `buf` that holds the value.
-fn encode_vlq(mut val: usize, buf: &mut [u8; 4]) -> &[u8] {
-    macro_rules! step {
-        ($n:expr) => {
-            buf[$n] = if $n == 3 {
-                (val & (VAL_MASK as usize)) as u8
-            } else {
-                val -= 1;
-                NEXT_MASK | (val & (VAL_MASK as usize)) as u8
-            };
-            val >>= 7;
-            if val == 0 {
-                return &buf[$n..];
-            }
-        };
+/// Parsed header of the encoded data.
+struct Header<'a> {
+    format: Format,
+    flags: usize,
+    /// Raw schema and index sections
+    #[cfg_attr(not(feature = "deflate"), allow(dead_code))]
+    index: &'a [u8],
+    /// Field names stored in the schema section
+    fields: Option<Vec<&'a str>>,
+    /// De-duplicated blobs
+    dedup: Vec<&'a [u8]>,
+    /// Data section following the index
+    data: &'a [u8],
+}
+
+fn parse_header(data: &[u8]) -> Result<Header<'_>, Error> {
+    let (format, mut pos) = if data.starts_with(V3_MAGIC) {
+        (Format::V3, V3_MAGIC.len())
+    } else {
+        (Format::V2, 0)
+    };
+    let vlq_len = format.vlq_len();
+    let flags = match format {
+        Format::V2 => 0,
+        Format::V3 => read_vlq_n(data, &mut pos, vlq_len)?,
+    };
+    if flags & !KNOWN_FLAGS != 0 {
+        return Err(Error::UnsupportedFlags);
     }
+    let data = if flags & FLAG_CHECKSUM != 0 {
+        let len = data
+            .len()
+            .checked_sub(CHECKSUM_LEN)
+            .filter(|&len| len >= pos)
+            .ok_or(Error::UnexpectedEnd)?;
+        let (body, footer) = data.split_at(len);
+        let expected = u32::from_le_bytes([footer[0], footer[1], footer[2], footer[3]]);
+        if crc32::crc32(body) != expected {
+            return Err(Error::InvalidChecksum);
+        }
+        body
+    } else {
+        data
+    };
 
-    step!(3);
-    step!(2);
-    step!(1);
-    step!(0);
-    panic!("integer is too big")
+    let index_start = pos;
+    let fields = if flags & FLAG_SCHEMA != 0 {
+        let k = read_vlq_n(data, &mut pos, vlq_len)?;
+        if k == 0 {
+            return Err(Error::InvalidSchema);
+        }
+        let mut fields = Vec::new();
+        for _ in 0..k {
+            let m = read_vlq_n(data, &mut pos, vlq_len)?;
+            let end = pos.checked_add(m).ok_or(Error::UnexpectedEnd)?;
+            let name = data.get(pos..end).ok_or(Error::UnexpectedEnd)?;
+            fields.push(core::str::from_utf8(name).map_err(|_| Error::InvalidSchema)?);
+            pos = end;
+        }
+        Some(fields)
+    } else {
+        None
+    };
+    let dedup_n = read_vlq_n(data, &mut pos, vlq_len)?;
+    let mut dedup: Vec<&[u8]> = Vec::new();
+    for _ in 0..dedup_n {
+        let m = read_vlq_n(data, &mut pos, vlq_len)?;
+        let end = pos.checked_add(m).ok_or(Error::UnexpectedEnd)?;
+        dedup.push(data.get(pos..end).ok_or(Error::UnexpectedEnd)?);
+        pos = end;
+    }
+    Ok(Header {
+        format,
+        flags,
+        index: &data[index_start..pos],
+        fields,
+        dedup,
+        data: &data[pos..],
+    })
 }
 
 /// Encode the given collection of binary blobs in .blb format into `writer`.
@@ -157,13 +383,32 @@ where
     &'a I: IntoIterator<Item = &'a T>,
     T: AsRef<[u8]>,
 {
+    let blobs: Vec<&[u8]> = blobs.into_iter().map(|v| v.as_ref()).collect();
+    let mut out_buf = Vec::new();
+    let idx_len = encode_into(&blobs, &mut out_buf, Format::V2, None, false, None);
+    (out_buf, idx_len)
+}
+
+/// Append encoded `blobs` to `out_buf`, returning the number of index entries.
+///
+/// Data section is compressed if compression level is specified,
+/// checksum footer is appended if `checksum` is `true` and schema section
+/// is written if `fields` are specified (version 3 format only).
+fn encode_into(
+    blobs: &[&[u8]],
+    out_buf: &mut Vec<u8>,
+    format: Format,
+    compression: Option<u8>,
+    checksum: bool,
+    fields: Option<&[String]>,
+) -> usize {
+    let start = out_buf.len();
     let mut idx_map = BTreeMap::new();
     blobs
-        .into_iter()
-        .map(|v| v.as_ref())
+        .iter()
         .filter(|blob| !blob.is_empty())
-        .for_each(|blob| {
-            let v = idx_map.entry(blob.as_ref()).or_insert(0);
+        .for_each(|&blob| {
+            let v = idx_map.entry(blob).or_insert(0);
             *v += 1;
         });
 
@@ -185,62 +430,315 @@ where
 
     let rev_idx: BTreeMap<&[u8], usize> = idx.iter().enumerate().map(|(i, &e)| (e, i)).collect();
 
-    let mut out_buf = Vec::new();
-    let mut buf = [0u8; 4];
-    out_buf.extend_from_slice(encode_vlq(idx.len(), &mut buf));
+    if format == Format::V3 {
+        let mut flags = 0;
+        if compression.is_some() {
+            flags |= FLAG_DEFLATE;
+        }
+        if checksum {
+            flags |= FLAG_CHECKSUM;
+        }
+        if fields.is_some() {
+            flags |= FLAG_SCHEMA;
+        }
+        out_buf.extend_from_slice(V3_MAGIC);
+        push_vlq(out_buf, flags, format);
+        if let Some(fields) = fields {
+            assert!(
+                !fields.is_empty() && blobs.len() % fields.len() == 0,
+                "number of blobs must be a multiple of the number of fields"
+            );
+            push_vlq(out_buf, fields.len(), format);
+            for f in fields {
+                push_vlq(out_buf, f.len(), format);
+                out_buf.extend_from_slice(f.as_bytes());
+            }
+        }
+    } else {
+        assert!(
+            compression.is_none(),
+            "compression requires version 3 format"
+        );
+        assert!(!checksum, "checksum requires version 3 format");
+        assert!(fields.is_none(), "schema requires version 3 format");
+    }
+    push_vlq(out_buf, idx.len(), format);
     for e in idx {
-        out_buf.extend_from_slice(encode_vlq(e.len(), &mut buf));
+        push_vlq(out_buf, e.len(), format);
         out_buf.extend_from_slice(e);
     }
+    let data_start = out_buf.len();
+
+    for &blob in blobs {
+        if let Some(&dup_pos) = rev_idx.get(blob) {
+            let n = match format {
+                Format::V2 => (dup_pos << 1) + 1usize,
+                Format::V3 => (dup_pos << 2) | 0b01,
+            };
+            push_vlq(out_buf, n, format);
+            continue;
+        }
+        if format == Format::V3 && !blob.is_empty() {
+            // reference blob as a prefix of a de-duplicated entry if it's
+            // shorter than storing it explicitly
+            if let Some((entry, &dup_pos)) = rev_idx.range(blob..).next() {
+                let n = (dup_pos << 2) | 0b11;
+                let ref_len = vlq_len(n) + vlq_len(0) + vlq_len(blob.len());
+                if entry.starts_with(blob) && ref_len < vlq_len(blob.len() << 1) + blob.len() {
+                    push_vlq(out_buf, n, format);
+                    push_vlq(out_buf, 0, format);
+                    push_vlq(out_buf, blob.len(), format);
+                    continue;
+                }
+            }
+        }
+        push_vlq(out_buf, blob.len() << 1, format);
+        out_buf.extend_from_slice(blob);
+    }
+
+    if let Some(_level) = compression {
+        let data = out_buf.split_off(data_start);
+        push_vlq(out_buf, data.len(), format);
+        #[cfg(feature = "deflate")]
+        out_buf.extend_from_slice(&deflate::compress(&data, _level));
+        #[cfg(not(feature = "deflate"))]
+        unreachable!("compression requires the `deflate` feature");
+    }
+    if checksum {
+        let crc = crc32::crc32(&out_buf[start..]);
+        out_buf.extend_from_slice(&crc.to_le_bytes());
+    }
+
+    idx_len
+}
+
+/// Writer of binary blobs in the .blb format.
+///
+/// Blobs are accumulated in the writer and encoded at once, since
+/// de-duplication requires knowledge of all stored blobs. Blobs occurring
+/// more than once are stored in the index and referenced by the entries.
+/// In the version 3 format blobs which are prefixes of de-duplicated blobs
+/// are stored as references as well.
+///
+/// By default the version 2 format is used, use [`BlobWriter::with_format`]
+/// to select a different version.
+///
+/// # Examples
+/// ```
+/// let mut w = blobby::BlobWriter::new();
+/// w.push(b"hello");
+/// w.push(b" ");
+/// w.push(b"");
+/// w.push(b"hello");
+/// let buf = w.encode();
+/// assert_eq!(buf, b"\x01\x05hello\x01\x02 \x00\x01");
+///
+/// let blobs: Vec<_> = blobby::BlobIterator::new(&buf).unwrap().collect();
+/// assert_eq!(blobs, [Ok(&b"hello"[..]), Ok(b" "), Ok(b""), Ok(b"hello")]);
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct BlobWriter {
+    blobs: Vec<Vec<u8>>,
+    format: Format,
+    compression: Option<u8>,
+    checksum: bool,
+    fields: Option<Vec<String>>,
+}
+
+impl BlobWriter {
+    /// Create new empty `BlobWriter` using the version 2 format.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create new empty `BlobWriter` using the given format.
+    pub fn with_format(format: Format) -> Self {
+        Self {
+            blobs: Vec::new(),
+            format,
+            compression: None,
+            checksum: false,
+            fields: None,
+        }
+    }
 
-    for blob in blobs.into_iter().map(|v| v.as_ref()) {
-        if let Some(dup_pos) = rev_idx.get(blob) {
-            let n = (dup_pos << 1) + 1usize;
-            out_buf.extend_from_slice(encode_vlq(n, &mut buf));
+    /// Format used by the writer.
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    /// Enable DEFLATE compression of the data section with the given
+    /// compression level (from 0 to 10), or disable it by passing `None`.
+    ///
+    /// The index is left uncompressed. Compression requires the version 3
+    /// format, so enabling it switches the writer to this format.
+    /// Compressed data has to be processed with [`decompress`] before
+    /// it can be read.
+    #[cfg(feature = "deflate")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "deflate")))]
+    pub fn set_compression(&mut self, level: Option<u8>) {
+        if level.is_some() {
+            self.format = Format::V3;
+        }
+        self.compression = level;
+    }
+
+    /// Enable or disable checksum footer.
+    ///
+    /// If enabled, readers verify integrity of the data before parsing it and
+    /// return [`Error::InvalidChecksum`] on a mismatch. Checksum requires
+    /// the version 3 format, so enabling it switches the writer to this format.
+    pub fn set_checksum(&mut self, enabled: bool) {
+        if enabled {
+            self.format = Format::V3;
+        }
+        self.checksum = enabled;
+    }
+
+    /// Set names of the record fields stored in the schema section.
+    ///
+    /// Stored blobs are grouped into records with one blob per field, which
+    /// can be accessed by field names using [`BlobReader::records`]. Passing
+    /// an empty slice disables the schema section. Schema requires the version
+    /// 3 format, so setting it switches the writer to this format.
+    pub fn set_fields(&mut self, fields: &[&str]) {
+        if fields.is_empty() {
+            self.fields = None;
         } else {
-            let n = blob.len() << 1;
-            out_buf.extend_from_slice(encode_vlq(n, &mut buf));
-            out_buf.extend_from_slice(blob);
+            self.format = Format::V3;
+            self.fields = Some(fields.iter().map(|&f| f.into()).collect());
         }
     }
 
-    (out_buf, idx_len)
+    /// Names of the record fields stored in the schema section.
+    pub fn fields(&self) -> Option<&[String]> {
+        self.fields.as_deref()
+    }
+
+    /// Add `blob` to the end of the stored sequence.
+    pub fn push(&mut self, blob: impl AsRef<[u8]>) {
+        self.blobs.push(blob.as_ref().to_vec());
+    }
+
+    /// Number of stored blobs.
+    pub fn len(&self) -> usize {
+        self.blobs.len()
+    }
+
+    /// Returns `true` if no blobs were stored.
+    pub fn is_empty(&self) -> bool {
+        self.blobs.is_empty()
+    }
+
+    /// Encode stored blobs.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        self.encode_into(&mut buf);
+        buf
+    }
+
+    /// Append encoded blobs to `buf`.
+    ///
+    /// Returns number of blobs included in the de-duplication index.
+    ///
+    /// # Panics
+    /// If a blob is too big for the version 2 format or if the number of
+    /// blobs is not a multiple of the number of fields.
+    pub fn encode_into(&self, buf: &mut Vec<u8>) -> usize {
+        let blobs: Vec<&[u8]> = self.blobs.iter().map(|v| v.as_slice()).collect();
+        encode_into(
+            &blobs,
+            buf,
+            self.format,
+            self.compression,
+            self.checksum,
+            self.fields.as_deref(),
+        )
+    }
+
+    /// Write encoded blobs into `writer`.
+    ///
+    /// Returns number of blobs included in the de-duplication index.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn write_to<W: std::io::Write>(&self, mut writer: W) -> std::io::Result<usize> {
+        let mut buf = Vec::new();
+        let idx_len = self.encode_into(&mut buf);
+        writer.write_all(&buf)?;
+        Ok(idx_len)
+    }
+}
+
+impl<T: AsRef<[u8]>> Extend<T> for BlobWriter {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for blob in iter {
+            self.push(blob);
+        }
+    }
+}
+
+impl<T: AsRef<[u8]>> core::iter::FromIterator<T> for BlobWriter {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut w = Self::new();
+        w.extend(iter);
+        w
+    }
 }
 
 impl<'a> BlobIterator<'a> {
     /// Create new `BlobIterator` for given `data`.
+    ///
+    /// Returns [`Error::Compressed`] if the data section is compressed.
     pub fn new(data: &'a [u8]) -> Result<Self, Error> {
-        let mut pos = 0;
-        let dedup_n = read_vlq(data, &mut pos)?;
-
-        let mut dedup: Vec<&[u8]> = vec![&[]; dedup_n];
-        for entry in dedup.iter_mut() {
-            let m = read_vlq(data, &mut pos).unwrap();
-            *entry = &data[pos..pos + m];
-            pos += m;
+        let header = parse_header(data)?;
+        if header.flags & FLAG_DEFLATE != 0 {
+            return Err(Error::Compressed);
         }
         Ok(BlobIterator {
-            data: &data[pos..],
-            dedup: dedup.into_boxed_slice(),
+            data: header.data,
+            dedup: header.dedup.into_boxed_slice(),
             pos: 0,
+            format: header.format,
+            fields: header.fields.map(Vec::into_boxed_slice),
         })
     }
 
+    /// Format of the iterated data.
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    /// Names of the record fields stored in the schema section.
+    pub fn fields(&self) -> Option<&[&'a str]> {
+        self.fields.as_deref()
+    }
+
     fn read(&mut self) -> Result<&'a [u8], Error> {
-        let val = read_vlq(self.data, &mut self.pos).unwrap();
+        let vlq_len = self.format.vlq_len();
+        let val = read_vlq_n(self.data, &mut self.pos, vlq_len)?;
         // the least significant bit is used as a flag
         let is_ref = (val & 1) != 0;
-        let val = val >> 1;
-        if is_ref {
-            if val >= self.dedup.len() {
-                return Err(Error::InvalidIndex);
-            }
-            Ok(self.dedup[val])
-        } else {
+        if !is_ref {
             let s = self.pos;
-            self.pos += val;
-            Ok(self.data.get(s..self.pos).ok_or(Error::UnexpectedEnd)?)
+            self.pos = s.checked_add(val >> 1).ok_or(Error::UnexpectedEnd)?;
+            return self.data.get(s..self.pos).ok_or(Error::UnexpectedEnd);
         }
+        match self.format {
+            Format::V2 => self.dedup_entry(val >> 1),
+            // the second least significant bit marks references to slices
+            Format::V3 if val & 0b10 == 0 => self.dedup_entry(val >> 2),
+            Format::V3 => {
+                let entry = self.dedup_entry(val >> 2)?;
+                let offset = read_vlq_n(self.data, &mut self.pos, vlq_len)?;
+                let len = read_vlq_n(self.data, &mut self.pos, vlq_len)?;
+                let end = offset.checked_add(len).ok_or(Error::InvalidIndex)?;
+                entry.get(offset..end).ok_or(Error::InvalidIndex)
+            }
+        }
+    }
+
+    fn dedup_entry(&self, idx: usize) -> Result<&'a [u8], Error> {
+        self.dedup.get(idx).copied().ok_or(Error::InvalidIndex)
     }
 
     fn error_block(&mut self) {
@@ -264,52 +762,305 @@ impl<'a> Iterator for BlobIterator<'a> {
     }
 }
 
-// TODO: use const generics on stabilization
-// docs are not generated due to https://github.com/rust-lang/rust/issues/52607
-macro_rules! new_iter {
-    ($name:ident, $n:expr) => {
-        pub struct $name<'a> {
-            inner: BlobIterator<'a>,
+/// Random-access reader of binary blobs.
+///
+/// In contrast to [`BlobIterator`], the data is parsed and validated once on
+/// construction, after which blobs can be accessed by their position in
+/// constant time.
+///
+/// # Examples
+/// ```
+/// let buf = b"\x02\x05hello\x06world!\x01\x02 \x00\x03\x06:::\x03\x01\x00";
+/// let r = blobby::BlobReader::new(buf).unwrap();
+/// assert_eq!(r.len(), 8);
+/// assert_eq!(r.get(3), Some(&b"world!"[..]));
+/// assert_eq!(r.get(8), None);
+/// assert_eq!(r.record(1, 4), Some(&[&b":::"[..], b"world!", b"hello", b""][..]));
+/// assert_eq!(r.record(2, 4), None);
+/// ```
+#[derive(Clone, Debug)]
+pub struct BlobReader<'a> {
+    blobs: Box<[&'a [u8]]>,
+    format: Format,
+    fields: Option<Box<[&'a str]>>,
+}
+
+impl<'a> BlobReader<'a> {
+    /// Create new `BlobReader` for given `data`.
+    ///
+    /// Returns [`Error::NotEnoughElements`] if the number of stored blobs
+    /// is not a multiple of the number of fields stored in the schema section.
+    pub fn new(data: &'a [u8]) -> Result<Self, Error> {
+        let mut iter = BlobIterator::new(data)?;
+        let format = iter.format();
+        let fields = iter.fields.take();
+        let blobs: Result<Vec<&[u8]>, Error> = iter.collect();
+        let blobs = blobs?.into_boxed_slice();
+        if let Some(f) = &fields {
+            if blobs.len() % f.len() != 0 {
+                return Err(Error::NotEnoughElements);
+            }
         }
+        Ok(Self {
+            blobs,
+            format,
+            fields,
+        })
+    }
+
+    /// Format of the read data.
+    pub fn format(&self) -> Format {
+        self.format
+    }
 
-        impl<'a> $name<'a> {
-            pub fn new(data: &'a [u8]) -> Result<Self, Error> {
-                BlobIterator::new(data).map(|inner| Self { inner })
+    /// Names of the record fields stored in the schema section.
+    pub fn fields(&self) -> Option<&[&'a str]> {
+        self.fields.as_deref()
+    }
+
+    /// Get stored records of type `T`.
+    ///
+    /// If the data contains the schema section, record fields are looked up
+    /// by their names, so records stay valid regardless of the order in which
+    /// fields were stored, and [`Error::MissingField`] is returned if one of
+    /// the fields in [`Record::FIELDS`] is not present in the schema.
+    /// Otherwise each record consists of `T::FIELDS.len()` consecutive blobs
+    /// and [`Error::NotEnoughElements`] is returned if the number of stored
+    /// blobs is not a multiple of it.
+    ///
+    /// # Examples
+    /// ```
+    /// blobby::record! {
+    ///     /// Test vector of a hash function
+    ///     struct HashVector { msg, digest }
+    /// }
+    ///
+    /// let mut w = blobby::BlobWriter::new();
+    /// w.set_fields(&["digest", "msg"]);
+    /// w.extend(["d1", "m1", "d2", "m2"].iter());
+    /// let buf = w.encode();
+    ///
+    /// let r = blobby::BlobReader::new(&buf).unwrap();
+    /// let vectors: Vec<HashVector> = r.records().unwrap();
+    /// assert_eq!(vectors[1].msg, b"m2");
+    /// assert_eq!(vectors[1].digest, b"d2");
+    /// ```
+    pub fn records<T: Record<'a>>(&self) -> Result<Vec<T>, Error> {
+        let (n, positions) = match &self.fields {
+            Some(fields) => {
+                let positions = T::FIELDS
+                    .iter()
+                    .map(|name| fields.iter().position(|f| f == name))
+                    .collect::<Option<Vec<usize>>>()
+                    .ok_or(Error::MissingField)?;
+                (fields.len(), positions)
             }
+            None => (T::FIELDS.len(), (0..T::FIELDS.len()).collect()),
+        };
+        if n == 0 {
+            return Ok(Vec::new());
         }
+        if self.blobs.len() % n != 0 {
+            return Err(Error::NotEnoughElements);
+        }
+        let mut buf = Vec::with_capacity(positions.len());
+        let records = self
+            .blobs
+            .chunks(n)
+            .map(|record| {
+                buf.clear();
+                buf.extend(positions.iter().map(|&i| record[i]));
+                T::from_fields(&buf)
+            })
+            .collect();
+        Ok(records)
+    }
 
-        impl<'a> Iterator for $name<'a> {
-            type Item = Result<[&'a [u8]; $n], Error>;
+    /// Number of stored blobs.
+    pub fn len(&self) -> usize {
+        self.blobs.len()
+    }
 
-            fn next(&mut self) -> Option<Self::Item> {
-                let mut res: [&[u8]; $n] = Default::default();
+    /// Returns `true` if no blobs are stored.
+    pub fn is_empty(&self) -> bool {
+        self.blobs.is_empty()
+    }
 
-                for (i, v) in res.iter_mut().enumerate() {
-                    *v = match self.inner.next() {
-                        Some(Ok(val)) => val,
-                        Some(Err(e)) => return Some(Err(e)),
-                        None if i == 0 => return None,
-                        None => {
-                            self.inner.error_block();
-                            return Some(Err(Error::NotEnoughElements));
-                        }
-                    };
+    /// Get blob at position `i`.
+    pub fn get(&self, i: usize) -> Option<&'a [u8]> {
+        self.blobs.get(i).copied()
+    }
+
+    /// Get `i`-th record consisting of `n` consecutive blobs, i.e. blobs
+    /// which would be returned by the `i`-th call of `next` on
+    /// [`BlobNIterator`].
+    ///
+    /// Returns `None` if `n` is equal to zero or the record is not complete.
+    pub fn record(&self, i: usize, n: usize) -> Option<&[&'a [u8]]> {
+        if n == 0 {
+            return None;
+        }
+        let start = i.checked_mul(n)?;
+        self.blobs.get(start..start.checked_add(n)?)
+    }
+
+    /// Number of complete records consisting of `n` blobs.
+    ///
+    /// # Panics
+    /// If `n` is equal to zero.
+    pub fn records_len(&self, n: usize) -> usize {
+        self.blobs.len() / n
+    }
+
+    /// Get all stored blobs.
+    pub fn as_slice(&self) -> &[&'a [u8]] {
+        &self.blobs
+    }
+
+    /// Iterate over stored blobs.
+    pub fn iter(&self) -> core::iter::Copied<core::slice::Iter<'_, &'a [u8]>> {
+        self.blobs.iter().copied()
+    }
+}
+
+/// Record consisting of named binary blobs.
+///
+/// This trait is usually implemented using the [`record!`] macro.
+pub trait Record<'a>: Sized {
+    /// Names of the record fields
+    const FIELDS: &'static [&'static str];
+
+    /// Create record from field values ordered according to [`Record::FIELDS`].
+    ///
+    /// # Panics
+    /// If length of `fields` is not equal to length of [`Record::FIELDS`].
+    fn from_fields(fields: &[&'a [u8]]) -> Self;
+}
+
+/// Define a struct with named binary blob fields implementing [`Record`].
+///
+/// Names of the struct fields are used as names of the record fields.
+/// Field values can be accessed using [`BlobReader::records`].
+///
+/// # Examples
+/// ```
+/// blobby::record! {
+///     /// AEAD test vector
+///     #[derive(Debug)]
+///     pub struct AeadVector { key, nonce, msg, tag }
+/// }
+///
+/// use blobby::Record;
+/// assert_eq!(AeadVector::FIELDS, ["key", "nonce", "msg", "tag"]);
+/// let v = AeadVector::from_fields(&[b"k", b"n", b"m", b"t"]);
+/// assert_eq!(v.nonce, b"n");
+/// ```
+#[macro_export]
+macro_rules! record {
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident {
+            $($(#[$field_attr:meta])* $field:ident),* $(,)?
+        }
+    ) => {
+        $(#[$attr])*
+        $vis struct $name<'a> {
+            $($(#[$field_attr])* pub $field: &'a [u8],)*
+        }
+
+        impl<'a> $crate::Record<'a> for $name<'a> {
+            const FIELDS: &'static [&'static str] = &[$(stringify!($field)),*];
+
+            fn from_fields(fields: &[&'a [u8]]) -> Self {
+                assert_eq!(fields.len(), Self::FIELDS.len(), "invalid number of fields");
+                let mut fields = fields.iter().copied();
+                Self {
+                    $($field: fields.next().unwrap(),)*
                 }
-                Some(Ok(res))
             }
         }
     };
 }
 
-new_iter!(Blob2Iterator, 2);
-new_iter!(Blob3Iterator, 3);
-new_iter!(Blob4Iterator, 4);
-new_iter!(Blob5Iterator, 5);
-new_iter!(Blob6Iterator, 6);
+/// Iterator over records consisting of `N` consecutive binary blobs
+///
+/// Iterator returns [`Error::NotEnoughElements`] if the number of stored
+/// blobs is not a multiple of `N`. For `N` equal to zero iterator does not
+/// yield any records.
+///
+/// # Examples
+/// ```
+/// let buf = b"\x02\x05hello\x06world!\x01\x02 \x00\x03\x06:::\x03\x01\x00";
+/// let mut v = blobby::BlobNIterator::<8>::new(buf).unwrap();
+/// assert_eq!(
+///     v.next(),
+///     Some(Ok([&b"hello"[..], b" ", b"", b"world!", b":::", b"world!", b"hello", b""])),
+/// );
+/// assert_eq!(v.next(), None);
+///
+/// let mut v = blobby::BlobNIterator::<3>::new(buf).unwrap();
+/// assert_eq!(v.next(), Some(Ok([&b"hello"[..], b" ", b""])));
+/// assert_eq!(v.next(), Some(Ok([&b"world!"[..], b":::", b"world!"])));
+/// assert_eq!(v.next(), Some(Err(blobby::Error::NotEnoughElements)));
+/// assert_eq!(v.next(), None);
+/// ```
+pub struct BlobNIterator<'a, const N: usize> {
+    inner: BlobIterator<'a>,
+}
+
+impl<'a, const N: usize> BlobNIterator<'a, N> {
+    /// Create new `BlobNIterator` for given `data`.
+    pub fn new(data: &'a [u8]) -> Result<Self, Error> {
+        BlobIterator::new(data).map(|inner| Self { inner })
+    }
+}
+
+impl<'a, const N: usize> Iterator for BlobNIterator<'a, N> {
+    type Item = Result<[&'a [u8]; N], Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if N == 0 {
+            return None;
+        }
+        let mut res: [&[u8]; N] = [&[]; N];
+
+        for (i, v) in res.iter_mut().enumerate() {
+            *v = match self.inner.next() {
+                Some(Ok(val)) => val,
+                Some(Err(e)) => return Some(Err(e)),
+                None if i == 0 => return None,
+                None => {
+                    self.inner.error_block();
+                    return Some(Err(Error::NotEnoughElements));
+                }
+            };
+        }
+        Some(Ok(res))
+    }
+}
+
+/// Iterator over pairs of binary blobs
+pub type Blob2Iterator<'a> = BlobNIterator<'a, 2>;
+/// Iterator over triples of binary blobs
+pub type Blob3Iterator<'a> = BlobNIterator<'a, 3>;
+/// Iterator over records of 4 binary blobs
+pub type Blob4Iterator<'a> = BlobNIterator<'a, 4>;
+/// Iterator over records of 5 binary blobs
+pub type Blob5Iterator<'a> = BlobNIterator<'a, 5>;
+/// Iterator over records of 6 binary blobs
+pub type Blob6Iterator<'a> = BlobNIterator<'a, 6>;
 
 #[cfg(test)]
 mod tests {
-    use super::{read_vlq, Error, NEXT_MASK, VAL_MASK};
+    use super::{
+        encode_blobs, read_vlq_n, BlobIterator, BlobReader, BlobWriter, Error, Format, MAX_VLQ_LEN,
+        NEXT_MASK, VAL_MASK,
+    };
+    use alloc::vec::Vec;
+
+    fn read_vlq(data: &[u8], pos: &mut usize) -> Result<usize, Error> {
+        read_vlq_n(data, pos, Format::V2.vlq_len())
+    }
 
     fn encode_vlq(mut val: usize, buf: &mut [u8; 4]) -> &[u8] {
         macro_rules! step {
@@ -337,10 +1088,12 @@ mod tests {
     #[test]
     fn encode_decode() {
         let mut buf = [0u8; 4];
+        let mut buf2 = [0u8; MAX_VLQ_LEN];
         for val in 0..=270549119 {
             let res = encode_vlq(val, &mut buf);
             let val_res = read_vlq(res, &mut 0).unwrap();
             assert_eq!(val, val_res);
+            assert_eq!(super::encode_vlq(val, &mut buf2), res);
         }
     }
 
@@ -386,4 +1139,216 @@ mod tests {
         assert_eq!(read_vlq(&examples, &mut pos), Err(Error::InvalidVlq));
         assert_eq!(pos, 25);
     }
+
+    #[test]
+    fn writer_roundtrip() {
+        let blobs: [&[u8]; 8] = [
+            b"hello", b" ", b"", b"world!", b":::", b"world!", b"hello", b"",
+        ];
+        let w: BlobWriter = blobs.iter().collect();
+        assert_eq!(w.len(), 8);
+
+        let buf = w.encode();
+        assert_eq!(buf, encode_blobs(&blobs).0);
+        let res: Vec<&[u8]> = BlobIterator::new(&buf)
+            .unwrap()
+            .map(|v| v.unwrap())
+            .collect();
+        assert_eq!(res, blobs);
+
+        let mut buf2 = b"prefix".to_vec();
+        assert_eq!(w.encode_into(&mut buf2), 2);
+        assert_eq!(&buf2[6..], &buf[..]);
+    }
+
+    #[test]
+    fn reader() {
+        let buf = b"\x02\x05hello\x06world!\x01\x02 \x00\x03\x06:::\x03\x01\x00";
+        let r = BlobReader::new(buf).unwrap();
+        let blobs: Vec<&[u8]> = BlobIterator::new(buf)
+            .unwrap()
+            .map(|v| v.unwrap())
+            .collect();
+        assert_eq!(r.as_slice(), &blobs[..]);
+        assert_eq!(r.iter().collect::<Vec<_>>(), blobs);
+        for (i, &blob) in blobs.iter().enumerate() {
+            assert_eq!(r.get(i), Some(blob));
+        }
+        assert_eq!(r.records_len(3), 2);
+        assert_eq!(r.record(1, 3), Some(&blobs[3..6]));
+        assert_eq!(r.record(2, 3), None);
+        assert_eq!(r.record(0, 0), None);
+        assert_eq!(r.record(core::usize::MAX, 2), None);
+
+        assert_eq!(
+            BlobReader::new(b"\x01\x05hello\x03").unwrap_err(),
+            Error::InvalidIndex
+        );
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn vlq_64bit() {
+        let mut buf = [0u8; MAX_VLQ_LEN];
+        let vals = [
+            270549120,
+            1 << 32,
+            (1 << 49) - 1,
+            1 << 56,
+            core::usize::MAX - 1,
+            core::usize::MAX,
+        ];
+        for &val in vals.iter() {
+            let res = super::encode_vlq(val, &mut buf);
+            assert!(res.len() > 4);
+            assert_eq!(read_vlq_n(res, &mut 0, MAX_VLQ_LEN), Ok(val));
+            assert_eq!(read_vlq(res, &mut 0), Err(Error::InvalidVlq));
+        }
+        assert_eq!(super::encode_vlq(core::usize::MAX, &mut buf).len(), 10);
+
+        // overflowing values
+        let data = [0xff; 10];
+        assert_eq!(read_vlq_n(&data, &mut 0, 10), Err(Error::InvalidVlq));
+        let data = [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f];
+        assert_eq!(read_vlq_n(&data, &mut 0, 10), Err(Error::InvalidVlq));
+    }
+
+    #[test]
+    fn v3_format() {
+        let buf = b"\xff\xff\xff\xffblb3\x00\x02\x05hello\x06world!\x01\x02 \x00\x05\x06:::\x03\x01\x03\x07\x00\x03\x03\x00\x03";
+        let res: Vec<&[u8]> = BlobIterator::new(buf)
+            .unwrap()
+            .map(|v| v.unwrap())
+            .collect();
+        let expected: [&[u8]; 8] = [
+            b"hello", b" ", b"", b"world!", b":::", b"ell", b"wor", b"hel",
+        ];
+        assert_eq!(res, expected);
+        assert_eq!(BlobIterator::new(buf).unwrap().format(), Format::V3);
+        assert_eq!(BlobIterator::new(b"\x00").unwrap().format(), Format::V2);
+
+        // out of range slice
+        let mut v = BlobIterator::new(b"\xff\xff\xff\xffblb3\x00\x01\x02ab\x03\x01\x02").unwrap();
+        assert_eq!(v.next(), Some(Err(Error::InvalidIndex)));
+        assert_eq!(v.next(), None);
+
+        // unknown flags
+        assert_eq!(
+            BlobIterator::new(b"\xff\xff\xff\xffblb3\x08\x00").err(),
+            Some(Error::UnsupportedFlags)
+        );
+        // compressed data section
+        assert_eq!(
+            BlobIterator::new(b"\xff\xff\xff\xffblb3\x01\x00\x00").err(),
+            Some(Error::Compressed)
+        );
+    }
+
+    #[test]
+    fn v3_writer_roundtrip() {
+        let blobs: [&[u8]; 9] = [
+            b"hello world!",
+            b"hello",
+            b"",
+            b"hello world!",
+            b"h",
+            b"world!",
+            b"hello world!",
+            b"world!",
+            b"hello world!!",
+        ];
+        let mut w = BlobWriter::with_format(Format::V3);
+        w.extend(blobs.iter());
+        let buf = w.encode();
+        assert!(buf.starts_with(b"\xff\xff\xff\xffblb3\x00\x02"));
+        // "hello" is stored as a reference to a prefix of "hello world!"
+        assert_eq!(&buf[31..34], b"\x03\x00\x05");
+
+        let res: Vec<&[u8]> = BlobIterator::new(&buf)
+            .unwrap()
+            .map(|v| v.unwrap())
+            .collect();
+        assert_eq!(res, blobs);
+        assert_eq!(BlobReader::new(&buf).unwrap().as_slice(), &blobs[..]);
+    }
+
+    #[test]
+    fn checksum() {
+        let mut w = BlobWriter::new();
+        w.extend(["hello", "", "hello"].iter());
+        w.set_checksum(true);
+        assert_eq!(w.format(), Format::V3);
+        let buf = w.encode();
+        assert_eq!(
+            &buf[..buf.len() - 4],
+            b"\xff\xff\xff\xffblb3\x02\x01\x05hello\x01\x00\x01",
+        );
+        assert_eq!(
+            &buf[buf.len() - 4..],
+            super::crc32::crc32(&buf[..buf.len() - 4]).to_le_bytes()
+        );
+        let res: Vec<&[u8]> = BlobIterator::new(&buf)
+            .unwrap()
+            .map(|v| v.unwrap())
+            .collect();
+        assert_eq!(res, [&b"hello"[..], b"", b"hello"]);
+
+        for i in 0..buf.len() {
+            let mut buf = buf.clone();
+            buf[i] ^= 0x10;
+            assert!(BlobIterator::new(&buf).is_err());
+        }
+        for i in 9..buf.len() {
+            assert!(BlobReader::new(&buf[..i]).is_err());
+        }
+    }
+
+    #[test]
+    fn schema() {
+        crate::record! {
+            struct Vector { msg, tag }
+        }
+
+        let mut w = BlobWriter::new();
+        w.set_fields(&["tag", "msg"]);
+        assert_eq!(w.format(), Format::V3);
+        w.extend(["t1", "m1", "t2", "m2"].iter());
+        let buf = w.encode();
+        assert_eq!(
+            buf,
+            &b"\xff\xff\xff\xffblb3\x04\x02\x03tag\x03msg\x00\x04t1\x04m1\x04t2\x04m2"[..],
+        );
+
+        let r = BlobReader::new(&buf).unwrap();
+        assert_eq!(r.fields(), Some(&["tag", "msg"][..]));
+        assert_eq!(r.len(), 4);
+        let v: Vec<Vector> = r.records().unwrap();
+        assert_eq!(v.len(), 2);
+        assert_eq!((v[0].msg, v[0].tag), (&b"m1"[..], &b"t1"[..]));
+        assert_eq!((v[1].msg, v[1].tag), (&b"m2"[..], &b"t2"[..]));
+
+        // without schema fields are positional
+        let buf2 = ["m1", "t1"].iter().collect::<BlobWriter>().encode();
+        let v: Vec<Vector> = BlobReader::new(&buf2).unwrap().records().unwrap();
+        assert_eq!((v[0].msg, v[0].tag), (&b"m1"[..], &b"t1"[..]));
+
+        w.set_fields(&["msg", "key"]);
+        let buf = w.encode();
+        let r = BlobReader::new(&buf).unwrap();
+        assert_eq!(r.records::<Vector>().err(), Some(Error::MissingField));
+
+        let err = |data: &[u8]| BlobReader::new(data).err();
+        assert_eq!(
+            err(b"\xff\xff\xff\xffblb3\x04\x00\x00"),
+            Some(Error::InvalidSchema)
+        );
+        assert_eq!(
+            err(b"\xff\xff\xff\xffblb3\x04\x01\x01\xff\x00"),
+            Some(Error::InvalidSchema),
+        );
+        assert_eq!(
+            err(b"\xff\xff\xff\xffblb3\x04\x02\x01a\x01b\x00\x00"),
+            Some(Error::NotEnoughElements),
+        );
+    }
 }
@@ -0,0 +1,222 @@
+//! Serde-compatible representation of stored blobs.
+use crate::{BlobIterator, BlobWriter, Error, Format};
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+/// List of binary blobs which can be converted to and from the storage
+/// format and (de)serialized using `serde`.
+///
+/// It allows to inspect and edit stored blobs using human-readable formats,
+/// e.g. JSON. With such formats blobs are represented as hex strings,
+/// while other formats (e.g. CBOR) use byte strings. Format version defaults
+/// to [`Format::V2`] if omitted, fields of the schema section are omitted
+/// if the data does not contain it.
+///
+/// Example of a JSON representation:
+/// ```json
+/// { "format": "v2", "blobs": ["68656c6c6f", "", "776f726c6421"] }
+/// ```
+///
+/// # Examples
+/// ```
+/// let buf = b"\x02\x05hello\x06world!\x01\x02 \x00\x03\x06:::\x03\x01\x00";
+/// let list = blobby::BlobList::from_bytes(buf).unwrap();
+/// assert_eq!(list.format, blobby::Format::V2);
+/// assert_eq!(list.blobs[3], b"world!");
+///
+/// let buf = list.encode();
+/// assert_eq!(blobby::BlobList::from_bytes(&buf).unwrap(), list);
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct BlobList {
+    /// Version of the storage format
+    #[serde(default)]
+    pub format: Format,
+    /// Names of the record fields stored in the schema section
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fields: Option<Vec<String>>,
+    /// Stored blobs
+    #[serde(with = "blobs")]
+    pub blobs: Vec<Vec<u8>>,
+}
+
+impl BlobList {
+    /// Decode blobs stored in `data`.
+    ///
+    /// Compressed data has to be decompressed first.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, Error> {
+        let iter = BlobIterator::new(data)?;
+        let format = iter.format();
+        let fields = iter
+            .fields()
+            .map(|f| f.iter().map(|&name| name.into()).collect());
+        let blobs = iter
+            .map(|blob| blob.map(|v| v.to_vec()))
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            format,
+            fields,
+            blobs,
+        })
+    }
+
+    /// Encode stored blobs.
+    ///
+    /// # Panics
+    /// If a blob is too big to be represented in the selected format, or
+    /// if the number of blobs is not a multiple of the number of fields.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut w = BlobWriter::with_format(self.format);
+        if let Some(fields) = &self.fields {
+            let fields: Vec<&str> = fields.iter().map(|f| f.as_str()).collect();
+            w.set_fields(&fields);
+        }
+        w.extend(self.blobs.iter());
+        w.encode()
+    }
+}
+
+/// (De)serialization of blobs as hex or byte strings.
+mod blobs {
+    use super::*;
+
+    struct Blob<'a>(&'a [u8]);
+
+    impl Serialize for Blob<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            if serializer.is_human_readable() {
+                serializer.serialize_str(&encode_hex(self.0))
+            } else {
+                serializer.serialize_bytes(self.0)
+            }
+        }
+    }
+
+    struct OwnedBlob(Vec<u8>);
+
+    impl<'de> Deserialize<'de> for OwnedBlob {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            if deserializer.is_human_readable() {
+                let s = String::deserialize(deserializer)?;
+                decode_hex(&s)
+                    .map(OwnedBlob)
+                    .ok_or_else(|| de::Error::invalid_value(de::Unexpected::Str(&s), &"hex string"))
+            } else {
+                deserializer
+                    .deserialize_byte_buf(BytesVisitor)
+                    .map(OwnedBlob)
+            }
+        }
+    }
+
+    struct BytesVisitor;
+
+    impl<'de> de::Visitor<'de> for BytesVisitor {
+        type Value = Vec<u8>;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("byte string")
+        }
+
+        fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+            Ok(v.to_vec())
+        }
+
+        fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+            Ok(v)
+        }
+
+        fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut v = Vec::new();
+            while let Some(b) = seq.next_element()? {
+                v.push(b);
+            }
+            Ok(v)
+        }
+    }
+
+    pub(super) fn serialize<S: Serializer>(
+        blobs: &[Vec<u8>],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(blobs.iter().map(|b| Blob(b)))
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Vec<u8>>, D::Error> {
+        let blobs = Vec::<OwnedBlob>::deserialize(deserializer)?;
+        Ok(blobs.into_iter().map(|b| b.0).collect())
+    }
+
+    fn encode_hex(data: &[u8]) -> String {
+        const HEX: &[u8; 16] = b"0123456789abcdef";
+        let mut s = String::with_capacity(2 * data.len());
+        for &b in data {
+            s.push(HEX[(b >> 4) as usize] as char);
+            s.push(HEX[(b & 0xf) as usize] as char);
+        }
+        s
+    }
+
+    fn decode_hex(s: &str) -> Option<Vec<u8>> {
+        fn val(c: u8) -> Option<u8> {
+            match c {
+                b'0'..=b'9' => Some(c - b'0'),
+                b'a'..=b'f' => Some(c - b'a' + 10),
+                b'A'..=b'F' => Some(c - b'A' + 10),
+                _ => None,
+            }
+        }
+        let s = s.as_bytes();
+        if s.len() % 2 != 0 {
+            return None;
+        }
+        s.chunks(2)
+            .map(|c| Some((val(c[0])? << 4) | val(c[1])?))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BlobList;
+    use crate::{Error, Format};
+    use alloc::vec;
+
+    #[test]
+    fn json_roundtrip() {
+        let list = BlobList {
+            format: Format::V3,
+            fields: None,
+            blobs: vec![b"hello".to_vec(), vec![], vec![0x00, 0xff]],
+        };
+        let json = serde_json::to_string(&list).unwrap();
+        assert_eq!(json, r#"{"format":"v3","blobs":["68656c6c6f","","00ff"]}"#);
+        assert_eq!(serde_json::from_str::<BlobList>(&json).unwrap(), list);
+
+        let list2: BlobList = serde_json::from_str(r#"{"blobs":["00FF"]}"#).unwrap();
+        assert_eq!(list2.format, Format::V2);
+        assert_eq!(list2.blobs, [[0x00, 0xff]]);
+
+        let json = r#"{"format":"v3","fields":["msg"],"blobs":["00"]}"#;
+        let list3: BlobList = serde_json::from_str(json).unwrap();
+        assert_eq!(list3.fields, Some(vec!["msg".into()]));
+        assert_eq!(serde_json::to_string(&list3).unwrap(), json);
+        assert_eq!(BlobList::from_bytes(&list3.encode()).unwrap(), list3);
+
+        assert!(serde_json::from_str::<BlobList>(r#"{"blobs":["0"]}"#).is_err());
+        assert!(serde_json::from_str::<BlobList>(r#"{"blobs":["zz"]}"#).is_err());
+    }
+
+    #[test]
+    fn bytes_roundtrip() {
+        let buf = b"\x02\x05hello\x06world!\x01\x02 \x00\x03\x06:::\x03\x01\x00";
+        let list = BlobList::from_bytes(buf).unwrap();
+        assert_eq!(list.blobs.len(), 8);
+        assert_eq!(BlobList::from_bytes(&list.encode()).unwrap(), list);
+        assert_eq!(BlobList::from_bytes(b"\x01"), Err(Error::UnexpectedEnd));
+    }
+}
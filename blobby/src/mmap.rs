@@ -0,0 +1,95 @@
+//! Memory-mapped blob files.
+use crate::{BlobIterator, BlobReader, Error, Record};
+use alloc::vec::Vec;
+use std::{fs::File, io, path::Path};
+
+/// Memory-mapped file containing encoded blobs.
+///
+/// Readers created by this type borrow blobs directly from the mapping,
+/// so the file contents are never copied into memory explicitly.
+///
+/// The file must not be modified while it's mapped. Modification of
+/// the mapped file by other processes results in undefined behavior.
+///
+/// # Examples
+/// ```no_run
+/// let file = blobby::BlobFile::open("tests/data/vectors.blb").unwrap();
+/// for blob in file.iter().unwrap() {
+///     let blob: &[u8] = blob.unwrap();
+///     println!("{:?}", blob);
+/// }
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "mmap")))]
+#[derive(Debug)]
+pub struct BlobFile {
+    mmap: memmap2::Mmap,
+}
+
+impl BlobFile {
+    /// Open and memory-map the file at `path`.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = File::open(path)?;
+        Self::from_file(&file)
+    }
+
+    /// Memory-map `file`.
+    pub fn from_file(file: &File) -> io::Result<Self> {
+        // SAFETY: we can not prevent modification of the underlying file,
+        // it's documented that the file must not be modified while mapped
+        let mmap = unsafe { memmap2::Mmap::map(file)? };
+        Ok(Self { mmap })
+    }
+
+    /// Raw contents of the file.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.mmap
+    }
+
+    /// Iterate over blobs stored in the file.
+    pub fn iter(&self) -> Result<BlobIterator<'_>, Error> {
+        BlobIterator::new(self.as_bytes())
+    }
+
+    /// Create random-access reader of blobs stored in the file.
+    pub fn reader(&self) -> Result<BlobReader<'_>, Error> {
+        BlobReader::new(self.as_bytes())
+    }
+
+    /// Get records of type `T` stored in the file.
+    ///
+    /// See [`BlobReader::records`] for more information.
+    pub fn records<'a, T: Record<'a>>(&'a self) -> Result<Vec<T>, Error> {
+        self.reader()?.records()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BlobFile;
+    use crate::BlobWriter;
+    use alloc::{format, vec::Vec};
+    use std::{fs, process};
+
+    #[test]
+    fn open_file() {
+        crate::record! {
+            struct Vector { msg, tag }
+        }
+
+        let mut w = BlobWriter::new();
+        w.set_fields(&["msg", "tag"]);
+        w.extend(["m1", "t1", "m2", "t1"].iter());
+        let buf = w.encode();
+
+        let path = std::env::temp_dir().join(format!("blobby-mmap-{}.blb", process::id()));
+        fs::write(&path, &buf).unwrap();
+        let file = BlobFile::open(&path).unwrap();
+        assert_eq!(file.as_bytes(), &buf[..]);
+        assert_eq!(file.iter().unwrap().count(), 4);
+        assert_eq!(file.reader().unwrap().get(3), Some(&b"t1"[..]));
+        let v: Vec<Vector> = file.records().unwrap();
+        assert_eq!((v[1].msg, v[1].tag), (&b"m2"[..], &b"t1"[..]));
+        drop(file);
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,250 @@
+//! Utilities for editing encoded data.
+use crate::{BlobReader, BlobWriter, Error, Format};
+use alloc::vec::Vec;
+use core::ops::{Bound, RangeBounds};
+
+/// Merge blobs stored in `inputs` into one encoded buffer.
+///
+/// Blobs are de-duplicated across all inputs. The result uses
+/// [`Format::V3`] if at least one of the inputs uses it and
+/// [`Format::V2`] otherwise. Schema section of the inputs is preserved,
+/// [`Error::InvalidSchema`] is returned if the inputs have different schemas.
+///
+/// # Examples
+/// ```
+/// use blobby::BlobWriter;
+///
+/// let a = ["hello", "world"].iter().collect::<BlobWriter>().encode();
+/// let b = ["hello"].iter().collect::<BlobWriter>().encode();
+/// let buf = blobby::merge(&[&a, &b]).unwrap();
+/// let r = blobby::BlobReader::new(&buf).unwrap();
+/// assert_eq!(r.as_slice(), [&b"hello"[..], b"world", b"hello"]);
+/// ```
+pub fn merge(inputs: &[&[u8]]) -> Result<Vec<u8>, Error> {
+    let mut format = Format::V2;
+    let mut fields = None;
+    let mut blobs = Vec::new();
+    for input in inputs {
+        let r = BlobReader::new(input)?;
+        if r.format() == Format::V3 {
+            format = Format::V3;
+        }
+        match fields {
+            None => fields = Some(r.fields().map(|f| f.to_vec())),
+            Some(ref f) if f.as_deref() != r.fields() => return Err(Error::InvalidSchema),
+            Some(_) => {}
+        }
+        blobs.extend(r.iter());
+    }
+    let mut w = BlobWriter::with_format(format);
+    if let Some(Some(f)) = fields {
+        w.set_fields(&f);
+    }
+    w.extend(blobs);
+    Ok(w.encode())
+}
+
+/// Select records consisting of `n` blobs with indices in `range`.
+///
+/// The result uses the same format and schema as `data`. Returns
+/// [`Error::NotEnoughElements`] if the number of stored blobs is not
+/// a multiple of `n` and [`Error::InvalidIndex`] if `range` is out of bounds.
+///
+/// # Panics
+/// If `n` is equal to zero.
+///
+/// # Examples
+/// ```
+/// let buf = b"\x02\x05hello\x06world!\x01\x02 \x00\x03\x06:::\x03\x01\x00";
+/// let buf = blobby::select(buf, 2, 1..3).unwrap();
+/// let r = blobby::BlobReader::new(&buf).unwrap();
+/// assert_eq!(r.as_slice(), [&b""[..], b"world!", b":::", b"world!"]);
+/// ```
+pub fn select(data: &[u8], n: usize, range: impl RangeBounds<usize>) -> Result<Vec<u8>, Error> {
+    assert_ne!(n, 0, "record size must be non-zero");
+    let r = BlobReader::new(data)?;
+    if r.len() % n != 0 {
+        return Err(Error::NotEnoughElements);
+    }
+    let start = match range.start_bound() {
+        Bound::Included(&i) => i,
+        Bound::Excluded(&i) => i.checked_add(1).ok_or(Error::InvalidIndex)?,
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&i) => i.checked_add(1).ok_or(Error::InvalidIndex)?,
+        Bound::Excluded(&i) => i,
+        Bound::Unbounded => r.records_len(n),
+    };
+    if start > end || end > r.records_len(n) {
+        return Err(Error::InvalidIndex);
+    }
+    let mut w = BlobWriter::with_format(r.format());
+    if let Some(f) = r.fields() {
+        w.set_fields(f);
+    }
+    w.extend(&r.as_slice()[start * n..end * n]);
+    Ok(w.encode())
+}
+
+/// Difference between two records with the same index.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RecordDiff<'a> {
+    /// Record is present in both inputs, but its blobs differ
+    Changed {
+        /// Index of the record
+        index: usize,
+        /// Blobs of the old record
+        old: Vec<&'a [u8]>,
+        /// Blobs of the new record
+        new: Vec<&'a [u8]>,
+    },
+    /// Record is present only in the old input
+    Removed {
+        /// Index of the record
+        index: usize,
+        /// Blobs of the removed record
+        old: Vec<&'a [u8]>,
+    },
+    /// Record is present only in the new input
+    Added {
+        /// Index of the record
+        index: usize,
+        /// Blobs of the added record
+        new: Vec<&'a [u8]>,
+    },
+}
+
+/// Compare records consisting of `n` blobs stored in `old` and `new`.
+///
+/// Records are compared by their position, i.e. the `i`-th record of `old`
+/// is compared with the `i`-th record of `new`. Returns
+/// [`Error::NotEnoughElements`] if the number of blobs stored in any of
+/// the inputs is not a multiple of `n`.
+///
+/// # Panics
+/// If `n` is equal to zero.
+///
+/// # Examples
+/// ```
+/// use blobby::{BlobWriter, RecordDiff};
+///
+/// let old = ["a", "1", "b", "2"].iter().collect::<BlobWriter>().encode();
+/// let new = ["a", "1", "b", "3", "c", "4"].iter().collect::<BlobWriter>().encode();
+/// let diff = blobby::diff(&old, &new, 2).unwrap();
+/// assert_eq!(diff, [
+///     RecordDiff::Changed { index: 1, old: vec![&b"b"[..], b"2"], new: vec![&b"b"[..], b"3"] },
+///     RecordDiff::Added { index: 2, new: vec![&b"c"[..], b"4"] },
+/// ]);
+/// ```
+pub fn diff<'a>(old: &'a [u8], new: &'a [u8], n: usize) -> Result<Vec<RecordDiff<'a>>, Error> {
+    assert_ne!(n, 0, "record size must be non-zero");
+    let old = BlobReader::new(old)?;
+    let new = BlobReader::new(new)?;
+    if old.len() % n != 0 || new.len() % n != 0 {
+        return Err(Error::NotEnoughElements);
+    }
+    let old_len = old.records_len(n);
+    let new_len = new.records_len(n);
+    let mut res = Vec::new();
+    for index in 0..core::cmp::max(old_len, new_len) {
+        let d = match (old.record(index, n), new.record(index, n)) {
+            (Some(o), Some(n)) if o == n => continue,
+            (Some(o), Some(n)) => RecordDiff::Changed {
+                index,
+                old: o.to_vec(),
+                new: n.to_vec(),
+            },
+            (Some(o), None) => RecordDiff::Removed {
+                index,
+                old: o.to_vec(),
+            },
+            (None, Some(n)) => RecordDiff::Added {
+                index,
+                new: n.to_vec(),
+            },
+            (None, None) => unreachable!(),
+        };
+        res.push(d);
+    }
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn merge_formats() {
+        let a = ["hello", "world"].iter().collect::<BlobWriter>().encode();
+        let mut b = BlobWriter::with_format(Format::V3);
+        b.push(b"world");
+        let b = b.encode();
+
+        let buf = merge(&[&a, &a]).unwrap();
+        let r = BlobReader::new(&buf).unwrap();
+        assert_eq!(r.format(), Format::V2);
+        assert_eq!(r.len(), 4);
+        // all blobs are de-duplicated
+        assert_eq!(buf.len(), 1 + 6 + 6 + 4);
+
+        let buf = merge(&[&a, &b]).unwrap();
+        let r = BlobReader::new(&buf).unwrap();
+        assert_eq!(r.format(), Format::V3);
+        assert_eq!(r.as_slice(), [&b"hello"[..], b"world", b"world"]);
+
+        assert_eq!(merge(&[&a, b"\x01"]), Err(Error::UnexpectedEnd));
+
+        let mut c = BlobWriter::new();
+        c.set_fields(&["msg"]);
+        c.push(b"hello");
+        let c = c.encode();
+        let buf = merge(&[&c, &c]).unwrap();
+        assert_eq!(BlobReader::new(&buf).unwrap().fields(), Some(&["msg"][..]));
+        assert_eq!(merge(&[&c, &b]), Err(Error::InvalidSchema));
+    }
+
+    #[test]
+    fn select_ranges() {
+        let buf = ["a", "1", "b", "2", "c", "3"]
+            .iter()
+            .collect::<BlobWriter>()
+            .encode();
+        let blobs = |data: &[u8]| -> Vec<Vec<u8>> {
+            let r = BlobReader::new(data).unwrap();
+            r.iter().map(|v| v.to_vec()).collect()
+        };
+        assert_eq!(blobs(&select(&buf, 2, ..).unwrap()), blobs(&buf));
+        assert_eq!(blobs(&select(&buf, 2, 1..=1).unwrap()), [b"b", b"2"]);
+        assert_eq!(blobs(&select(&buf, 3, 1..).unwrap()), [b"2", b"c", b"3"]);
+        assert!(blobs(&select(&buf, 2, 3..).unwrap()).is_empty());
+        assert_eq!(select(&buf, 2, 2..4), Err(Error::InvalidIndex));
+        assert_eq!(select(&buf, 4, ..), Err(Error::NotEnoughElements));
+    }
+
+    #[test]
+    fn diff_records() {
+        let old = ["a", "1", "b", "2", "c", "3"]
+            .iter()
+            .collect::<BlobWriter>()
+            .encode();
+        let new = ["a", "0", "b", "2"].iter().collect::<BlobWriter>().encode();
+        assert_eq!(diff(&old, &old, 2), Ok(vec![]));
+        assert_eq!(
+            diff(&old, &new, 2).unwrap(),
+            [
+                RecordDiff::Changed {
+                    index: 0,
+                    old: vec![&b"a"[..], b"1"],
+                    new: vec![&b"a"[..], b"0"],
+                },
+                RecordDiff::Removed {
+                    index: 2,
+                    old: vec![&b"c"[..], b"3"],
+                },
+            ]
+        );
+        assert_eq!(diff(&old, &new, 3), Err(Error::NotEnoughElements));
+    }
+}
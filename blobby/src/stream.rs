@@ -0,0 +1,418 @@
+//! Streaming parser over `io::Read`.
+use crate::{
+    crc32::Crc32, encode_vlq, Error, Format, CHECKSUM_LEN, FLAG_CHECKSUM, FLAG_DEFLATE,
+    FLAG_SCHEMA, KNOWN_FLAGS, MAX_VLQ_LEN, NEXT_MASK, V3_MAGIC, VAL_MASK,
+};
+use alloc::{string::String, vec::Vec};
+use std::io::{self, Read};
+
+/// Streaming iterator over binary blobs read from [`io::Read`].
+///
+/// In contrast to [`BlobIterator`][crate::BlobIterator], the data is consumed
+/// incrementally, so only de-duplicated blobs and the currently returned blob
+/// are kept in memory. Blobs are read byte-by-byte, so it's recommended to
+/// wrap unbuffered readers (e.g. files or sockets) into [`io::BufReader`].
+///
+/// Errors specific to the storage format are reported as [`io::Error`]s
+/// wrapping [`Error`], with [`io::ErrorKind::UnexpectedEof`] used for
+/// [`Error::UnexpectedEnd`] and [`io::ErrorKind::InvalidData`] for
+/// other errors. Compressed data is not supported. After the first error
+/// iterator does not yield any more values.
+///
+/// Checksum footer (if present) can be verified only after the whole data
+/// has been read, so [`Error::InvalidChecksum`] is returned after the last
+/// blob. Corrupted data may result in other errors before that.
+///
+/// # Examples
+/// ```
+/// let buf = b"\x02\x05hello\x06world!\x01\x02 \x00\x03\x06:::\x03\x01\x00";
+/// let mut v = blobby::BlobStream::new(&buf[..]).unwrap();
+/// assert_eq!(v.next().unwrap().unwrap(), b"hello");
+/// assert_eq!(v.next().unwrap().unwrap(), b" ");
+/// assert_eq!(v.next().unwrap().unwrap(), b"");
+/// assert_eq!(v.next().unwrap().unwrap(), b"world!");
+/// assert_eq!(v.count(), 4);
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[derive(Debug)]
+pub struct BlobStream<R: Read> {
+    reader: R,
+    /// Bytes read while detecting format, which are yet to be parsed
+    head: [u8; 8],
+    head_pos: usize,
+    head_len: usize,
+    dedup: Vec<Vec<u8>>,
+    format: Format,
+    fields: Option<Vec<String>>,
+    footer: Option<Footer>,
+    done: bool,
+}
+
+/// State of the checksum verification.
+#[derive(Debug)]
+struct Footer {
+    crc: Crc32,
+    /// Last bytes read from the reader, which may turn out to be the footer
+    tail: [u8; CHECKSUM_LEN],
+    tail_len: usize,
+}
+
+impl<R: Read> BlobStream<R> {
+    /// Create new `BlobStream` reading from `reader`.
+    ///
+    /// Reads format header and all de-duplicated blobs.
+    pub fn new(reader: R) -> io::Result<Self> {
+        let mut s = BlobStream {
+            reader,
+            head: [0; 8],
+            head_pos: 0,
+            head_len: 0,
+            dedup: Vec::new(),
+            format: Format::V2,
+            fields: None,
+            footer: None,
+            done: false,
+        };
+        while s.head_len < s.head.len() {
+            match read_retry(&mut s.reader, &mut s.head[s.head_len..])? {
+                0 => break,
+                n => s.head_len += n,
+            }
+        }
+        if &s.head == V3_MAGIC {
+            s.format = Format::V3;
+            s.head_pos = s.head_len;
+            let flags = s.read_vlq()?;
+            if flags & !KNOWN_FLAGS != 0 {
+                return Err(error(Error::UnsupportedFlags));
+            }
+            if flags & FLAG_DEFLATE != 0 {
+                return Err(error(Error::Compressed));
+            }
+            if flags & FLAG_CHECKSUM != 0 {
+                let mut crc = Crc32::new();
+                crc.update(V3_MAGIC);
+                crc.update(encode_vlq(flags, &mut [0u8; MAX_VLQ_LEN]));
+                s.footer = Some(Footer {
+                    crc,
+                    tail: [0; CHECKSUM_LEN],
+                    tail_len: 0,
+                });
+            }
+            if flags & FLAG_SCHEMA != 0 {
+                let k = s.read_vlq()?;
+                if k == 0 {
+                    return Err(error(Error::InvalidSchema));
+                }
+                let mut fields = Vec::new();
+                for _ in 0..k {
+                    let m = s.read_vlq()?;
+                    let name = String::from_utf8(s.read_bytes(m)?)
+                        .map_err(|_| error(Error::InvalidSchema))?;
+                    fields.push(name);
+                }
+                s.fields = Some(fields);
+            }
+        }
+
+        let dedup_n = s.read_vlq()?;
+        for _ in 0..dedup_n {
+            let m = s.read_vlq()?;
+            let blob = s.read_bytes(m)?;
+            s.dedup.push(blob);
+        }
+        Ok(s)
+    }
+
+    /// Format of the read data.
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    /// Names of the record fields stored in the schema section.
+    pub fn fields(&self) -> Option<&[String]> {
+        self.fields.as_deref()
+    }
+
+    /// De-duplicated blobs stored in the index.
+    pub fn dedup(&self) -> &[Vec<u8>] {
+        &self.dedup
+    }
+
+    /// Read a single byte, returns `None` on the end of data.
+    fn read_byte(&mut self) -> io::Result<Option<u8>> {
+        if self.head_pos < self.head_len {
+            self.head_pos += 1;
+            return Ok(Some(self.head[self.head_pos - 1]));
+        }
+        let mut b = [0u8];
+        match self.read_raw(&mut b)? {
+            0 => Ok(None),
+            _ => Ok(Some(b[0])),
+        }
+    }
+
+    /// Read data from the underlying reader, excluding checksum footer.
+    fn read_raw(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let footer = match &mut self.footer {
+            Some(footer) => footer,
+            None => return read_retry(&mut self.reader, buf),
+        };
+        // keep the last `CHECKSUM_LEN` bytes in `tail`, so the footer is
+        // not returned as data after reaching the end
+        while footer.tail_len < CHECKSUM_LEN {
+            let n = read_retry(&mut self.reader, &mut footer.tail[footer.tail_len..])?;
+            if n == 0 {
+                return Ok(0);
+            }
+            footer.tail_len += n;
+        }
+        let n = read_retry(&mut self.reader, buf)?;
+        let mut joined = [0u8; 2 * CHECKSUM_LEN];
+        if n >= CHECKSUM_LEN {
+            joined[..CHECKSUM_LEN].copy_from_slice(&buf[n - CHECKSUM_LEN..n]);
+            buf.copy_within(..n - CHECKSUM_LEN, CHECKSUM_LEN);
+            buf[..CHECKSUM_LEN].copy_from_slice(&footer.tail);
+            footer.tail.copy_from_slice(&joined[..CHECKSUM_LEN]);
+        } else {
+            joined[..CHECKSUM_LEN].copy_from_slice(&footer.tail);
+            joined[CHECKSUM_LEN..][..n].copy_from_slice(&buf[..n]);
+            buf[..n].copy_from_slice(&joined[..n]);
+            footer.tail.copy_from_slice(&joined[n..][..CHECKSUM_LEN]);
+        }
+        footer.crc.update(&buf[..n]);
+        Ok(n)
+    }
+
+    /// Verify checksum footer after reaching the end of data.
+    fn verify_footer(&self) -> io::Result<()> {
+        match &self.footer {
+            None => Ok(()),
+            Some(f) if f.tail_len < CHECKSUM_LEN => Err(error(Error::UnexpectedEnd)),
+            Some(f) if f.crc.finalize() != u32::from_le_bytes(f.tail) => {
+                Err(error(Error::InvalidChecksum))
+            }
+            Some(_) => Ok(()),
+        }
+    }
+
+    /// Read a git-flavoured VLQ value starting with byte `b`.
+    fn read_vlq_from(&mut self, mut b: u8) -> io::Result<usize> {
+        let mut val = (b & VAL_MASK) as usize;
+        for _ in 1..self.format.vlq_len() {
+            if b & NEXT_MASK == 0 {
+                return Ok(val);
+            }
+            b = self
+                .read_byte()?
+                .ok_or_else(|| error(Error::UnexpectedEnd))?;
+            val = val
+                .checked_add(1)
+                .and_then(|v| v.checked_mul(1 << 7))
+                .and_then(|v| v.checked_add((b & VAL_MASK) as usize))
+                .ok_or_else(|| error(Error::InvalidVlq))?;
+        }
+        if b & NEXT_MASK == 0 {
+            Ok(val)
+        } else {
+            Err(error(Error::InvalidVlq))
+        }
+    }
+
+    fn read_vlq(&mut self) -> io::Result<usize> {
+        let b = self
+            .read_byte()?
+            .ok_or_else(|| error(Error::UnexpectedEnd))?;
+        self.read_vlq_from(b)
+    }
+
+    /// Read exactly `n` bytes without allocating the whole buffer upfront,
+    /// since `n` comes from untrusted data.
+    fn read_bytes(&mut self, n: usize) -> io::Result<Vec<u8>> {
+        let mut buf = Vec::new();
+        let head_n = core::cmp::min(n, self.head_len - self.head_pos);
+        buf.extend_from_slice(&self.head[self.head_pos..][..head_n]);
+        self.head_pos += head_n;
+        while buf.len() < n {
+            let len = buf.len();
+            buf.resize(len + core::cmp::min(n - len, 8 * 1024), 0);
+            let read = self.read_raw(&mut buf[len..])?;
+            if read == 0 {
+                return Err(error(Error::UnexpectedEnd));
+            }
+            buf.truncate(len + read);
+        }
+        Ok(buf)
+    }
+
+    fn dedup_entry(&self, idx: usize) -> io::Result<&[u8]> {
+        match self.dedup.get(idx) {
+            Some(v) => Ok(v),
+            None => Err(error(Error::InvalidIndex)),
+        }
+    }
+
+    fn read(&mut self, first: u8) -> io::Result<Vec<u8>> {
+        let val = self.read_vlq_from(first)?;
+        // the least significant bit is used as a flag
+        let is_ref = (val & 1) != 0;
+        if !is_ref {
+            return self.read_bytes(val >> 1);
+        }
+        match self.format {
+            Format::V2 => self.dedup_entry(val >> 1).map(|v| v.to_vec()),
+            // the second least significant bit marks references to slices
+            Format::V3 if val & 0b10 == 0 => self.dedup_entry(val >> 2).map(|v| v.to_vec()),
+            Format::V3 => {
+                let offset = self.read_vlq()?;
+                let len = self.read_vlq()?;
+                let end = offset
+                    .checked_add(len)
+                    .ok_or_else(|| error(Error::InvalidIndex))?;
+                let entry = self.dedup_entry(val >> 2)?;
+                match entry.get(offset..end) {
+                    Some(v) => Ok(v.to_vec()),
+                    None => Err(error(Error::InvalidIndex)),
+                }
+            }
+        }
+    }
+}
+
+impl<R: Read> Iterator for BlobStream<R> {
+    type Item = io::Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let res = match self.read_byte() {
+            Ok(Some(b)) => self.read(b),
+            Ok(None) => {
+                self.done = true;
+                return self.verify_footer().err().map(Err);
+            }
+            Err(e) => Err(e),
+        };
+        if res.is_err() {
+            self.done = true;
+        }
+        Some(res)
+    }
+}
+
+fn read_retry(reader: &mut impl Read, buf: &mut [u8]) -> io::Result<usize> {
+    loop {
+        match reader.read(buf) {
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            res => return res,
+        }
+    }
+}
+
+fn error(err: Error) -> io::Error {
+    let kind = match err {
+        Error::UnexpectedEnd => io::ErrorKind::UnexpectedEof,
+        _ => io::ErrorKind::InvalidData,
+    };
+    io::Error::new(kind, err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BlobStream;
+    use crate::{BlobIterator, BlobWriter, Error, Format};
+    use alloc::vec::Vec;
+    use std::io;
+
+    fn collect(buf: &[u8]) -> io::Result<Vec<Vec<u8>>> {
+        BlobStream::new(buf)?.collect()
+    }
+
+    fn inner_error(err: io::Error) -> Error {
+        *err.into_inner().unwrap().downcast::<Error>().unwrap()
+    }
+
+    #[test]
+    fn matches_iterator() {
+        let blobs: [&[u8]; 6] = [
+            b"hello",
+            b"",
+            b"hello world",
+            b"world",
+            b"hello world",
+            b"x",
+        ];
+        for &format in &[Format::V2, Format::V3] {
+            let mut w = BlobWriter::with_format(format);
+            w.extend(blobs.iter());
+            let buf = w.encode();
+            let expected: Vec<&[u8]> = BlobIterator::new(&buf)
+                .unwrap()
+                .map(|v| v.unwrap())
+                .collect();
+
+            let s = BlobStream::new(&buf[..]).unwrap();
+            assert_eq!(s.format(), format);
+            assert_eq!(s.map(|v| v.unwrap()).collect::<Vec<_>>(), expected);
+        }
+        let mut w = BlobWriter::new();
+        w.set_fields(&["msg", "tag"]);
+        w.set_checksum(true);
+        w.extend(["a", "b", "c", "b"].iter());
+        let buf = w.encode();
+        let s = BlobStream::new(&buf[..]).unwrap();
+        assert_eq!(s.fields().unwrap(), ["msg", "tag"]);
+        assert_eq!(
+            s.map(|v| v.unwrap()).collect::<Vec<_>>(),
+            [b"a", b"b", b"c", b"b"]
+        );
+
+        // data shorter than the magic bytes
+        assert_eq!(collect(b"\x00\x02a").unwrap(), [b"a"]);
+        assert!(collect(b"\x00").unwrap().is_empty());
+    }
+
+    #[test]
+    fn errors() {
+        let err = collect(b"\x01\x05hel").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+        assert_eq!(inner_error(err), Error::UnexpectedEnd);
+
+        let err = collect(b"\x00\x02a\x03").unwrap_err();
+        assert_eq!(inner_error(err), Error::InvalidIndex);
+
+        let err = collect(b"\xff\xff\xff\xff\x00").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(inner_error(err), Error::InvalidVlq);
+
+        let err = collect(b"\xff\xff\xff\xffblb3\x08\x00").unwrap_err();
+        assert_eq!(inner_error(err), Error::UnsupportedFlags);
+
+        let mut w = BlobWriter::new();
+        w.extend(["hello", "world", "hello"].iter());
+        w.set_checksum(true);
+        let mut buf = w.encode();
+        let blobs = collect(&buf).unwrap();
+        assert_eq!(blobs, [b"hello", b"world", b"hello"]);
+        let n = buf.len();
+        buf[n - 1] ^= 1;
+        let mut s = BlobStream::new(&buf[..]).unwrap();
+        assert_eq!(s.by_ref().take(3).count(), 3);
+        assert_eq!(
+            inner_error(s.next().unwrap().unwrap_err()),
+            Error::InvalidChecksum
+        );
+        assert!(s.next().is_none());
+        // truncated data is detected as checksum mismatch
+        let err = collect(&buf[..n - 1]).unwrap_err();
+        assert_eq!(inner_error(err), Error::InvalidChecksum);
+        let err = collect(&buf[..11]).unwrap_err();
+        assert_eq!(inner_error(err), Error::UnexpectedEnd);
+
+        let mut s = BlobStream::new(&b"\x00\x02a\x05"[..]).unwrap();
+        assert_eq!(s.next().unwrap().unwrap(), b"a");
+        assert!(s.next().unwrap().is_err());
+        assert!(s.next().is_none());
+    }
+}
@@ -0,0 +1,115 @@
+//! Tests for handling of malformed inputs.
+use blobby::{BlobIterator, BlobReader, Error};
+
+const V3: &[u8; 9] = b"\xff\xff\xff\xffblb3\x00";
+
+fn v3(data: &[u8]) -> Vec<u8> {
+    let mut buf = V3.to_vec();
+    buf.extend_from_slice(data);
+    buf
+}
+
+fn check(data: &[u8], err: Error) {
+    assert_eq!(BlobReader::new(data).err(), Some(err), "{:02x?}", data);
+}
+
+#[test]
+fn truncated_index() {
+    // empty data and truncated number of index entries
+    check(b"", Error::UnexpectedEnd);
+    check(b"\x80", Error::UnexpectedEnd);
+    // missing index entries
+    check(b"\x02", Error::UnexpectedEnd);
+    check(b"\x02\x01a", Error::UnexpectedEnd);
+    // truncated index entry
+    check(b"\x01\x05hell", Error::UnexpectedEnd);
+    check(b"\x01\x80", Error::UnexpectedEnd);
+    check(&v3(b"\x01\x05hell"), Error::UnexpectedEnd);
+    // truncated flags
+    check(b"\xff\xff\xff\xffblb3", Error::UnexpectedEnd);
+    // all prefixes of valid data are either valid or rejected
+    let buf = b"\x02\x05hello\x06world!\x01\x02 \x00\x03\x06:::\x03\x01\x00";
+    for i in 0..buf.len() {
+        let _ = BlobReader::new(&buf[..i]);
+    }
+    check(&buf[..9], Error::UnexpectedEnd);
+}
+
+#[test]
+fn truncated_data() {
+    check(b"\x00\x0ahell", Error::UnexpectedEnd);
+    check(b"\x00\x80", Error::UnexpectedEnd);
+    check(&v3(b"\x00\x0ahell"), Error::UnexpectedEnd);
+    // truncated slice reference
+    check(&v3(b"\x01\x01a\x03"), Error::UnexpectedEnd);
+    check(&v3(b"\x01\x01a\x03\x00"), Error::UnexpectedEnd);
+}
+
+#[test]
+fn invalid_vlq() {
+    // version 2 VLQ values are limited to 4 bytes
+    check(b"\x80\x80\x80\x80\x00", Error::InvalidVlq);
+    check(b"\x00\x80\x80\x80\x80\x00", Error::InvalidVlq);
+    // version 3 VLQ values are limited to 10 bytes and must fit into usize
+    check(
+        &v3(b"\x80\x80\x80\x80\x80\x80\x80\x80\x80\x80\x00"),
+        Error::InvalidVlq,
+    );
+    check(
+        &v3(b"\x00\xff\xff\xff\xff\xff\xff\xff\xff\xff\x7f"),
+        Error::InvalidVlq,
+    );
+}
+
+#[test]
+fn out_of_range_references() {
+    check(b"\x00\x01", Error::InvalidIndex);
+    check(b"\x01\x01a\x03", Error::InvalidIndex);
+    check(&v3(b"\x01\x01a\x05"), Error::InvalidIndex);
+    // reference to a slice outside of the entry
+    check(&v3(b"\x01\x03abc\x03\x01\x03"), Error::InvalidIndex);
+    check(&v3(b"\x01\x03abc\x03\x04\x00"), Error::InvalidIndex);
+    // overflowing slice bounds
+    check(
+        &v3(b"\x01\x03abc\x03\x01\xfe\xfe\xfe\xfe\xfe\xfe\xfe\xfe\x7f"),
+        Error::InvalidIndex,
+    );
+    // valid slice references
+    let buf = v3(b"\x01\x03abc\x03\x01\x02\x03\x03\x00");
+    let r = BlobReader::new(&buf).unwrap();
+    assert_eq!(r.as_slice(), [&b"bc"[..], b""]);
+}
+
+#[test]
+fn overlapping_entries() {
+    // index entry which covers the data section results in no stored blobs
+    let r = BlobReader::new(b"\x01\x04\x00\x01\x00\x01").unwrap();
+    assert!(r.is_empty());
+    // entry covering the following index entry makes it truncated
+    check(b"\x02\x02\x01a", Error::UnexpectedEnd);
+    // blob length covering following entries consumes them as its content
+    let r = BlobReader::new(b"\x00\x06\x02a\x02").unwrap();
+    assert_eq!(r.as_slice(), [&b"\x02a\x02"[..]]);
+}
+
+#[test]
+fn iterator_stops_after_error() {
+    let mut iter = BlobIterator::new(b"\x00\x02a\x05\x02b").unwrap();
+    assert_eq!(iter.next(), Some(Ok(&b"a"[..])));
+    assert_eq!(iter.next(), Some(Err(Error::InvalidIndex)));
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+fn invalid_header() {
+    check(b"\xff\xff\xff\xffblb3\x08\x00", Error::UnsupportedFlags);
+    check(b"\xff\xff\xff\xffblb3\x01\x00\x00", Error::Compressed);
+    check(b"\xff\xff\xff\xffblb3\x02\x00", Error::UnexpectedEnd);
+    check(
+        b"\xff\xff\xff\xffblb3\x02\x00\x00\x00\x00\x00",
+        Error::InvalidChecksum,
+    );
+    check(b"\xff\xff\xff\xffblb3\x04\x00\x00", Error::InvalidSchema);
+    // magic bytes which are not followed by "blb3" form an invalid VLQ
+    check(b"\xff\xff\xff\xffblb2\x00", Error::InvalidVlq);
+}
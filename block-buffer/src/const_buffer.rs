@@ -0,0 +1,276 @@
+use super::{copy::copy, BlockBuffer, BufferKind, Eager, Lazy};
+use core::{marker::PhantomData, slice};
+use generic_array::{
+    typenum::{IsLess, Le, NonZero, U256},
+    ArrayLength,
+};
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// Eager block buffer with const generic block size.
+pub type ConstEagerBuffer<const N: usize> = ConstBlockBuffer<N, Eager>;
+/// Lazy block buffer with const generic block size.
+pub type ConstLazyBuffer<const N: usize> = ConstBlockBuffer<N, Lazy>;
+
+/// Buffer for block processing of data with block size defined by
+/// a const generic parameter.
+///
+/// It mirrors [`BlockBuffer`] API, but does not require `typenum` bounds.
+/// Block size must be in the range of `1..256`.
+#[derive(Debug)]
+#[cfg_attr(docsrs, doc(cfg(feature = "const-generics")))]
+pub struct ConstBlockBuffer<const N: usize, Kind: BufferKind> {
+    buffer: [u8; N],
+    pos: u8,
+    _pd: PhantomData<Kind>,
+}
+
+impl<const N: usize, Kind: BufferKind> Default for ConstBlockBuffer<N, Kind> {
+    /// # Panics
+    /// If `N` is equal to zero or bigger than 255.
+    fn default() -> Self {
+        assert!(N != 0 && N < 256, "invalid block size");
+        Self {
+            buffer: [0; N],
+            pos: 0,
+            _pd: PhantomData,
+        }
+    }
+}
+
+impl<const N: usize, Kind: BufferKind> Clone for ConstBlockBuffer<N, Kind> {
+    fn clone(&self) -> Self {
+        Self {
+            buffer: self.buffer,
+            pos: self.pos,
+            _pd: PhantomData,
+        }
+    }
+}
+
+impl<const N: usize, Kind: BufferKind> ConstBlockBuffer<N, Kind> {
+    /// Create new buffer from slice.
+    ///
+    /// # Panics
+    /// If slice length is not valid for used buffer kind or if `N` is
+    /// equal to zero or bigger than 255.
+    #[inline(always)]
+    pub fn new(buf: &[u8]) -> Self {
+        let pos = buf.len();
+        assert!(Kind::invariant(pos, N));
+        let mut res = Self::default();
+        res.buffer[..pos].copy_from_slice(buf);
+        res.set_pos_unchecked(pos);
+        res
+    }
+
+    /// Create new buffer from [`BlockBuffer`] with the same block size.
+    ///
+    /// # Panics
+    /// If `BlockSize` is not equal to `N`.
+    #[inline]
+    pub fn from_block_buffer<BlockSize>(buf: &BlockBuffer<BlockSize, Kind>) -> Self
+    where
+        BlockSize: ArrayLength<u8> + IsLess<U256>,
+        Le<BlockSize, U256>: NonZero,
+    {
+        assert_eq!(BlockSize::USIZE, N, "block sizes are not equal");
+        Self::new(buf.get_data())
+    }
+
+    /// Convert buffer into [`BlockBuffer`] with the same block size.
+    ///
+    /// # Panics
+    /// If `BlockSize` is not equal to `N`.
+    #[inline]
+    pub fn to_block_buffer<BlockSize>(&self) -> BlockBuffer<BlockSize, Kind>
+    where
+        BlockSize: ArrayLength<u8> + IsLess<U256>,
+        Le<BlockSize, U256>: NonZero,
+    {
+        assert_eq!(BlockSize::USIZE, N, "block sizes are not equal");
+        BlockBuffer::new(self.get_data())
+    }
+
+    /// Digest data in `input` in blocks of size `N` using
+    /// the `compress` function, which accepts slice of blocks.
+    #[inline]
+    pub fn digest_blocks(&mut self, mut input: &[u8], mut compress: impl FnMut(&[[u8; N]])) {
+        let pos = self.get_pos();
+        let rem = N - pos;
+        let n = input.len();
+        if Kind::invariant(n, rem) {
+            // double slicing allows to remove panic branches
+            copy(&mut self.buffer[pos..][..n], input);
+            self.set_pos_unchecked(pos + n);
+            return;
+        }
+        if pos != 0 {
+            let (left, right) = input.split_at(rem);
+            input = right;
+            copy(&mut self.buffer[pos..], left);
+            compress(slice::from_ref(&self.buffer));
+        }
+
+        let (blocks, leftover) = split_blocks::<N, Kind>(input);
+        if !blocks.is_empty() {
+            compress(blocks);
+        }
+
+        let n = leftover.len();
+        copy(&mut self.buffer[..n], leftover);
+        self.set_pos_unchecked(n);
+    }
+
+    /// Reset buffer by setting cursor position to zero.
+    #[inline(always)]
+    pub fn reset(&mut self) {
+        self.set_pos_unchecked(0);
+    }
+
+    /// Pad remaining data with zeros and return resulting block.
+    #[inline(always)]
+    pub fn pad_with_zeros(&mut self) -> &mut [u8; N] {
+        let pos = self.get_pos();
+        self.buffer[pos..].iter_mut().for_each(|b| *b = 0);
+        self.set_pos_unchecked(0);
+        &mut self.buffer
+    }
+
+    /// Return current cursor position.
+    #[inline(always)]
+    pub fn get_pos(&self) -> usize {
+        let pos = self.pos as usize;
+        if !Kind::invariant(pos, N) {
+            debug_assert!(false);
+            // SAFETY: `pos` never breaks the invariant
+            unsafe {
+                core::hint::unreachable_unchecked();
+            }
+        }
+        pos
+    }
+
+    /// Return slice of data stored inside the buffer.
+    #[inline(always)]
+    pub fn get_data(&self) -> &[u8] {
+        &self.buffer[..self.get_pos()]
+    }
+
+    /// Return size of the internall buffer in bytes.
+    #[inline(always)]
+    pub fn size(&self) -> usize {
+        N
+    }
+
+    /// Return number of remaining bytes in the internall buffer.
+    #[inline(always)]
+    pub fn remaining(&self) -> usize {
+        self.size() - self.get_pos()
+    }
+
+    #[inline(always)]
+    fn set_pos_unchecked(&mut self, pos: usize) {
+        debug_assert!(Kind::invariant(pos, N));
+        self.pos = pos as u8;
+    }
+}
+
+impl<const N: usize> ConstBlockBuffer<N, Eager> {
+    /// Compress remaining data after padding it with `delim`, zeros and
+    /// the `suffix` bytes. If there is not enough unused space, `compress`
+    /// will be called twice.
+    ///
+    /// # Panics
+    /// If suffix length is bigger than block size.
+    #[inline(always)]
+    pub fn digest_pad(&mut self, delim: u8, suffix: &[u8], mut compress: impl FnMut(&[u8; N])) {
+        if suffix.len() > N {
+            panic!("suffix is too long");
+        }
+        let pos = self.get_pos();
+        self.buffer[pos] = delim;
+        for b in &mut self.buffer[pos + 1..] {
+            *b = 0;
+        }
+
+        let n = N - suffix.len();
+        if N - pos - 1 < suffix.len() {
+            compress(&self.buffer);
+            let mut block = [0u8; N];
+            block[n..].copy_from_slice(suffix);
+            compress(&block);
+        } else {
+            self.buffer[n..].copy_from_slice(suffix);
+            compress(&self.buffer);
+        }
+        self.set_pos_unchecked(0)
+    }
+
+    /// Pad message with 0x80, zeros and 64-bit message length using
+    /// big-endian byte order.
+    #[inline]
+    pub fn len64_padding_be(&mut self, data_len: u64, compress: impl FnMut(&[u8; N])) {
+        self.digest_pad(0x80, &data_len.to_be_bytes(), compress);
+    }
+
+    /// Pad message with 0x80, zeros and 64-bit message length using
+    /// little-endian byte order.
+    #[inline]
+    pub fn len64_padding_le(&mut self, data_len: u64, compress: impl FnMut(&[u8; N])) {
+        self.digest_pad(0x80, &data_len.to_le_bytes(), compress);
+    }
+
+    /// Pad message with 0x80, zeros and 128-bit message length using
+    /// big-endian byte order.
+    #[inline]
+    pub fn len128_padding_be(&mut self, data_len: u128, compress: impl FnMut(&[u8; N])) {
+        self.digest_pad(0x80, &data_len.to_be_bytes(), compress);
+    }
+
+    /// Pad message with 0x80, zeros and 128-bit message length using
+    /// little-endian byte order.
+    #[inline]
+    pub fn len128_padding_le(&mut self, data_len: u128, compress: impl FnMut(&[u8; N])) {
+        self.digest_pad(0x80, &data_len.to_le_bytes(), compress);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+#[cfg_attr(docsrs, doc(cfg(feature = "zeroize")))]
+impl<const N: usize, Kind: BufferKind> Zeroize for ConstBlockBuffer<N, Kind> {
+    #[inline]
+    fn zeroize(&mut self) {
+        self.buffer.zeroize();
+        self.pos.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+#[cfg_attr(docsrs, doc(cfg(feature = "zeroize")))]
+impl<const N: usize, Kind: BufferKind> Drop for ConstBlockBuffer<N, Kind> {
+    #[inline]
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+#[cfg_attr(docsrs, doc(cfg(feature = "zeroize")))]
+impl<const N: usize, Kind: BufferKind> ZeroizeOnDrop for ConstBlockBuffer<N, Kind> {}
+
+/// Split input data into slice of blocks and tail. Lazy buffers keep
+/// the last full block in the tail.
+#[inline(always)]
+fn split_blocks<const N: usize, Kind: BufferKind>(data: &[u8]) -> (&[[u8; N]], &[u8]) {
+    let mut tail_len = data.len() % N;
+    if tail_len == 0 && !data.is_empty() && Kind::invariant(N, N) {
+        tail_len = N;
+    }
+    let (blocks, tail) = data.split_at(data.len() - tail_len);
+    let nb = blocks.len() / N;
+    // SAFETY: we guarantee that created slice does not point
+    // outside of `data`
+    let blocks = unsafe { slice::from_raw_parts(blocks.as_ptr() as *const [u8; N], nb) };
+    (blocks, tail)
+}
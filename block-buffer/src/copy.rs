@@ -0,0 +1,26 @@
+//! Copy routine used by buffers for moving input data into the internal
+//! block.
+//!
+//! With enabled `pulp` feature on `riscv32` targets hardware loops and
+//! post-increment loads and stores are used, other targets rely on
+//! `copy_from_slice`, i.e. on `memcpy`.
+
+#[cfg(all(feature = "pulp", target_arch = "riscv32"))]
+mod pulp;
+
+/// Copy all bytes from `src` into `dst`.
+///
+/// # Panics
+/// If slices have different lengths.
+#[inline(always)]
+pub(crate) fn copy(dst: &mut [u8], src: &[u8]) {
+    #[cfg(all(feature = "pulp", target_arch = "riscv32"))]
+    {
+        assert_eq!(dst.len(), src.len());
+        // SAFETY: slices have equal lengths and can not overlap
+        let pos = unsafe { pulp::copy(src.as_ptr(), dst.as_mut_ptr(), src.len()) };
+        dst[pos..].copy_from_slice(&src[pos..]);
+    }
+    #[cfg(not(all(feature = "pulp", target_arch = "riscv32")))]
+    dst.copy_from_slice(src);
+}
@@ -0,0 +1,34 @@
+//! Backend for PULP cores, uses hardware loops and post-increment
+//! loads and stores.
+
+/// Copy 8 bytes per loop iteration, returns number of copied bytes.
+///
+/// Buffers which are not 4-byte aligned are not processed.
+#[inline(always)]
+pub(super) unsafe fn copy(in_ptr: *const u8, out_ptr: *mut u8, len: usize) -> usize {
+    let aligned = (in_ptr as usize | out_ptr as usize) % core::mem::align_of::<u32>() == 0;
+    let words = if aligned { len / 8 } else { 0 };
+    if words != 0 {
+        // PULP extensions are not supported by the LLVM assembler,
+        // so instructions are encoded manually
+        core::arch::asm!(
+            // lp.setup 0, t2, 8
+            ".word 0x0083C07B",
+            // p.lw t0, 4(a0!)
+            ".word 0x0045228B",
+            // p.lw t1, 4(a0!)
+            ".word 0x0045230B",
+            // p.sw t0, 4(a1!)
+            ".word 0x0055A22B",
+            // p.sw t1, 4(a1!)
+            ".word 0x0065A22B",
+            // pointers are post-incremented by the loop
+            inout("a0") in_ptr as *const u32 => _,
+            inout("a1") out_ptr as *const u32 => _,
+            out("t0") _,
+            out("t1") _,
+            in("t2") words,
+        )
+    }
+    8 * words
+}
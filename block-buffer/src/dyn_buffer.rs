@@ -0,0 +1,191 @@
+use super::{copy::copy, Block, BufferKind, Eager, Lazy};
+use core::marker::PhantomData;
+use generic_array::{
+    typenum::{IsLess, Le, NonZero, U256},
+    ArrayLength,
+};
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// Eager block buffer with block size selected at runtime.
+pub type DynEagerBuffer<M> = DynBlockBuffer<M, Eager>;
+/// Lazy block buffer with block size selected at runtime.
+pub type DynLazyBuffer<M> = DynBlockBuffer<M, Lazy>;
+
+/// Buffer for block processing of data with block size selected at
+/// construction.
+///
+/// Block size can not be bigger than `MaxBlockSize`. It allows to build
+/// hash wrappers which select the algorithm at runtime.
+#[derive(Debug)]
+pub struct DynBlockBuffer<MaxBlockSize, Kind>
+where
+    MaxBlockSize: ArrayLength<u8> + IsLess<U256>,
+    Le<MaxBlockSize, U256>: NonZero,
+    Kind: BufferKind,
+{
+    buffer: Block<MaxBlockSize>,
+    block_size: u8,
+    pos: u8,
+    _pd: PhantomData<Kind>,
+}
+
+impl<MaxBlockSize, Kind> Clone for DynBlockBuffer<MaxBlockSize, Kind>
+where
+    MaxBlockSize: ArrayLength<u8> + IsLess<U256>,
+    Le<MaxBlockSize, U256>: NonZero,
+    Kind: BufferKind,
+{
+    fn clone(&self) -> Self {
+        Self {
+            buffer: self.buffer.clone(),
+            block_size: self.block_size,
+            pos: self.pos,
+            _pd: PhantomData,
+        }
+    }
+}
+
+impl<MaxBlockSize, Kind> DynBlockBuffer<MaxBlockSize, Kind>
+where
+    MaxBlockSize: ArrayLength<u8> + IsLess<U256>,
+    Le<MaxBlockSize, U256>: NonZero,
+    Kind: BufferKind,
+{
+    /// Create new empty buffer with the given block size.
+    ///
+    /// # Panics
+    /// If `block_size` is equal to zero or bigger than `MaxBlockSize`.
+    #[inline]
+    pub fn new(block_size: usize) -> Self {
+        assert!(block_size != 0 && block_size <= MaxBlockSize::USIZE);
+        Self {
+            buffer: Default::default(),
+            block_size: block_size as u8,
+            pos: 0,
+            _pd: PhantomData,
+        }
+    }
+
+    /// Digest data in `input` in blocks of the selected size using
+    /// the `compress` function, which accepts slice of one or more
+    /// consecutive blocks.
+    #[inline]
+    pub fn digest_blocks(&mut self, mut input: &[u8], mut compress: impl FnMut(&[u8])) {
+        let bs = self.size();
+        let pos = self.get_pos();
+        let rem = bs - pos;
+        let n = input.len();
+        if Kind::invariant(n, rem) {
+            copy(&mut self.buffer[pos..][..n], input);
+            self.set_pos_unchecked(pos + n);
+            return;
+        }
+        if pos != 0 {
+            let (left, right) = input.split_at(rem);
+            input = right;
+            copy(&mut self.buffer[pos..bs], left);
+            compress(&self.buffer[..bs]);
+        }
+
+        // lazy buffers keep the last full block in the buffer
+        let mut tail_len = input.len() % bs;
+        if tail_len == 0 && !input.is_empty() && Kind::invariant(bs, bs) {
+            tail_len = bs;
+        }
+        let (blocks, leftover) = input.split_at(input.len() - tail_len);
+        if !blocks.is_empty() {
+            compress(blocks);
+        }
+
+        let n = leftover.len();
+        copy(&mut self.buffer[..n], leftover);
+        self.set_pos_unchecked(n);
+    }
+
+    /// Reset buffer by setting cursor position to zero.
+    #[inline(always)]
+    pub fn reset(&mut self) {
+        self.set_pos_unchecked(0);
+    }
+
+    /// Pad remaining data with zeros and return resulting block.
+    #[inline(always)]
+    pub fn pad_with_zeros(&mut self) -> &mut [u8] {
+        let (pos, bs) = (self.get_pos(), self.size());
+        self.buffer[pos..bs].iter_mut().for_each(|b| *b = 0);
+        self.set_pos_unchecked(0);
+        &mut self.buffer[..bs]
+    }
+
+    /// Return current cursor position.
+    #[inline(always)]
+    pub fn get_pos(&self) -> usize {
+        let pos = self.pos as usize;
+        debug_assert!(Kind::invariant(pos, self.size()));
+        pos
+    }
+
+    /// Return slice of data stored inside the buffer.
+    #[inline(always)]
+    pub fn get_data(&self) -> &[u8] {
+        &self.buffer[..self.get_pos()]
+    }
+
+    /// Return block size selected on construction.
+    #[inline(always)]
+    pub fn size(&self) -> usize {
+        self.block_size as usize
+    }
+
+    /// Return number of remaining bytes in the internall buffer.
+    #[inline(always)]
+    pub fn remaining(&self) -> usize {
+        self.size() - self.get_pos()
+    }
+
+    #[inline(always)]
+    fn set_pos_unchecked(&mut self, pos: usize) {
+        debug_assert!(Kind::invariant(pos, self.size()));
+        self.pos = pos as u8;
+    }
+}
+
+#[cfg(feature = "zeroize")]
+#[cfg_attr(docsrs, doc(cfg(feature = "zeroize")))]
+impl<MaxBlockSize, Kind> Zeroize for DynBlockBuffer<MaxBlockSize, Kind>
+where
+    MaxBlockSize: ArrayLength<u8> + IsLess<U256>,
+    Le<MaxBlockSize, U256>: NonZero,
+    Kind: BufferKind,
+{
+    #[inline]
+    fn zeroize(&mut self) {
+        self.buffer.as_mut_slice().zeroize();
+        self.pos.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+#[cfg_attr(docsrs, doc(cfg(feature = "zeroize")))]
+impl<MaxBlockSize, Kind> Drop for DynBlockBuffer<MaxBlockSize, Kind>
+where
+    MaxBlockSize: ArrayLength<u8> + IsLess<U256>,
+    Le<MaxBlockSize, U256>: NonZero,
+    Kind: BufferKind,
+{
+    #[inline]
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+#[cfg_attr(docsrs, doc(cfg(feature = "zeroize")))]
+impl<MaxBlockSize, Kind> ZeroizeOnDrop for DynBlockBuffer<MaxBlockSize, Kind>
+where
+    MaxBlockSize: ArrayLength<u8> + IsLess<U256>,
+    Le<MaxBlockSize, U256>: NonZero,
+    Kind: BufferKind,
+{
+}
@@ -4,21 +4,48 @@
     html_logo_url = "https://raw.githubusercontent.com/RustCrypto/media/6ee8e381/logo.svg",
     html_favicon_url = "https://raw.githubusercontent.com/RustCrypto/media/6ee8e381/logo.svg"
 )]
+#![cfg_attr(docsrs, feature(doc_cfg))]
 #![warn(missing_docs, rust_2018_idioms)]
 
 pub use generic_array;
 
-use core::{marker::PhantomData, slice};
+use copy::copy;
+use core::{fmt, marker::PhantomData, ops::Add, slice};
 use generic_array::{
-    typenum::{IsLess, Le, NonZero, U256},
+    typenum::{Add1, IsLess, Le, NonZero, B1, U256},
     ArrayLength, GenericArray,
 };
-
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+#[cfg(feature = "const-generics")]
+mod const_buffer;
+mod copy;
+mod dyn_buffer;
+mod read;
 mod sealed;
 
+#[cfg(feature = "const-generics")]
+pub use const_buffer::{ConstBlockBuffer, ConstEagerBuffer, ConstLazyBuffer};
+pub use dyn_buffer::{DynBlockBuffer, DynEagerBuffer, DynLazyBuffer};
+pub use read::ReadBuffer;
+
 /// Block on which `BlockBuffer` operates.
 pub type Block<BlockSize> = GenericArray<u8, BlockSize>;
 
+/// Array of `M` blocks which can be processed in parallel.
+pub type ParBlock<BlockSize, M> = GenericArray<Block<BlockSize>, M>;
+
+/// Block buffer error.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct Error;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str("Block buffer error")
+    }
+}
+
 /// Trait for buffer kinds.
 pub trait BufferKind: sealed::Sealed {}
 
@@ -106,6 +133,33 @@ where
         }
     }
 
+    /// Create new buffer from slice.
+    ///
+    /// Returns an error if slice length is not valid for used buffer kind.
+    #[inline(always)]
+    pub fn try_new(buf: &[u8]) -> Result<Self, Error> {
+        if !Kind::invariant(buf.len(), BlockSize::USIZE) {
+            return Err(Error);
+        }
+        Ok(Self::new(buf))
+    }
+
+    /// Restore buffer from block and cursor position, e.g. saved from
+    /// a previous hashing session.
+    ///
+    /// Returns an error if `pos` is not valid for used buffer kind.
+    #[inline]
+    pub fn try_restore(buf: Block<BlockSize>, pos: usize) -> Result<Self, Error> {
+        if !Kind::invariant(pos, BlockSize::USIZE) {
+            return Err(Error);
+        }
+        Ok(Self {
+            buffer: buf,
+            pos: pos as u8,
+            _pd: PhantomData,
+        })
+    }
+
     /// Digest data in `input` in blocks of size `BlockSize` using
     /// the `compress` function, which accepts slice of blocks.
     #[inline]
@@ -128,14 +182,14 @@ where
         // we convince compiler that `BlockSize - pos` never underflows.
         if Kind::invariant(n, rem) {
             // double slicing allows to remove panic branches
-            self.buffer[pos..][..n].copy_from_slice(input);
+            copy(&mut self.buffer[pos..][..n], input);
             self.set_pos_unchecked(pos + n);
             return;
         }
         if pos != 0 {
             let (left, right) = input.split_at(rem);
             input = right;
-            self.buffer[pos..].copy_from_slice(left);
+            copy(&mut self.buffer[pos..], left);
             compress(slice::from_ref(&self.buffer));
         }
 
@@ -145,7 +199,110 @@ where
         }
 
         let n = leftover.len();
-        self.buffer[..n].copy_from_slice(leftover);
+        copy(&mut self.buffer[..n], leftover);
+        self.set_pos_unchecked(n);
+    }
+
+    /// Digest data in `input` in blocks of size `BlockSize` using
+    /// the `compress` function pointer, which accepts mutable reference
+    /// to the `ctx` state and slice of blocks.
+    ///
+    /// It can be used instead of [`BlockBuffer::digest_blocks`] when
+    /// compression function can not be represented by a closure, e.g.
+    /// by backends which wrap C functions.
+    #[inline]
+    pub fn digest_blocks_ctx<S>(
+        &mut self,
+        input: &[u8],
+        ctx: &mut S,
+        compress: fn(&mut S, &[Block<BlockSize>]),
+    ) {
+        self.digest_blocks(input, |blocks| compress(ctx, blocks));
+    }
+
+    /// Digest data stored in non-contiguous `parts` in blocks of size
+    /// `BlockSize` using the `compress` function.
+    ///
+    /// The result is equivalent to calling [`BlockBuffer::digest_blocks`]
+    /// with concatenation of all parts.
+    #[inline]
+    pub fn digest_blocks_vectored<'a>(
+        &mut self,
+        parts: impl IntoIterator<Item = &'a [u8]>,
+        mut compress: impl FnMut(&[Block<BlockSize>]),
+    ) {
+        for part in parts {
+            self.digest_blocks(part, &mut compress);
+        }
+    }
+
+    /// Digest data in `input` in blocks of size `BlockSize`, passing groups
+    /// of `M` consecutive blocks to `compress_par` and the remaining blocks
+    /// to `compress`.
+    ///
+    /// It allows to use multi-block compression functions for big inputs.
+    ///
+    /// # Panics
+    /// If `M` is equal to zero.
+    #[inline]
+    pub fn digest_par_blocks<M: ArrayLength<Block<BlockSize>>>(
+        &mut self,
+        input: &[u8],
+        mut compress_par: impl FnMut(&[ParBlock<BlockSize, M>]),
+        mut compress: impl FnMut(&[Block<BlockSize>]),
+    ) {
+        assert!(M::USIZE != 0);
+        self.digest_blocks(input, |blocks| {
+            let (par_blocks, blocks) = to_par_blocks::<BlockSize, M>(blocks);
+            if !par_blocks.is_empty() {
+                compress_par(par_blocks);
+            }
+            if !blocks.is_empty() {
+                compress(blocks);
+            }
+        });
+    }
+
+    /// Set `data` to generated blocks.
+    ///
+    /// Buffered bytes of the previously generated block are used first,
+    /// after which `process_blocks` is called to generate the next blocks.
+    /// Unused bytes of the last generated block are kept in the buffer.
+    /// It can be used to implement readers of extendable-output functions.
+    ///
+    /// For lazy buffers cursor position equal to block size is treated
+    /// as position of an exhausted block.
+    #[inline]
+    pub fn set_data(
+        &mut self,
+        mut data: &mut [u8],
+        mut process_blocks: impl FnMut(&mut [Block<BlockSize>]),
+    ) {
+        let pos = self.get_pos();
+        let r = self.remaining();
+        let n = data.len();
+        if pos != 0 {
+            if n < r {
+                // double slicing allows to remove panic branches
+                data.copy_from_slice(&self.buffer[pos..][..n]);
+                self.set_pos_unchecked(pos + n);
+                return;
+            }
+            let (left, right) = data.split_at_mut(r);
+            data = right;
+            left.copy_from_slice(&self.buffer[pos..]);
+        }
+
+        let (blocks, leftover) = to_blocks_mut(data);
+        process_blocks(blocks);
+
+        let n = leftover.len();
+        if n != 0 {
+            let mut block = Default::default();
+            process_blocks(slice::from_mut(&mut block));
+            leftover.copy_from_slice(&block[..n]);
+            self.buffer = block;
+        }
         self.set_pos_unchecked(n);
     }
 
@@ -214,46 +371,49 @@ where
     }
 }
 
-impl<BlockSize> BlockBuffer<BlockSize, Eager>
+impl<BlockSize, Kind> BlockBuffer<BlockSize, Kind>
 where
-    BlockSize: ArrayLength<u8> + IsLess<U256>,
+    BlockSize: ArrayLength<u8> + IsLess<U256> + Add<B1>,
     Le<BlockSize, U256>: NonZero,
+    Add1<BlockSize>: ArrayLength<u8>,
+    Kind: BufferKind,
 {
-    /// Set `data` to generated blocks.
+    /// Serialize buffer into a byte array.
+    ///
+    /// The first `BlockSize` bytes contain buffered data padded with zeros,
+    /// while the last byte contains cursor position.
     #[inline]
-    pub fn set_data(
-        &mut self,
-        mut data: &mut [u8],
-        mut process_blocks: impl FnMut(&mut [Block<BlockSize>]),
-    ) {
-        let pos = self.get_pos();
-        let r = self.remaining();
-        let n = data.len();
-        if pos != 0 {
-            if n < r {
-                // double slicing allows to remove panic branches
-                data.copy_from_slice(&self.buffer[pos..][..n]);
-                self.set_pos_unchecked(pos + n);
-                return;
-            }
-            let (left, right) = data.split_at_mut(r);
-            data = right;
-            left.copy_from_slice(&self.buffer[pos..]);
-        }
-
-        let (blocks, leftover) = to_blocks_mut(data);
-        process_blocks(blocks);
+    pub fn serialize(&self) -> GenericArray<u8, Add1<BlockSize>> {
+        let mut res = GenericArray::<u8, Add1<BlockSize>>::default();
+        let data = self.get_data();
+        res[..data.len()].copy_from_slice(data);
+        res[BlockSize::USIZE] = data.len() as u8;
+        res
+    }
 
-        let n = leftover.len();
-        if n != 0 {
-            let mut block = Default::default();
-            process_blocks(slice::from_mut(&mut block));
-            leftover.copy_from_slice(&block[..n]);
-            self.buffer = block;
+    /// Deserialize buffer from a byte array created by
+    /// [`BlockBuffer::serialize`].
+    ///
+    /// Returns an error if cursor position is not valid for used buffer kind
+    /// or if padding bytes are not equal to zero.
+    #[inline]
+    pub fn deserialize(buf: &GenericArray<u8, Add1<BlockSize>>) -> Result<Self, Error> {
+        let pos = buf[BlockSize::USIZE] as usize;
+        if !Kind::invariant(pos, BlockSize::USIZE) {
+            return Err(Error);
         }
-        self.set_pos_unchecked(n);
+        if buf[pos..BlockSize::USIZE].iter().any(|&b| b != 0) {
+            return Err(Error);
+        }
+        Ok(Self::new(&buf[..pos]))
     }
+}
 
+impl<BlockSize> BlockBuffer<BlockSize, Eager>
+where
+    BlockSize: ArrayLength<u8> + IsLess<U256>,
+    Le<BlockSize, U256>: NonZero,
+{
     /// Compress remaining data after padding it with `delim`, zeros and
     /// the `suffix` bytes. If there is not enough unused space, `compress`
     /// will be called twice.
@@ -289,6 +449,82 @@ where
         self.set_pos_unchecked(0)
     }
 
+    /// Compress remaining data after appending `bits` most significant
+    /// bits of `last_byte`, a single one bit, zeros and the `suffix` bytes.
+    ///
+    /// It allows to process messages which length is not multiple of 8 bits
+    /// with Merkle–Damgård padding. Note that message length encoded in
+    /// `suffix` should include the `bits` bits.
+    ///
+    /// # Panics
+    /// If `bits` is bigger than 7 or if suffix length is bigger than
+    /// block size.
+    #[inline]
+    pub fn digest_pad_bits(
+        &mut self,
+        last_byte: u8,
+        bits: u8,
+        suffix: &[u8],
+        compress: impl FnMut(&Block<BlockSize>),
+    ) {
+        assert!(bits < 8, "number of bits must be smaller than 8");
+        let mask = !(0xFFu8 >> bits);
+        let delim = (last_byte & mask) | (0x80 >> bits);
+        self.digest_pad(delim, suffix, compress);
+    }
+
+    /// Compress remaining data after appending `bits` least significant
+    /// bits of `last_byte` and the `delim` bits, followed by zeros and
+    /// a final one bit in the last byte of the block.
+    ///
+    /// It allows to process messages which length is not multiple of 8 bits
+    /// with the Keccak pad10*1 padding. Bits are filled starting from the
+    /// least significant one and `delim` contains domain separation bits
+    /// followed by the first padding bit, e.g. `0x06` for SHA-3 or `0x1F`
+    /// for SHAKE. If there is not enough unused space, `compress` will be
+    /// called twice.
+    ///
+    /// # Panics
+    /// If `bits` is bigger than 7 or if `delim` is equal to zero.
+    #[inline]
+    pub fn keccak_pad_bits(
+        &mut self,
+        last_byte: u8,
+        bits: u8,
+        delim: u8,
+        mut compress: impl FnMut(&Block<BlockSize>),
+    ) {
+        assert!(bits < 8, "number of bits must be smaller than 8");
+        assert!(delim != 0, "delimiter must contain padding bit");
+        let mask = (1u16 << bits) - 1;
+        let val = (u16::from(last_byte) & mask) | (u16::from(delim) << bits);
+        // number of bits occupied by message bits and `delim`
+        let n = (bits as usize) + 8 - delim.leading_zeros() as usize;
+        let [lo, hi] = val.to_le_bytes();
+
+        let pos = self.get_pos();
+        self.buffer[pos] = lo;
+        for b in &mut self.buffer[pos + 1..] {
+            *b = 0;
+        }
+
+        let last = self.size() - 1;
+        if n >= 8 * (self.size() - pos) {
+            compress(&self.buffer);
+            let mut block = Block::<BlockSize>::default();
+            block[0] = hi;
+            block[last] |= 0x80;
+            compress(&block);
+        } else {
+            if hi != 0 {
+                self.buffer[pos + 1] = hi;
+            }
+            self.buffer[last] |= 0x80;
+            compress(&self.buffer);
+        }
+        self.set_pos_unchecked(0)
+    }
+
     /// Pad message with 0x80, zeros and 64-bit message length using
     /// big-endian byte order.
     #[inline]
@@ -309,6 +545,52 @@ where
     pub fn len128_padding_be(&mut self, data_len: u128, compress: impl FnMut(&Block<BlockSize>)) {
         self.digest_pad(0x80, &data_len.to_be_bytes(), compress);
     }
+
+    /// Pad message with 0x80, zeros and 128-bit message length using
+    /// little-endian byte order.
+    #[inline]
+    pub fn len128_padding_le(&mut self, data_len: u128, compress: impl FnMut(&Block<BlockSize>)) {
+        self.digest_pad(0x80, &data_len.to_le_bytes(), compress);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+#[cfg_attr(docsrs, doc(cfg(feature = "zeroize")))]
+impl<BlockSize, Kind> Zeroize for BlockBuffer<BlockSize, Kind>
+where
+    BlockSize: ArrayLength<u8> + IsLess<U256>,
+    Le<BlockSize, U256>: NonZero,
+    Kind: BufferKind,
+{
+    #[inline]
+    fn zeroize(&mut self) {
+        self.buffer.as_mut_slice().zeroize();
+        self.pos.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+#[cfg_attr(docsrs, doc(cfg(feature = "zeroize")))]
+impl<BlockSize, Kind> Drop for BlockBuffer<BlockSize, Kind>
+where
+    BlockSize: ArrayLength<u8> + IsLess<U256>,
+    Le<BlockSize, U256>: NonZero,
+    Kind: BufferKind,
+{
+    #[inline]
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+#[cfg_attr(docsrs, doc(cfg(feature = "zeroize")))]
+impl<BlockSize, Kind> ZeroizeOnDrop for BlockBuffer<BlockSize, Kind>
+where
+    BlockSize: ArrayLength<u8> + IsLess<U256>,
+    Le<BlockSize, U256>: NonZero,
+    Kind: BufferKind,
+{
 }
 
 /// Split message into mutable slice of parallel blocks, blocks, and leftover bytes.
@@ -322,3 +604,18 @@ fn to_blocks_mut<N: ArrayLength<u8>>(data: &mut [u8]) -> (&mut [Block<N>], &mut
     let blocks = unsafe { slice::from_raw_parts_mut(p, nb) };
     (blocks, right)
 }
+
+/// Split slice of blocks into slice of parallel blocks and leftover blocks.
+#[inline(always)]
+fn to_par_blocks<N, M>(blocks: &[Block<N>]) -> (&[ParBlock<N, M>], &[Block<N>])
+where
+    N: ArrayLength<u8>,
+    M: ArrayLength<Block<N>>,
+{
+    let nb = blocks.len() / M::USIZE;
+    let (left, right) = blocks.split_at(nb * M::USIZE);
+    let p = left.as_ptr() as *const ParBlock<N, M>;
+    // SAFETY: we guarantee that `par_blocks` does not point outside of `blocks`
+    let par_blocks = unsafe { slice::from_raw_parts(p, nb) };
+    (par_blocks, right)
+}
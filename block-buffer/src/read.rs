@@ -0,0 +1,185 @@
+use super::{to_blocks_mut, Block, Error};
+use core::slice;
+use generic_array::{
+    typenum::{IsLess, Le, NonZero, U256},
+    ArrayLength,
+};
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// Buffer for reading of generated blocks, e.g. keystream or XOF output,
+/// in chunks of arbitrary length.
+///
+/// Unread bytes of the last generated block are kept in the buffer and
+/// used by the following reads.
+#[derive(Debug)]
+pub struct ReadBuffer<BlockSize>
+where
+    BlockSize: ArrayLength<u8> + IsLess<U256>,
+    Le<BlockSize, U256>: NonZero,
+{
+    buffer: Block<BlockSize>,
+    // position of the first unread byte, equal to block size if all
+    // buffered bytes were read
+    pos: u8,
+}
+
+impl<BlockSize> Default for ReadBuffer<BlockSize>
+where
+    BlockSize: ArrayLength<u8> + IsLess<U256>,
+    Le<BlockSize, U256>: NonZero,
+{
+    fn default() -> Self {
+        Self {
+            buffer: Default::default(),
+            pos: BlockSize::USIZE as u8,
+        }
+    }
+}
+
+impl<BlockSize> Clone for ReadBuffer<BlockSize>
+where
+    BlockSize: ArrayLength<u8> + IsLess<U256>,
+    Le<BlockSize, U256>: NonZero,
+{
+    fn clone(&self) -> Self {
+        Self {
+            buffer: self.buffer.clone(),
+            pos: self.pos,
+        }
+    }
+}
+
+impl<BlockSize> ReadBuffer<BlockSize>
+where
+    BlockSize: ArrayLength<u8> + IsLess<U256>,
+    Le<BlockSize, U256>: NonZero,
+{
+    /// Create new buffer from generated block and position of the first
+    /// unread byte in it.
+    ///
+    /// Returns an error if `pos` is bigger than block size.
+    #[inline]
+    pub fn try_new(buf: Block<BlockSize>, pos: usize) -> Result<Self, Error> {
+        if pos > BlockSize::USIZE {
+            return Err(Error);
+        }
+        Ok(Self {
+            buffer: buf,
+            pos: pos as u8,
+        })
+    }
+
+    /// Write generated bytes into `data`.
+    ///
+    /// Unread bytes of the buffered block are used first, after which
+    /// `gen_blocks` is called to generate the next blocks. Full blocks
+    /// are generated directly into `data`.
+    #[inline]
+    pub fn read(
+        &mut self,
+        mut data: &mut [u8],
+        mut gen_blocks: impl FnMut(&mut [Block<BlockSize>]),
+    ) {
+        let pos = self.get_pos();
+        let r = self.remaining();
+        let n = data.len();
+        if n <= r {
+            // double slicing allows to remove panic branches
+            data.copy_from_slice(&self.buffer[pos..][..n]);
+            self.set_pos_unchecked(pos + n);
+            return;
+        }
+        let (left, right) = data.split_at_mut(r);
+        data = right;
+        left.copy_from_slice(&self.buffer[pos..]);
+
+        let (blocks, leftover) = to_blocks_mut(data);
+        if !blocks.is_empty() {
+            gen_blocks(blocks);
+        }
+
+        let n = leftover.len();
+        if n != 0 {
+            gen_blocks(slice::from_mut(&mut self.buffer));
+            leftover.copy_from_slice(&self.buffer[..n]);
+            self.set_pos_unchecked(n);
+        } else {
+            self.set_pos_unchecked(BlockSize::USIZE);
+        }
+    }
+
+    /// Discard buffered bytes.
+    #[inline(always)]
+    pub fn reset(&mut self) {
+        self.set_pos_unchecked(BlockSize::USIZE);
+    }
+
+    /// Return position of the first unread byte in the buffered block.
+    #[inline(always)]
+    pub fn get_pos(&self) -> usize {
+        let pos = self.pos as usize;
+        if pos > BlockSize::USIZE {
+            debug_assert!(false);
+            // SAFETY: `pos` never exceeds block size
+            unsafe {
+                core::hint::unreachable_unchecked();
+            }
+        }
+        pos
+    }
+
+    /// Return size of the internall buffer in bytes.
+    #[inline(always)]
+    pub fn size(&self) -> usize {
+        BlockSize::USIZE
+    }
+
+    /// Return number of unread bytes in the internall buffer.
+    #[inline(always)]
+    pub fn remaining(&self) -> usize {
+        self.size() - self.get_pos()
+    }
+
+    #[inline(always)]
+    fn set_pos_unchecked(&mut self, pos: usize) {
+        debug_assert!(pos <= BlockSize::USIZE);
+        self.pos = pos as u8;
+    }
+}
+
+#[cfg(feature = "zeroize")]
+#[cfg_attr(docsrs, doc(cfg(feature = "zeroize")))]
+impl<BlockSize> Zeroize for ReadBuffer<BlockSize>
+where
+    BlockSize: ArrayLength<u8> + IsLess<U256>,
+    Le<BlockSize, U256>: NonZero,
+{
+    #[inline]
+    fn zeroize(&mut self) {
+        self.buffer.as_mut_slice().zeroize();
+        self.reset();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+#[cfg_attr(docsrs, doc(cfg(feature = "zeroize")))]
+impl<BlockSize> Drop for ReadBuffer<BlockSize>
+where
+    BlockSize: ArrayLength<u8> + IsLess<U256>,
+    Le<BlockSize, U256>: NonZero,
+{
+    #[inline]
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+#[cfg_attr(docsrs, doc(cfg(feature = "zeroize")))]
+impl<BlockSize> ZeroizeOnDrop for ReadBuffer<BlockSize>
+where
+    BlockSize: ArrayLength<u8> + IsLess<U256>,
+    Le<BlockSize, U256>: NonZero,
+{
+}
@@ -1,6 +1,6 @@
 use block_buffer::{
-    generic_array::typenum::{U10, U16, U24, U4, U8},
-    Block, EagerBuffer, LazyBuffer,
+    generic_array::typenum::{U10, U136, U16, U2, U24, U4, U8},
+    Block, DynEagerBuffer, DynLazyBuffer, EagerBuffer, LazyBuffer, ReadBuffer,
 };
 
 #[test]
@@ -101,6 +101,280 @@ fn test_eager_set_data() {
     assert_eq!(buf.get_pos(), 0);
 }
 
+#[test]
+fn test_eager_digest_par_blocks() {
+    let mut buf = EagerBuffer::<U4>::default();
+    let calls = core::cell::RefCell::new(Vec::new());
+    let inputs = [&b"01"[..], &b"23456789abcdefghij"[..], &b"klmnopq"[..]];
+    for input in inputs.iter() {
+        buf.digest_par_blocks::<U2>(
+            input,
+            |pb| calls.borrow_mut().push(("par", pb.len())),
+            |b| calls.borrow_mut().push(("single", b.len())),
+        );
+    }
+    let expected = [("single", 1), ("par", 2), ("single", 1)];
+    assert_eq!(calls.into_inner(), expected);
+    assert_eq!(buf.get_data(), b"opq");
+}
+
+#[test]
+fn test_digest_blocks_ctx() {
+    fn compress(ctx: &mut Vec<u8>, blocks: &[Block<U4>]) {
+        blocks.iter().for_each(|b| ctx.extend_from_slice(b));
+    }
+
+    let mut buf = EagerBuffer::<U4>::default();
+    let mut data = Vec::new();
+    buf.digest_blocks_ctx(b"012", &mut data, compress);
+    buf.digest_blocks_ctx(b"3456789ab", &mut data, compress);
+    assert_eq!(data, b"0123456789ab");
+    assert_eq!(buf.get_pos(), 0);
+}
+
+#[test]
+fn test_digest_blocks_vectored() {
+    let parts = [&b"01"[..], &b""[..], &b"2345"[..], &b"6789ab"[..]];
+    let mut buf = LazyBuffer::<U4>::default();
+    let mut data = Vec::new();
+    buf.digest_blocks_vectored(parts.iter().copied(), |blocks| {
+        blocks.iter().for_each(|b| data.extend_from_slice(b));
+    });
+    assert_eq!(data, b"01234567");
+    assert_eq!(buf.get_data(), b"89ab");
+}
+
+#[test]
+fn test_digest_pad_bits() {
+    let mut buf = EagerBuffer::<U8>::new(&[0x42]);
+    let mut out = Vec::<u8>::new();
+    buf.digest_pad_bits(0b1011_0111, 3, &[0x0b], |block| out.extend(block));
+    assert_eq!(out, [0x42, 0b1011_0000, 0, 0, 0, 0, 0, 0x0b]);
+
+    let mut buf = EagerBuffer::<U8>::new(&[0x42]);
+    let mut out = Vec::<u8>::new();
+    buf.digest_pad_bits(0xFF, 0, &[0x08], |block| out.extend(block));
+    assert_eq!(out, [0x42, 0x80, 0, 0, 0, 0, 0, 0x08]);
+}
+
+/// Absorb a single block into the Keccak-f[1600] state.
+fn keccak_absorb(state: &mut [u64; 25], block: &[u8]) {
+    const RC: [u64; 24] = [
+        0x0000_0000_0000_0001,
+        0x0000_0000_0000_8082,
+        0x8000_0000_0000_808A,
+        0x8000_0000_8000_8000,
+        0x0000_0000_0000_808B,
+        0x0000_0000_8000_0001,
+        0x8000_0000_8000_8081,
+        0x8000_0000_0000_8009,
+        0x0000_0000_0000_008A,
+        0x0000_0000_0000_0088,
+        0x0000_0000_8000_8009,
+        0x0000_0000_8000_000A,
+        0x0000_0000_8000_808B,
+        0x8000_0000_0000_008B,
+        0x8000_0000_0000_8089,
+        0x8000_0000_0000_8003,
+        0x8000_0000_0000_8002,
+        0x8000_0000_0000_0080,
+        0x0000_0000_0000_800A,
+        0x8000_0000_8000_000A,
+        0x8000_0000_8000_8081,
+        0x8000_0000_0000_8080,
+        0x0000_0000_8000_0001,
+        0x8000_0000_8000_8008,
+    ];
+    const ROT: [u32; 25] = [
+        0, 1, 62, 28, 27, 36, 44, 6, 55, 20, 3, 10, 43, 25, 39, 41, 45, 15, 21, 8, 18, 2, 61, 56,
+        14,
+    ];
+
+    for (s, chunk) in state.iter_mut().zip(block.chunks(8)) {
+        let mut word = [0u8; 8];
+        word.copy_from_slice(chunk);
+        *s ^= u64::from_le_bytes(word);
+    }
+    for rc in RC.iter() {
+        let mut c = [0u64; 5];
+        for x in 0..5 {
+            c[x] = state[x] ^ state[x + 5] ^ state[x + 10] ^ state[x + 15] ^ state[x + 20];
+        }
+        for x in 0..5 {
+            let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+            for y in 0..5 {
+                state[x + 5 * y] ^= d;
+            }
+        }
+        let mut b = [0u64; 25];
+        for x in 0..5 {
+            for y in 0..5 {
+                b[y + 5 * ((2 * x + 3 * y) % 5)] = state[x + 5 * y].rotate_left(ROT[x + 5 * y]);
+            }
+        }
+        for x in 0..5 {
+            for y in 0..5 {
+                state[x + 5 * y] =
+                    b[x + 5 * y] ^ (!b[(x + 1) % 5 + 5 * y] & b[(x + 2) % 5 + 5 * y]);
+            }
+        }
+        state[0] ^= rc;
+    }
+}
+
+/// Compute SHA3-256 of a message with `bits` trailing bits in `last_byte`.
+fn sha3_256_bits(data: &[u8], last_byte: u8, bits: u8) -> [u8; 32] {
+    let mut state = [0u64; 25];
+    let mut buf = EagerBuffer::<U136>::default();
+    buf.digest_blocks(data, |blocks| {
+        blocks.iter().for_each(|b| keccak_absorb(&mut state, b))
+    });
+    buf.keccak_pad_bits(last_byte, bits, 0x06, |b| keccak_absorb(&mut state, b));
+    let mut res = [0u8; 32];
+    for (chunk, s) in res.chunks_mut(8).zip(state.iter()) {
+        chunk.copy_from_slice(&s.to_le_bytes());
+    }
+    res
+}
+
+#[test]
+fn test_keccak_pad_bits() {
+    // SHA3-256 of the empty message
+    assert_eq!(
+        sha3_256_bits(&[], 0, 0),
+        [
+            0xa7, 0xff, 0xc6, 0xf8, 0xbf, 0x1e, 0xd7, 0x66, 0x51, 0xc1, 0x47, 0x56, 0xa0, 0x61,
+            0xd6, 0x62, 0xf5, 0x80, 0xff, 0x4d, 0xe4, 0x3b, 0x49, 0xfa, 0x82, 0xd8, 0x0a, 0x4b,
+            0x80, 0xf8, 0x43, 0x4a,
+        ],
+    );
+    // NIST SHA3-256 examples for 5-bit and 30-bit messages
+    assert_eq!(
+        sha3_256_bits(&[], 0b1_0011, 5),
+        [
+            0x7b, 0x00, 0x47, 0xcf, 0x5a, 0x45, 0x68, 0x82, 0x36, 0x3c, 0xbf, 0x0f, 0xb0, 0x53,
+            0x22, 0xcf, 0x65, 0xf4, 0xb7, 0x05, 0x9a, 0x46, 0x36, 0x5e, 0x83, 0x01, 0x32, 0xe3,
+            0xb5, 0xd9, 0x57, 0xaf,
+        ],
+    );
+    assert_eq!(
+        sha3_256_bits(&[0x53, 0x58, 0x7b], 0b01_1001, 6),
+        [
+            0xc8, 0x24, 0x2f, 0xef, 0x40, 0x9e, 0x5a, 0xe9, 0xd1, 0xf1, 0xc8, 0x57, 0xae, 0x4d,
+            0xc6, 0x24, 0xb9, 0x2b, 0x19, 0x80, 0x9f, 0x62, 0xaa, 0x8c, 0x07, 0x41, 0x1c, 0x54,
+            0xa0, 0x78, 0xb1, 0xd0,
+        ],
+    );
+
+    let mut buf = EagerBuffer::<U4>::new(&[0x42]);
+    let mut out = Vec::<u8>::new();
+    buf.keccak_pad_bits(0xFF, 3, 0x06, |block| out.extend(block));
+    assert_eq!(out, [0x42, 0b0011_0111, 0, 0x80]);
+
+    // padding bits which do not fit into the last byte spill into a new block
+    let mut buf = EagerBuffer::<U4>::new(b"abc");
+    let mut out = Vec::<u8>::new();
+    buf.keccak_pad_bits(0x7F, 7, 0x06, |block| out.extend(block));
+    assert_eq!(out, [b'a', b'b', b'c', 0x7F, 0b11, 0, 0, 0x80]);
+    assert_eq!(buf.get_pos(), 0);
+
+    // first padding bit in the last bit of the block
+    let mut buf = EagerBuffer::<U4>::new(b"abc");
+    let mut out = Vec::<u8>::new();
+    buf.keccak_pad_bits(0x00, 7, 0x01, |block| out.extend(block));
+    assert_eq!(out, [b'a', b'b', b'c', 0x80, 0, 0, 0, 0x80]);
+}
+
+#[test]
+fn test_try_new() {
+    assert_eq!(
+        EagerBuffer::<U4>::try_new(b"012").unwrap().get_data(),
+        b"012"
+    );
+    assert!(EagerBuffer::<U4>::try_new(b"0123").is_err());
+    assert_eq!(
+        LazyBuffer::<U4>::try_new(b"0123").unwrap().get_data(),
+        b"0123"
+    );
+    assert!(LazyBuffer::<U4>::try_new(b"01234").is_err());
+
+    let block = Block::<U4>::clone_from_slice(b"0123");
+    let buf = EagerBuffer::<U4>::try_restore(block, 2).unwrap();
+    assert_eq!(buf.get_data(), b"01");
+    assert!(EagerBuffer::<U4>::try_restore(block, 4).is_err());
+    assert_eq!(
+        LazyBuffer::<U4>::try_restore(block, 4).unwrap().get_pos(),
+        4
+    );
+    assert!(LazyBuffer::<U4>::try_restore(block, 5).is_err());
+}
+
+#[test]
+fn test_lazy_set_data() {
+    let mut n = 0u8;
+    let mut gen = |blocks: &mut [Block<U4>]| {
+        for block in blocks {
+            block.iter_mut().for_each(|b| *b = n);
+            n += 1;
+        }
+    };
+
+    // full buffer is treated as an exhausted block
+    let mut buf = LazyBuffer::<U4>::new(b"abcd");
+    let mut out = [0u8; 5];
+    buf.set_data(&mut out, &mut gen);
+    assert_eq!(out, [0, 0, 0, 0, 1]);
+    assert_eq!(buf.get_pos(), 1);
+
+    let mut out = [0u8; 3];
+    buf.set_data(&mut out, &mut gen);
+    assert_eq!(out, [1, 1, 1]);
+    assert_eq!(buf.get_pos(), 0);
+
+    let mut out = [0u8; 2];
+    buf.set_data(&mut out, &mut gen);
+    assert_eq!(out, [2, 2]);
+    assert_eq!(n, 3);
+    assert_eq!(buf.get_pos(), 2);
+}
+
+#[test]
+fn test_read_buffer() {
+    let mut buf = ReadBuffer::<U4>::default();
+    assert_eq!(buf.remaining(), 0);
+    let mut n = 0u8;
+    let mut gen_blocks = |blocks: &mut [Block<U4>]| {
+        for block in blocks {
+            block.iter_mut().for_each(|b| *b = n);
+            n += 1;
+        }
+    };
+
+    let mut out = [0xFF; 10];
+    buf.read(&mut out[..3], &mut gen_blocks);
+    assert_eq!(buf.remaining(), 1);
+    buf.read(&mut out[3..], &mut gen_blocks);
+    assert_eq!(out, [0, 0, 0, 0, 1, 1, 1, 1, 2, 2]);
+    assert_eq!(buf.get_pos(), 2);
+
+    let mut out = [0xFF; 6];
+    buf.read(&mut out, &mut gen_blocks);
+    assert_eq!(out, [2, 2, 3, 3, 3, 3]);
+    assert_eq!(buf.remaining(), 0);
+
+    buf.read(&mut out[..1], &mut gen_blocks);
+    assert_eq!(out[0], 4);
+    buf.reset();
+    buf.read(&mut out[..1], &mut gen_blocks);
+    assert_eq!(out[0], 5);
+
+    let block = Block::<U4>::clone_from_slice(b"0123");
+    let mut buf = ReadBuffer::<U4>::try_new(block, 1).unwrap();
+    buf.read(&mut out[..3], |_| unreachable!());
+    assert_eq!(&out[..3], b"123");
+    assert!(ReadBuffer::<U4>::try_new(block, 5).is_err());
+}
+
 #[test]
 #[rustfmt::skip]
 fn test_eager_paddings() {
@@ -170,6 +444,19 @@ fn test_eager_paddings() {
         ],
     );
 
+    let mut buf = EagerBuffer::<U24>::new(&[0x42]);
+    let mut out = Vec::<u8>::new();
+    let len = 0x0001_0203_0405_0607_0809_0a0b_0c0d_0e0f;
+    buf.len128_padding_le(len, |block| out.extend(block));
+    assert_eq!(
+        out,
+        [
+            0x42, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x0f, 0x0e, 0x0d, 0x0c, 0x0b, 0x0a, 0x09, 0x08,
+            0x07, 0x06, 0x05, 0x04, 0x03, 0x02, 0x01, 0x00,
+        ],
+    );
+
     let mut buf = EagerBuffer::<U4>::new(&[0x42]);
     let mut out = Vec::<u8>::new();
     buf.digest_pad(0xff, &[0x10, 0x11, 0x12], |block| out.extend(block));
@@ -186,3 +473,119 @@ fn test_eager_paddings() {
         [0x42, 0xff, 0x10, 0x11],
     );
 }
+
+#[test]
+fn test_dyn_digest_blocks() {
+    let inputs = [&b"01234"[..], &b"5"[..], &b"6789abcdefgh"[..], &b"ij"[..]];
+
+    let mut buf = DynEagerBuffer::<U8>::new(3);
+    let mut blocks = Vec::new();
+    for input in inputs.iter() {
+        buf.digest_blocks(input, |b| {
+            assert_eq!(b.len() % 3, 0);
+            blocks.extend_from_slice(b);
+        });
+    }
+    assert_eq!(blocks, b"0123456789abcdefgh");
+    assert_eq!(buf.get_data(), b"ij");
+    assert_eq!(buf.remaining(), 1);
+    assert_eq!(buf.pad_with_zeros(), b"ij\0");
+    assert_eq!(buf.get_pos(), 0);
+
+    let mut buf = DynLazyBuffer::<U8>::new(3);
+    let mut blocks = Vec::new();
+    for input in inputs[..3].iter() {
+        buf.digest_blocks(input, |b| blocks.extend_from_slice(b));
+    }
+    assert_eq!(blocks, b"0123456789abcde");
+    assert_eq!(buf.get_data(), b"fgh");
+    assert_eq!(buf.size(), 3);
+}
+
+#[test]
+#[should_panic]
+fn test_dyn_block_size_too_big() {
+    DynEagerBuffer::<U4>::new(5);
+}
+
+#[test]
+fn test_serialize() {
+    let mut buf = EagerBuffer::<U4>::default();
+    buf.digest_blocks(b"012345", |_| {});
+    let ser = buf.serialize();
+    assert_eq!(ser[..], [b'4', b'5', 0, 0, 2]);
+    let buf2 = EagerBuffer::<U4>::deserialize(&ser).unwrap();
+    assert_eq!(buf2.get_data(), b"45");
+
+    let mut buf = LazyBuffer::<U4>::default();
+    buf.digest_blocks(b"0123", |_| {});
+    let ser = buf.serialize();
+    assert_eq!(ser[..], [b'0', b'1', b'2', b'3', 4]);
+    assert_eq!(LazyBuffer::<U4>::deserialize(&ser).unwrap().get_pos(), 4);
+    // position equal to block size is not valid for eager buffers
+    assert!(EagerBuffer::<U4>::deserialize(&ser).is_err());
+
+    let mut ser = EagerBuffer::<U4>::new(b"4").serialize();
+    ser[2] = 1;
+    assert!(EagerBuffer::<U4>::deserialize(&ser).is_err());
+    ser[2] = 0;
+    ser[4] = 5;
+    assert!(LazyBuffer::<U4>::deserialize(&ser).is_err());
+}
+
+#[cfg(feature = "zeroize")]
+#[test]
+fn test_zeroize() {
+    use zeroize::Zeroize;
+
+    let mut buf = LazyBuffer::<U4>::default();
+    buf.digest_blocks(b"secret", |_| {});
+    assert_eq!(buf.get_data(), b"et");
+    buf.zeroize();
+    assert_eq!(buf.get_pos(), 0);
+    assert_eq!(buf.pad_with_zeros()[..], [0; 4]);
+
+    let mut buf = DynLazyBuffer::<U8>::new(4);
+    buf.digest_blocks(b"secret", |_| {});
+    assert_eq!(buf.get_data(), b"et");
+    buf.zeroize();
+    assert_eq!(buf.get_pos(), 0);
+    assert_eq!(buf.size(), 4);
+    assert_eq!(buf.pad_with_zeros(), [0; 4]);
+}
+
+#[cfg(all(feature = "zeroize", feature = "const-generics"))]
+#[test]
+fn test_const_zeroize() {
+    use block_buffer::ConstLazyBuffer;
+    use zeroize::Zeroize;
+
+    let mut buf = ConstLazyBuffer::<4>::default();
+    buf.digest_blocks(b"secret", |_| {});
+    assert_eq!(buf.get_data(), b"et");
+    buf.zeroize();
+    assert_eq!(buf.get_pos(), 0);
+    assert_eq!(buf.pad_with_zeros(), &[0; 4]);
+}
+
+#[cfg(feature = "const-generics")]
+#[test]
+fn test_const_buffer() {
+    use block_buffer::{ConstEagerBuffer, ConstLazyBuffer};
+
+    let mut buf = ConstLazyBuffer::<4>::default();
+    let mut n = 0;
+    buf.digest_blocks(b"01234567", |b| n += b.len());
+    assert_eq!((n, buf.get_data()), (1, &b"4567"[..]));
+
+    let typed = buf.to_block_buffer::<U4>();
+    assert_eq!(typed.get_data(), b"4567");
+    let buf2 = ConstLazyBuffer::<4>::from_block_buffer(&typed);
+    assert_eq!(buf2.get_data(), b"4567");
+
+    let mut buf = ConstEagerBuffer::<16>::new(b"abc");
+    let mut out = [0u8; 16];
+    buf.len64_padding_be(24, |b| out = *b);
+    assert_eq!(&out, b"abc\x80\0\0\0\0\0\0\0\0\0\0\0\x18");
+    assert_eq!(buf.get_pos(), 0);
+}
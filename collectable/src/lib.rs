@@ -2,6 +2,10 @@
 //! various collection types. In particular, it supports traits for fallible
 //! operations (such as [`TryExtend`]) which are useful for supporting
 //! fixed-capacity containers, such as `heapless::Vec` or `tinyvec::Array`.
+//!
+//! Impls for `heapless` and `arrayvec` containers are enabled by the crate
+//! features with the same names. Both crates use const generics, so these
+//! features require Rust 1.51.
 
 #![no_std]
 #![doc(
@@ -12,6 +16,11 @@
 #[cfg(feature = "alloc")]
 extern crate alloc;
 
+#[cfg(feature = "std")]
+extern crate std;
+
+use core::fmt;
+
 /// Collection types implement all of the traits in this crate.
 pub trait Collection<T>:
     AsRef<[T]> + AsMut<[T]> + Default + Length + Truncate + TryExtend<T> + TryPush<T>
@@ -38,6 +47,45 @@ pub trait Truncate {
     fn truncate(&mut self, len: usize);
 }
 
+/// Query and reserve capacity of a collection.
+///
+/// Allows to check whether a collection has enough space before beginning
+/// a multi-element insertion instead of failing halfway.
+///
+/// The trait is not implemented for `alloc` collections: their `reserve`
+/// methods abort on allocation failure, while fallible `try_reserve` methods
+/// require Rust 1.57, which is newer than MSRV of this crate.
+pub trait TryReserve: Length {
+    /// Error type.
+    type Error;
+
+    /// Get the number of elements this collection can hold without
+    /// reallocating or, for fixed-capacity containers, at all.
+    fn capacity(&self) -> usize;
+
+    /// Get the number of elements which can be added to this collection
+    /// without reallocating or, for fixed-capacity containers, at all.
+    fn remaining_capacity(&self) -> usize {
+        self.capacity() - self.len()
+    }
+
+    /// Try to reserve capacity for at least `additional` more elements.
+    ///
+    /// Fixed-capacity containers return an error if `additional` exceeds
+    /// [`TryReserve::remaining_capacity`].
+    fn try_reserve(&mut self, additional: usize) -> Result<(), Self::Error>;
+}
+
+/// Error returned when the capacity of a fixed-capacity container is exceeded.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct CapacityError;
+
+impl fmt::Display for CapacityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("collection capacity exceeded")
+    }
+}
+
 /// Fallible equivalent of [`core::iter::Extend`] - extends a collection
 /// with the contents of an iterator, but with the option to return an error
 /// in the event the container's capacity has been exceeded.
@@ -59,6 +107,49 @@ pub trait TryExtend<A> {
     {
         self.try_extend(slice.iter().cloned())
     }
+
+    /// Try to extend the collection from the given iterator of fallible
+    /// items, e.g. one produced by a parser.
+    ///
+    /// Stops on the first item error or when the container's capacity has
+    /// been exceeded, whichever comes first. Elements consumed before that
+    /// are kept in the collection.
+    fn try_extend_from_fallible<T, E>(&mut self, iter: T) -> Result<(), ExtendError<E, Self::Error>>
+    where
+        T: IntoIterator<Item = Result<A, E>>,
+    {
+        let mut item_err = None;
+        let items = iter.into_iter().scan((), |_, item| match item {
+            Ok(item) => Some(item),
+            Err(err) => {
+                item_err = Some(err);
+                None
+            }
+        });
+        self.try_extend(items).map_err(ExtendError::Capacity)?;
+        match item_err {
+            Some(err) => Err(ExtendError::Item(err)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Error returned by [`TryExtend::try_extend_from_fallible`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ExtendError<E, C> {
+    /// Iterator has produced an error.
+    Item(E),
+    /// Collection has returned an error, e.g. its capacity has been exceeded.
+    Capacity(C),
+}
+
+impl<E: fmt::Display, C: fmt::Display> fmt::Display for ExtendError<E, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExtendError::Item(err) => write!(f, "iterator error: {}", err),
+            ExtendError::Capacity(err) => write!(f, "collection error: {}", err),
+        }
+    }
 }
 
 /// Try to build a collection type from an [`Iterator`].
@@ -143,4 +234,391 @@ mod vec_impls {
             Ok(())
         }
     }
+
+    impl<'a> TryExtend<&'a [u8]> for Vec<u8> {
+        type Error = Infallible;
+
+        fn try_extend<T: IntoIterator<Item = &'a [u8]>>(
+            &mut self,
+            iter: T,
+        ) -> Result<(), Infallible> {
+            for chunk in iter {
+                Vec::extend_from_slice(self, chunk);
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+mod string_impls {
+    use super::{Length, Truncate, TryExtend, TryPush};
+    use alloc::string::String;
+    use core::convert::Infallible;
+
+    impl Length for String {
+        fn len(&self) -> usize {
+            String::len(self)
+        }
+    }
+
+    impl Truncate for String {
+        fn truncate(&mut self, len: usize) {
+            String::truncate(self, len);
+        }
+    }
+
+    impl TryExtend<char> for String {
+        type Error = Infallible;
+
+        fn try_extend<T: IntoIterator<Item = char>>(&mut self, iter: T) -> Result<(), Infallible> {
+            String::extend(self, iter);
+            Ok(())
+        }
+    }
+
+    impl<'a> TryExtend<&'a str> for String {
+        type Error = Infallible;
+
+        fn try_extend<T: IntoIterator<Item = &'a str>>(
+            &mut self,
+            iter: T,
+        ) -> Result<(), Infallible> {
+            String::extend(self, iter);
+            Ok(())
+        }
+    }
+
+    impl TryPush<char> for String {
+        fn try_push(&mut self, c: char) -> Result<(), char> {
+            String::push(self, c);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+mod os_string_impls {
+    use super::{Length, TryExtend, TryPush};
+    use core::convert::Infallible;
+    use std::ffi::{OsStr, OsString};
+
+    impl Length for OsString {
+        fn len(&self) -> usize {
+            OsStr::len(self)
+        }
+    }
+
+    impl<A: AsRef<OsStr>> TryExtend<A> for OsString {
+        type Error = Infallible;
+
+        fn try_extend<T: IntoIterator<Item = A>>(&mut self, iter: T) -> Result<(), Infallible> {
+            for s in iter {
+                OsString::push(self, s);
+            }
+            Ok(())
+        }
+    }
+
+    impl<A: AsRef<OsStr>> TryPush<A> for OsString {
+        fn try_push(&mut self, s: A) -> Result<(), A> {
+            OsString::push(self, s);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+mod vec_deque_impls {
+    use super::{Length, Truncate, TryExtend, TryPush};
+    use alloc::collections::VecDeque;
+    use core::convert::Infallible;
+
+    impl<T> Length for VecDeque<T> {
+        fn len(&self) -> usize {
+            VecDeque::len(self)
+        }
+    }
+
+    impl<T> Truncate for VecDeque<T> {
+        fn truncate(&mut self, len: usize) {
+            VecDeque::truncate(self, len);
+        }
+    }
+
+    impl<A> TryExtend<A> for VecDeque<A> {
+        type Error = Infallible;
+
+        fn try_extend<T: IntoIterator<Item = A>>(&mut self, iter: T) -> Result<(), Infallible> {
+            VecDeque::extend(self, iter);
+            Ok(())
+        }
+    }
+
+    impl<T> TryPush<T> for VecDeque<T> {
+        fn try_push(&mut self, item: T) -> Result<(), T> {
+            VecDeque::push_back(self, item);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+mod binary_heap_impls {
+    use super::{Length, TryExtend, TryPush};
+    use alloc::collections::BinaryHeap;
+    use core::convert::Infallible;
+
+    impl<T> Length for BinaryHeap<T> {
+        fn len(&self) -> usize {
+            BinaryHeap::len(self)
+        }
+    }
+
+    impl<A: Ord> TryExtend<A> for BinaryHeap<A> {
+        type Error = Infallible;
+
+        fn try_extend<T: IntoIterator<Item = A>>(&mut self, iter: T) -> Result<(), Infallible> {
+            BinaryHeap::extend(self, iter);
+            Ok(())
+        }
+    }
+
+    impl<T: Ord> TryPush<T> for BinaryHeap<T> {
+        fn try_push(&mut self, item: T) -> Result<(), T> {
+            BinaryHeap::push(self, item);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "heapless")]
+mod heapless_impls {
+    use super::{CapacityError, Collection, Length, Truncate, TryExtend, TryPush, TryReserve};
+    use heapless::{String, Vec};
+
+    impl<T, const N: usize> Collection<T> for Vec<T, N> {}
+
+    impl<T, const N: usize> Length for Vec<T, N> {
+        fn len(&self) -> usize {
+            self.as_slice().len()
+        }
+    }
+
+    impl<T, const N: usize> Truncate for Vec<T, N> {
+        fn truncate(&mut self, len: usize) {
+            Vec::truncate(self, len);
+        }
+    }
+
+    impl<A, const N: usize> TryExtend<A> for Vec<A, N> {
+        type Error = CapacityError;
+
+        /// Elements pushed before the capacity was exceeded are kept.
+        fn try_extend<T: IntoIterator<Item = A>>(&mut self, iter: T) -> Result<(), CapacityError> {
+            for item in iter {
+                self.push(item).map_err(|_| CapacityError)?;
+            }
+            Ok(())
+        }
+
+        fn try_extend_from_slice(&mut self, slice: &[A]) -> Result<(), CapacityError>
+        where
+            A: Clone,
+        {
+            self.extend_from_slice(slice).map_err(|_| CapacityError)
+        }
+    }
+
+    impl<T, const N: usize> TryPush<T> for Vec<T, N> {
+        fn try_push(&mut self, item: T) -> Result<(), T> {
+            self.push(item)
+        }
+    }
+
+    impl<T, const N: usize> TryReserve for Vec<T, N> {
+        type Error = CapacityError;
+
+        fn capacity(&self) -> usize {
+            Vec::capacity(self)
+        }
+
+        fn try_reserve(&mut self, additional: usize) -> Result<(), CapacityError> {
+            if additional <= self.remaining_capacity() {
+                Ok(())
+            } else {
+                Err(CapacityError)
+            }
+        }
+    }
+
+    impl<const N: usize> Length for String<N> {
+        fn len(&self) -> usize {
+            self.as_str().len()
+        }
+    }
+
+    impl<const N: usize> Truncate for String<N> {
+        fn truncate(&mut self, len: usize) {
+            String::truncate(self, len);
+        }
+    }
+
+    impl<const N: usize> TryExtend<char> for String<N> {
+        type Error = CapacityError;
+
+        /// Characters pushed before the capacity was exceeded are kept.
+        fn try_extend<T: IntoIterator<Item = char>>(
+            &mut self,
+            iter: T,
+        ) -> Result<(), CapacityError> {
+            for c in iter {
+                self.push(c).map_err(|_| CapacityError)?;
+            }
+            Ok(())
+        }
+    }
+
+    impl<'a, const N: usize> TryExtend<&'a str> for String<N> {
+        type Error = CapacityError;
+
+        /// Strings pushed before the capacity was exceeded are kept.
+        fn try_extend<T: IntoIterator<Item = &'a str>>(
+            &mut self,
+            iter: T,
+        ) -> Result<(), CapacityError> {
+            for s in iter {
+                self.push_str(s).map_err(|_| CapacityError)?;
+            }
+            Ok(())
+        }
+    }
+
+    impl<const N: usize> TryPush<char> for String<N> {
+        fn try_push(&mut self, c: char) -> Result<(), char> {
+            self.push(c).map_err(|_| c)
+        }
+    }
+
+    impl<const N: usize> TryReserve for String<N> {
+        type Error = CapacityError;
+
+        fn capacity(&self) -> usize {
+            String::capacity(self)
+        }
+
+        fn try_reserve(&mut self, additional: usize) -> Result<(), CapacityError> {
+            if additional <= self.remaining_capacity() {
+                Ok(())
+            } else {
+                Err(CapacityError)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "arrayvec")]
+mod arrayvec_impls {
+    use super::{CapacityError, Collection, Length, Truncate, TryExtend, TryPush, TryReserve};
+    use arrayvec::ArrayVec;
+
+    impl<T, const CAP: usize> Collection<T> for ArrayVec<T, CAP> {}
+
+    impl<T, const CAP: usize> Length for ArrayVec<T, CAP> {
+        fn len(&self) -> usize {
+            ArrayVec::len(self)
+        }
+    }
+
+    impl<T, const CAP: usize> Truncate for ArrayVec<T, CAP> {
+        fn truncate(&mut self, len: usize) {
+            ArrayVec::truncate(self, len);
+        }
+    }
+
+    impl<A, const CAP: usize> TryExtend<A> for ArrayVec<A, CAP> {
+        type Error = CapacityError;
+
+        /// Elements pushed before the capacity was exceeded are kept.
+        fn try_extend<T: IntoIterator<Item = A>>(&mut self, iter: T) -> Result<(), CapacityError> {
+            for item in iter {
+                self.try_push(item).map_err(|_| CapacityError)?;
+            }
+            Ok(())
+        }
+    }
+
+    impl<T, const CAP: usize> TryPush<T> for ArrayVec<T, CAP> {
+        fn try_push(&mut self, item: T) -> Result<(), T> {
+            ArrayVec::try_push(self, item).map_err(|err| err.element())
+        }
+    }
+
+    impl<T, const CAP: usize> TryReserve for ArrayVec<T, CAP> {
+        type Error = CapacityError;
+
+        fn capacity(&self) -> usize {
+            ArrayVec::capacity(self)
+        }
+
+        fn try_reserve(&mut self, additional: usize) -> Result<(), CapacityError> {
+            if additional <= self.remaining_capacity() {
+                Ok(())
+            } else {
+                Err(CapacityError)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "smallvec")]
+mod smallvec_impls {
+    use super::{Collection, Length, Truncate, TryExtend, TryPush, TryReserve};
+    use core::convert::Infallible;
+    use smallvec::{Array, CollectionAllocErr, SmallVec};
+
+    impl<A: Array> Collection<A::Item> for SmallVec<A> {}
+
+    impl<A: Array> Length for SmallVec<A> {
+        fn len(&self) -> usize {
+            SmallVec::len(self)
+        }
+    }
+
+    impl<A: Array> Truncate for SmallVec<A> {
+        fn truncate(&mut self, len: usize) {
+            SmallVec::truncate(self, len);
+        }
+    }
+
+    impl<A: Array> TryExtend<A::Item> for SmallVec<A> {
+        type Error = Infallible;
+
+        fn try_extend<T: IntoIterator<Item = A::Item>>(
+            &mut self,
+            iter: T,
+        ) -> Result<(), Infallible> {
+            SmallVec::extend(self, iter);
+            Ok(())
+        }
+    }
+
+    impl<A: Array> TryPush<A::Item> for SmallVec<A> {
+        fn try_push(&mut self, item: A::Item) -> Result<(), A::Item> {
+            SmallVec::push(self, item);
+            Ok(())
+        }
+    }
+
+    impl<A: Array> TryReserve for SmallVec<A> {
+        type Error = CollectionAllocErr;
+
+        fn capacity(&self) -> usize {
+            SmallVec::capacity(self)
+        }
+
+        fn try_reserve(&mut self, additional: usize) -> Result<(), CollectionAllocErr> {
+            SmallVec::try_reserve(self, additional)
+        }
+    }
 }
@@ -0,0 +1,295 @@
+use collectable::{CapacityError, ExtendError, TryExtend};
+
+/// Stream of parsed items which fails on the item with index `err_at`.
+fn stream(len: u8, err_at: Option<u8>) -> impl Iterator<Item = Result<u8, &'static str>> {
+    (0..len).map(move |i| {
+        if Some(i) == err_at {
+            Err("invalid item")
+        } else {
+            Ok(i)
+        }
+    })
+}
+
+/// Collection which holds at most 4 elements.
+#[derive(Default)]
+struct Bounded(Vec<u8>);
+
+impl TryExtend<u8> for Bounded {
+    type Error = CapacityError;
+
+    fn try_extend<T: IntoIterator<Item = u8>>(&mut self, iter: T) -> Result<(), CapacityError> {
+        for item in iter {
+            if self.0.len() == 4 {
+                return Err(CapacityError);
+            }
+            self.0.push(item);
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn from_fallible() {
+    let mut buf = Bounded::default();
+    assert_eq!(buf.try_extend_from_fallible(stream(3, None)), Ok(()));
+    assert_eq!(buf.0, [0, 1, 2]);
+}
+
+#[test]
+fn from_fallible_item_error_first() {
+    let mut buf = Bounded::default();
+    let res = buf.try_extend_from_fallible(stream(8, Some(2)));
+    assert_eq!(res, Err(ExtendError::Item("invalid item")));
+    assert_eq!(buf.0, [0, 1]);
+}
+
+#[test]
+fn from_fallible_capacity_first() {
+    let mut buf = Bounded::default();
+    let res = buf.try_extend_from_fallible(stream(8, Some(6)));
+    assert_eq!(res, Err(ExtendError::Capacity(CapacityError)));
+    assert_eq!(buf.0, [0, 1, 2, 3]);
+}
+
+#[test]
+fn from_fallible_keeps_items() {
+    let mut buf = Bounded(vec![9]);
+    let res = buf.try_extend_from_fallible(stream(8, Some(1)));
+    assert_eq!(res, Err(ExtendError::Item("invalid item")));
+    assert_eq!(buf.0, [9, 0]);
+    let res = buf.try_extend_from_fallible(stream(8, None));
+    assert_eq!(res, Err(ExtendError::Capacity(CapacityError)));
+    assert_eq!(buf.0, [9, 0, 0, 1]);
+}
+
+#[test]
+fn extend_error_display() {
+    let err: ExtendError<&str, &str> = ExtendError::Item("bad");
+    assert_eq!(err.to_string(), "iterator error: bad");
+    let err: ExtendError<&str, &str> = ExtendError::Capacity("full");
+    assert_eq!(err.to_string(), "collection error: full");
+}
+
+#[cfg(feature = "alloc")]
+mod alloc_impls {
+    use super::stream;
+    use collectable::{ExtendError, Length, Truncate, TryExtend, TryPush};
+    use std::collections::{BinaryHeap, VecDeque};
+
+    #[test]
+    fn vec_from_fallible() {
+        let mut vec = Vec::new();
+        assert_eq!(vec.try_extend_from_fallible(stream(5, None)), Ok(()));
+        assert_eq!(vec, [0, 1, 2, 3, 4]);
+
+        let mut vec = vec![10];
+        let res = vec.try_extend_from_fallible(stream(5, Some(3)));
+        assert_eq!(res, Err(ExtendError::Item("invalid item")));
+        assert_eq!(vec, [10, 0, 1, 2]);
+    }
+
+    #[test]
+    fn vec_from_byte_slices() {
+        let mut vec = b"ab".to_vec();
+        let chunks: [&[u8]; 3] = [b"cd", b"", b"e"];
+        assert_eq!(vec.try_extend(chunks.iter().cloned()), Ok(()));
+        assert_eq!(vec, b"abcde");
+        Truncate::truncate(&mut vec, 1);
+        assert_eq!(Length::len(&vec), 1);
+    }
+
+    #[test]
+    fn string() {
+        let mut s = String::from("a");
+        assert_eq!(s.try_extend("bc".chars()), Ok(()));
+        assert_eq!(s.try_extend(["de", "f"].iter().cloned()), Ok(()));
+        assert_eq!(s.try_push('g'), Ok(()));
+        assert_eq!(s, "abcdefg");
+
+        let chars = "hij".chars().map(|c| if c == 'j' { Err(c) } else { Ok(c) });
+        assert_eq!(
+            s.try_extend_from_fallible(chars),
+            Err(ExtendError::Item('j'))
+        );
+        assert_eq!(s, "abcdefghi");
+    }
+
+    #[test]
+    fn vec_deque() {
+        let mut deque = VecDeque::new();
+        assert_eq!(deque.try_extend(0..2), Ok(()));
+        assert_eq!(deque.try_push(7), Ok(()));
+        assert_eq!(deque, [0, 1, 7]);
+        Truncate::truncate(&mut deque, 2);
+        assert_eq!(Length::len(&deque), 2);
+    }
+
+    #[test]
+    fn binary_heap() {
+        let mut heap = BinaryHeap::new();
+        assert_eq!(heap.try_extend(vec![3, 0, 1]), Ok(()));
+        assert_eq!(heap.try_push(7), Ok(()));
+        assert_eq!(Length::len(&heap), 4);
+        assert_eq!(heap.into_sorted_vec(), [0, 1, 3, 7]);
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn os_string() {
+    use collectable::{Length, TryPush};
+    use std::ffi::OsString;
+
+    let mut s = OsString::from("a");
+    assert_eq!(s.try_extend(["b", "cd"].iter()), Ok(()));
+    assert_eq!(s.try_push("e"), Ok(()));
+    assert_eq!(s, "abcde");
+    assert_eq!(Length::len(&s), 5);
+}
+
+#[cfg(feature = "heapless")]
+mod heapless_impls {
+    use super::stream;
+    use collectable::{
+        CapacityError, ExtendError, Length, Truncate, TryExtend, TryPush, TryReserve,
+    };
+    use heapless::{String, Vec};
+
+    #[test]
+    fn vec() {
+        let mut vec = Vec::<u8, 4>::new();
+        assert_eq!(vec.try_extend(0..6), Err(CapacityError));
+        assert_eq!(vec.as_slice(), [0, 1, 2, 3]);
+        assert_eq!(vec.try_push(4), Err(4));
+        Truncate::truncate(&mut vec, 2);
+        assert_eq!(Length::len(&vec), 2);
+        assert_eq!(vec.try_push(4), Ok(()));
+        assert_eq!(vec.as_slice(), [0, 1, 4]);
+    }
+
+    #[test]
+    fn vec_from_slice() {
+        let mut vec = Vec::<u8, 4>::new();
+        assert_eq!(vec.try_extend_from_slice(&[1, 2, 3]), Ok(()));
+        assert_eq!(vec.try_extend_from_slice(&[4, 5]), Err(CapacityError));
+        assert_eq!(vec.as_slice(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn vec_item_error_first() {
+        let mut vec = Vec::<u8, 4>::new();
+        let res = vec.try_extend_from_fallible(stream(6, Some(2)));
+        assert_eq!(res, Err(ExtendError::Item("invalid item")));
+        assert_eq!(vec.as_slice(), [0, 1]);
+    }
+
+    #[test]
+    fn vec_capacity_first() {
+        let mut vec = Vec::<u8, 4>::new();
+        let res = vec.try_extend_from_fallible(stream(6, Some(5)));
+        assert_eq!(res, Err(ExtendError::Capacity(CapacityError)));
+        assert_eq!(vec.as_slice(), [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn vec_reserve() {
+        let mut vec = Vec::<u8, 4>::new();
+        vec.try_extend(0..3).unwrap();
+        assert_eq!(TryReserve::capacity(&vec), 4);
+        assert_eq!(vec.remaining_capacity(), 1);
+        assert_eq!(TryReserve::try_reserve(&mut vec, 1), Ok(()));
+        assert_eq!(TryReserve::try_reserve(&mut vec, 2), Err(CapacityError));
+    }
+
+    #[test]
+    fn string() {
+        let mut s = String::<4>::new();
+        assert_eq!(s.try_extend("ab".chars()), Ok(()));
+        assert_eq!(s.try_push('c'), Ok(()));
+        assert_eq!(s.try_extend(["d", "e"].iter().cloned()), Err(CapacityError));
+        assert_eq!(s.as_str(), "abcd");
+        assert_eq!(s.try_push('f'), Err('f'));
+        assert_eq!(Length::len(&s), 4);
+    }
+
+    #[test]
+    fn string_from_fallible() {
+        let mut s = String::<4>::new();
+        let chars = "abcdef".chars().map(Ok::<char, ()>);
+        let res = s.try_extend_from_fallible(chars);
+        assert_eq!(res, Err(ExtendError::Capacity(CapacityError)));
+        assert_eq!(s.as_str(), "abcd");
+    }
+
+    #[test]
+    fn string_reserve() {
+        let mut s = String::<4>::new();
+        s.try_extend("abc".chars()).unwrap();
+        assert_eq!(s.remaining_capacity(), 1);
+        assert_eq!(TryReserve::try_reserve(&mut s, 1), Ok(()));
+        assert_eq!(TryReserve::try_reserve(&mut s, 2), Err(CapacityError));
+    }
+}
+
+#[cfg(feature = "arrayvec")]
+mod arrayvec_impls {
+    use super::stream;
+    use arrayvec::ArrayVec;
+    use collectable::{
+        CapacityError, ExtendError, Length, Truncate, TryExtend, TryPush, TryReserve,
+    };
+
+    #[test]
+    fn item_error_first() {
+        let mut vec = ArrayVec::<u8, 4>::new();
+        let res = vec.try_extend_from_fallible(stream(6, Some(1)));
+        assert_eq!(res, Err(ExtendError::Item("invalid item")));
+        assert_eq!(vec.as_slice(), [0]);
+    }
+
+    #[test]
+    fn capacity_first() {
+        let mut vec = ArrayVec::<u8, 4>::new();
+        let res = vec.try_extend_from_fallible(stream(6, None));
+        assert_eq!(res, Err(ExtendError::Capacity(CapacityError)));
+        assert_eq!(vec.as_slice(), [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn push_and_reserve() {
+        let mut vec = ArrayVec::<u8, 2>::new();
+        assert_eq!(TryPush::try_push(&mut vec, 1), Ok(()));
+        assert_eq!(TryReserve::try_reserve(&mut vec, 1), Ok(()));
+        assert_eq!(TryReserve::try_reserve(&mut vec, 2), Err(CapacityError));
+        assert_eq!(TryPush::try_push(&mut vec, 2), Ok(()));
+        assert_eq!(TryPush::try_push(&mut vec, 3), Err(3));
+        Truncate::truncate(&mut vec, 1);
+        assert_eq!(Length::len(&vec), 1);
+        assert_eq!(TryReserve::remaining_capacity(&vec), 1);
+    }
+}
+
+#[cfg(feature = "smallvec")]
+mod smallvec_impls {
+    use super::stream;
+    use collectable::{ExtendError, Length, TryExtend, TryPush, TryReserve};
+    use smallvec::SmallVec;
+
+    #[test]
+    fn from_fallible() {
+        let mut vec = SmallVec::<[u8; 2]>::default();
+        let res = vec.try_extend_from_fallible(stream(6, Some(4)));
+        assert_eq!(res, Err(ExtendError::Item("invalid item")));
+        assert_eq!(vec.as_ref(), [0, 1, 2, 3]);
+        assert_eq!(TryPush::try_push(&mut vec, 9), Ok(()));
+        assert_eq!(Length::len(&vec), 5);
+    }
+
+    #[test]
+    fn reserve() {
+        let mut vec = SmallVec::<[u8; 2]>::default();
+        assert!(TryReserve::try_reserve(&mut vec, 16).is_ok());
+        assert!(TryReserve::capacity(&vec) >= 16);
+    }
+}
@@ -0,0 +1,129 @@
+//! Deferred decoding of macro-expanded inputs.
+//!
+//! Stable procedural macros can not expand macros passed to them, so inputs
+//! such as `concat!(..)` or `include_str!(..)` can not be decoded while the
+//! `hex!` macro itself is expanded. Instead the macro emits a `const` block
+//! which decodes the expanded string literals during constant evaluation.
+//! Invalid inputs result in a constant evaluation (i.e. compilation) error.
+
+use proc_macro::{Delimiter, Group, TokenStream, TokenTree};
+
+use crate::{Case, Options};
+
+/// Decoder evaluated at compile time, mirrors `HexIter` and comment
+/// exclusion implemented by `ExcludingComments`.
+///
+/// Comment exclusion states: 0 - normal, 1 - potential comment,
+/// 2 - line comment, 3 - block comment, 4 - potential block comment end.
+const DECODE_FN: &str = r#"
+const fn __hex_decode<const N: usize>(
+    inputs: &[&[u8]],
+    case: u8,
+    reverse: bool,
+) -> ([u8; N], usize) {
+    let mut out = [0u8; N];
+    let mut len = 0;
+    let mut i = 0;
+    while i < inputs.len() {
+        let buf = inputs[i];
+        let mut state = 0;
+        let mut high = None;
+        let mut j = 0;
+        while j < buf.len() {
+            let c = buf[j];
+            j += 1;
+            match (state, c) {
+                (0, b'/') => state = 1,
+                (0, _) => {}
+                (1, b'/') => state = 2,
+                (1, b'*') => state = 3,
+                (1, _) => panic!("encountered isolated `/`"),
+                (2, b'\n') => state = 0,
+                (3, b'*') => state = 4,
+                (4, b'/') => state = 0,
+                (4, _) => state = 3,
+                _ => {}
+            }
+            if state != 0 || c == b'/' {
+                continue;
+            }
+            let v = match c {
+                b'0'..=b'9' => c - 48,
+                b'A'..=b'F' if case == 2 => panic!("encountered upper case character"),
+                b'a'..=b'f' if case == 1 => panic!("encountered lower case character"),
+                b'A'..=b'F' => c - 55,
+                b'a'..=b'f' => c - 87,
+                b' ' | b'\r' | b'\n' | b'\t' => continue,
+                0..=127 => panic!("encountered invalid character"),
+                _ => panic!("encountered invalid non-ASCII character"),
+            };
+            match high {
+                Some(h) => {
+                    if len < N {
+                        let pos = if reverse { N - 1 - len } else { len };
+                        out[pos] = (h << 4) + v;
+                    }
+                    len += 1;
+                    high = None;
+                }
+                None => high = Some(v),
+            }
+        }
+        match state {
+            1 => panic!("encountered isolated `/`"),
+            3 | 4 => panic!("block comment not terminated with */"),
+            _ => {}
+        }
+        if high.is_some() {
+            panic!("expected even number of hex characters");
+        }
+        i += 1;
+    }
+    (out, len)
+}
+"#;
+
+/// Emits a block expression decoding `inputs` (expressions evaluating to
+/// `&str`) during constant evaluation.
+pub(crate) fn expand(inputs: Vec<TokenStream>, options: Options) -> TokenStream {
+    let mut list = TokenStream::new();
+    for input in inputs {
+        list.extend(Some(TokenTree::Group(Group::new(
+            Delimiter::Parenthesis,
+            input,
+        ))));
+        list.extend(parse(".as_bytes(),"));
+    }
+    let case = match options.case {
+        Case::Any => 0,
+        Case::Upper => 1,
+        Case::Lower => 2,
+    };
+    let args = format!("__HEX_INPUTS, {}, {}", case, options.reverse);
+
+    let mut block = parse(DECODE_FN);
+    block.extend(parse("const __HEX_INPUTS: &[&[u8]] = &"));
+    block.extend(Some(TokenTree::Group(Group::new(Delimiter::Bracket, list))));
+    block.extend(parse(";"));
+    block.extend(parse(&match options.len {
+        Some(len) => format!(
+            "const __HEX_LEN: usize = {};
+            const _: () = assert!(
+                __hex_decode::<0>({}).1 == __HEX_LEN,
+                \"unexpected length of decoded data\",
+            );",
+            len, args,
+        ),
+        None => format!("const __HEX_LEN: usize = __hex_decode::<0>({}).1;", args),
+    }));
+    block.extend(parse(&format!(
+        "const __HEX_OUT: [u8; __HEX_LEN] = __hex_decode::<__HEX_LEN>({}).0;
+        __HEX_OUT",
+        args,
+    )));
+    TokenStream::from(TokenTree::Group(Group::new(Delimiter::Brace, block)))
+}
+
+fn parse(src: &str) -> TokenStream {
+    src.parse().expect("valid Rust tokens")
+}
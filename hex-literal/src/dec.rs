@@ -0,0 +1,123 @@
+//! Conversion of arbitrary-precision decimal numbers into bytes.
+
+use std::vec::IntoIter;
+
+use crate::comments::ExcludingComments;
+
+/// Iterator over decimal digit values of a string literal's content.
+pub(crate) struct DecIter {
+    buf: ExcludingComments<IntoIter<u8>>,
+}
+
+impl DecIter {
+    pub(crate) fn new(buf: ExcludingComments<IntoIter<u8>>) -> Self {
+        Self { buf }
+    }
+}
+
+impl Iterator for DecIter {
+    type Item = u8;
+
+    /// Parses a single decimal digit, ignoring whitespace and `_` separators.
+    ///
+    /// # Panics
+    /// This panics if a non-digit, non-whitespace character is encountered.
+    fn next(&mut self) -> Option<u8> {
+        loop {
+            let v = self.buf.next()?;
+            return match v {
+                b'0'..=b'9' => Some(v - b'0'),
+                b' ' | b'\r' | b'\n' | b'\t' | b'_' => continue,
+                0..=127 => panic!("encountered invalid character: `{}`", v as char),
+                _ => panic!("encountered invalid non-ASCII character"),
+            };
+        }
+    }
+}
+
+/// Converts decimal digits (most significant first) into the minimal
+/// big-endian byte representation of the number.
+///
+/// Zero is represented by a single zero byte.
+///
+/// # Panics
+/// This panics if `digits` is empty.
+pub(crate) fn to_be_bytes(digits: &[u8]) -> Vec<u8> {
+    if digits.is_empty() {
+        panic!("expected at least one decimal digit");
+    }
+    // little-endian bytes of the number
+    let mut bytes: Vec<u8> = Vec::new();
+    for &d in digits {
+        let mut carry = d as u16;
+        for b in bytes.iter_mut() {
+            let v = (*b as u16) * 10 + carry;
+            *b = v as u8;
+            carry = v >> 8;
+        }
+        if carry != 0 {
+            bytes.push(carry as u8);
+        }
+    }
+    if bytes.is_empty() {
+        bytes.push(0);
+    }
+    bytes.reverse();
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::comments::Exclude;
+
+    fn digits(input: &str) -> Vec<u8> {
+        DecIter::new(
+            input
+                .to_string()
+                .into_bytes()
+                .into_iter()
+                .exclude_comments(),
+        )
+        .collect()
+    }
+
+    #[test]
+    fn parse_digits() {
+        assert_eq!(digits("0123"), [0, 1, 2, 3]);
+        assert_eq!(
+            digits("1_000 000\n42 // comment"),
+            [1, 0, 0, 0, 0, 0, 0, 4, 2]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "encountered invalid character: `a`")]
+    fn invalid_digit() {
+        digits("12a");
+    }
+
+    #[test]
+    fn small_numbers() {
+        assert_eq!(to_be_bytes(&[0]), [0]);
+        assert_eq!(to_be_bytes(&[0, 0, 0]), [0]);
+        assert_eq!(to_be_bytes(&[2, 5, 5]), [0xff]);
+        assert_eq!(to_be_bytes(&[2, 5, 6]), [0x01, 0x00]);
+        assert_eq!(to_be_bytes(&[0, 6, 5, 5, 3, 7]), [0x01, 0x00, 0x01]);
+    }
+
+    #[test]
+    fn large_number() {
+        let n = digits("12345678901234567890");
+        assert_eq!(
+            to_be_bytes(&n),
+            [0xab, 0x54, 0xa9, 0x8c, 0xeb, 0x1f, 0x0a, 0xd2]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "expected at least one decimal digit")]
+    fn empty() {
+        to_be_bytes(&[]);
+    }
+}
@@ -1,5 +1,7 @@
 //! This crate provides the `hex!` macro for converting hexadecimal string literals
-//! to a byte array at compile time.
+//! to a byte array at compile time, its byte-reversing counterpart `hex_le!`,
+//! and the `dec_bytes!` macro doing the same for decimal numbers of arbitrary
+//! size.
 //!
 //! It accepts the following characters in the input string:
 //!
@@ -44,16 +46,42 @@
 //! assert_eq!(hex!("0a0B /* multi-line
 //!                          block comments
 //!                       */ 0c0d"), [10, 11, 12, 13]);
+//!
+//! // the expected length of the array can be specified, mismatches
+//! // (e.g. truncated keys) result in a compilation error
+//! assert_eq!(hex!(4; "01020304"), [1, 2, 3, 4]);
+//!
+//! // canonical case can be enforced, mixed case input results in
+//! // a compilation error
+//! assert_eq!(hex!(upper: "A1 B2"), [0xA1, 0xB2]);
+//! assert_eq!(hex!(2; lower: "c3 d4"), [0xC3, 0xD4]);
+//!
+//! // `hex_le!` produces bytes in reversed order
+//! assert_eq!(hex_le!("0a0B 0C0d"), [13, 12, 11, 10]);
+//!
+//! // a list of equal-length rows produces a two-dimensional array
+//! let blocks: [[u8; 2]; 3] = hex!(["0001", "0203", "0405"]);
+//! assert_eq!(blocks, [[0, 1], [2, 3], [4, 5]]);
 //! # }
 //! ```
+//!
+//! Macros expanding to string literals, such as `concat!` and `include_str!`,
+//! can be used in place of literals. Note that it requires Rust 1.57 or later:
+//! ```ignore
+//! # #[macro_use] extern crate hex_literal;
+//! assert_eq!(hex!(concat!("0a0B", "0C0d")), [10, 11, 12, 13]);
+//! ```
 #![doc(
     html_logo_url = "https://raw.githubusercontent.com/RustCrypto/media/6ee8e381/logo.svg",
     html_favicon_url = "https://raw.githubusercontent.com/RustCrypto/media/6ee8e381/logo.svg"
 )]
 
 mod comments;
+mod const_eval;
+mod dec;
 extern crate proc_macro;
 
+use std::iter::Peekable;
 use std::vec::IntoIter;
 
 use proc_macro::{Delimiter, Group, Literal, Punct, Spacing, TokenStream, TokenTree};
@@ -79,42 +107,105 @@ fn ignore_groups(mut input: TokenStream) -> TokenStream {
     }
 }
 
-struct TokenTreeIter {
+type Tokens = Peekable<IntoIter<TokenTree>>;
+
+/// Collects the macro input into a peekable iterator of `TokenTree`s.
+fn tokens(input: TokenStream) -> Tokens {
+    let tokens: Vec<TokenTree> = ignore_groups(input).into_iter().collect();
+    tokens.into_iter().peekable()
+}
+
+/// Returns the contents of a given string literal with comments excluded.
+///
+/// # Panics
+/// This panics if the given `Literal` is not a string literal.
+fn string_content(input: Literal) -> ExcludingComments<IntoIter<u8>> {
+    let mut buf: Vec<u8> = input.to_string().into();
+
+    match buf.as_slice() {
+        [b'"', .., b'"'] => (),
+        _ => panic!("expected string literal, got `{}`", input),
+    };
+    buf.pop();
+    let mut iter = buf.into_iter().exclude_comments();
+    iter.next();
+    iter
+}
+
+/// Letter case accepted by the `hex!` macro.
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Case {
+    /// Both upper and lower case hex characters are accepted.
+    Any,
+    /// Only `'A'...'F'` are accepted.
+    Upper,
+    /// Only `'a'...'f'` are accepted.
+    Lower,
+}
+
+/// Options of the `hex!` family of macros.
+#[derive(Copy, Clone)]
+struct Options {
+    /// Expected number of decoded bytes.
+    len: Option<usize>,
+    /// Accepted case of hex characters.
+    case: Case,
+    /// Reverse order of the decoded bytes.
+    reverse: bool,
+}
+
+impl Options {
+    /// Parses options prefixing the macro input.
+    fn parse(tokens: &mut Tokens, reverse: bool) -> Self {
+        let len = parse_expected_len(tokens);
+        let case = parse_case(tokens);
+        Self { len, case, reverse }
+    }
+}
+
+/// Single input of the `hex!` macro.
+enum Input {
+    /// String literal decoded during macro expansion.
+    Literal(Literal),
+    /// Macro invocation expanding to a string literal.
+    Macro(TokenStream),
+}
+
+struct HexIter {
     buf: ExcludingComments<IntoIter<u8>>,
-    is_punct: bool,
+    case: Case,
 }
 
-impl TokenTreeIter {
-    /// Constructs a new `TokenTreeIter` from a given `proc_macro::Literal`.
+impl HexIter {
+    /// Constructs a new `HexIter` from a given `proc_macro::Literal`
+    /// accepting hex characters of the given case.
     ///
     /// # Panics
     /// This panics if the given `Literal` is not a string literal.
-    fn new(input: Literal) -> Self {
-        let mut buf: Vec<u8> = input.to_string().into();
-
-        match buf.as_slice() {
-            [b'"', .., b'"'] => (),
-            _ => panic!("expected string literal, got `{}`", input),
-        };
-        buf.pop();
-        let mut iter = buf.into_iter().exclude_comments();
-        iter.next();
+    fn new(input: Literal, case: Case) -> Self {
         Self {
-            buf: iter,
-            is_punct: false,
+            buf: string_content(input),
+            case,
         }
     }
 
-    /// Parses a single hex character (a-f/A-F/0-9) as a `u8` from the `TokenTreeIter`'s
+    /// Parses a single hex character (a-f/A-F/0-9) as a `u8` from the `HexIter`'s
     /// internal buffer, ignoring whitespace.
     ///
     /// # Panics
-    /// This panics if a non-hex, non-whitespace character is encountered.
+    /// This panics if a non-hex, non-whitespace character or a hex character
+    /// of the wrong case is encountered.
     fn next_hex_val(&mut self) -> Option<u8> {
         loop {
             let v = self.buf.next()?;
             let n = match v {
                 b'0'..=b'9' => v - 48,
+                b'A'..=b'F' if self.case == Case::Lower => {
+                    panic!("encountered upper case character: `{}`", v as char)
+                }
+                b'a'..=b'f' if self.case == Case::Upper => {
+                    panic!("encountered lower case character: `{}`", v as char)
+                }
                 b'A'..=b'F' => v - 55,
                 b'a'..=b'f' => v - 87,
                 b' ' | b'\r' | b'\n' | b'\t' => continue,
@@ -126,44 +217,294 @@ impl TokenTreeIter {
     }
 }
 
-impl Iterator for TokenTreeIter {
-    type Item = TokenTree;
+impl Iterator for HexIter {
+    type Item = u8;
 
-    /// Produces hex values (as `u8` literals) parsed from the `TokenTreeIter`'s
-    /// internal buffer, alternating with commas to separate the elements of the
-    /// generated array of bytes.
+    /// Produces bytes parsed from the `HexIter`'s internal buffer.
     ///
     /// # Panics
     /// This panics if the internal buffer contains an odd number of hex
     /// characters.
-    fn next(&mut self) -> Option<TokenTree> {
-        let v = if self.is_punct {
-            TokenTree::Punct(Punct::new(',', Spacing::Alone))
-        } else {
-            let p1 = self.next_hex_val()?;
-            let p2 = match self.next_hex_val() {
-                Some(v) => v,
-                None => panic!("expected even number of hex characters"),
-            };
-            let val = (p1 << 4) + p2;
-            TokenTree::Literal(Literal::u8_suffixed(val))
+    fn next(&mut self) -> Option<u8> {
+        let p1 = self.next_hex_val()?;
+        let p2 = match self.next_hex_val() {
+            Some(v) => v,
+            None => panic!("expected even number of hex characters"),
         };
-        self.is_punct = !self.is_punct;
-        Some(v)
+        Some((p1 << 4) + p2)
+    }
+}
+
+/// Parses the optional `<len>;` prefix of the macro input, returning the
+/// expected number of decoded bytes.
+///
+/// # Panics
+/// This panics if the length literal is not followed by `;` or can not be
+/// parsed as `usize`.
+fn parse_expected_len(tokens: &mut Tokens) -> Option<usize> {
+    let lit = match tokens.peek() {
+        Some(TokenTree::Literal(lit)) => lit.to_string(),
+        _ => return None,
+    };
+    if !lit.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    tokens.next();
+    match tokens.next() {
+        Some(TokenTree::Punct(p)) if p.as_char() == ';' => (),
+        _ => panic!("expected `;` after length `{}`", lit),
+    }
+    let digits = lit.trim_end_matches("usize").replace('_', "");
+    match digits.parse() {
+        Ok(len) => Some(len),
+        Err(_) => panic!("expected length to be a `usize` literal, got `{}`", lit),
+    }
+}
+
+/// Parses the optional `<mode>:` prefix of the macro input, returning the
+/// mode identifier.
+fn parse_mode(tokens: &mut Tokens) -> Option<String> {
+    let mut ahead = tokens.clone();
+    match (ahead.next(), ahead.next()) {
+        (Some(TokenTree::Ident(ident)), Some(TokenTree::Punct(p)))
+            if p.as_char() == ':' && p.spacing() == Spacing::Alone =>
+        {
+            *tokens = ahead;
+            Some(ident.to_string())
+        }
+        _ => None,
+    }
+}
+
+/// Parses the optional `upper:` or `lower:` prefix of the macro input.
+///
+/// # Panics
+/// This panics if an unknown case mode is specified.
+fn parse_case(tokens: &mut Tokens) -> Case {
+    match parse_mode(tokens).as_deref() {
+        None => Case::Any,
+        Some("upper") => Case::Upper,
+        Some("lower") => Case::Lower,
+        Some(unexpected) => panic!(
+            "expected `upper` or `lower` case mode, got `{}`",
+            unexpected
+        ),
+    }
+}
+
+/// Splits the remaining macro input into string literals and macro
+/// invocations (e.g. `concat!(..)` or `include_str!(..)`).
+///
+/// # Panics
+/// This panics if the input contains anything else.
+fn parse_inputs(tokens: Tokens) -> Vec<Input> {
+    let mut inputs = Vec::new();
+    let mut path = TokenStream::new();
+    for tt in tokens {
+        match tt {
+            TokenTree::Literal(literal) if path.is_empty() => inputs.push(Input::Literal(literal)),
+            TokenTree::Ident(_) | TokenTree::Punct(_) => path.extend(Some(tt)),
+            TokenTree::Group(group) if path.to_string().ends_with('!') => {
+                path.extend(Some(TokenTree::Group(group)));
+                inputs.push(Input::Macro(path));
+                path = TokenStream::new();
+            }
+            unexpected => panic!("expected string literal, got `{}`", unexpected),
+        }
+    }
+    if !path.is_empty() {
+        panic!("expected string literal, got `{}`", path);
     }
+    inputs
+}
+
+/// Converts decoded bytes into an array expression.
+fn to_array(bytes: &[u8]) -> TokenStream {
+    let mut out_ts = TokenStream::new();
+    for &b in bytes {
+        out_ts.extend(vec![
+            TokenTree::Literal(Literal::u8_suffixed(b)),
+            TokenTree::Punct(Punct::new(',', Spacing::Alone)),
+        ]);
+    }
+    TokenStream::from(TokenTree::Group(Group::new(Delimiter::Bracket, out_ts)))
 }
 
 /// Macro for converting sequence of string literals containing hex-encoded data
 /// into an array of bytes.
+///
+/// The literals can be prefixed with the expected length of the resulting
+/// array followed by `;`, in which case the macro fails to compile if the
+/// decoded data has a different length.
+///
+/// Adding the `upper:` or `lower:` prefix (after the length, if any) rejects
+/// hex characters of the other case.
+///
+/// Macro invocations expanding to string literals (e.g. `concat!` or
+/// `include_str!`) are accepted in place of literals. Such inputs are decoded
+/// during constant evaluation, so errors in them are reported as constant
+/// evaluation errors. Note that this functionality requires Rust 1.57 or
+/// later.
+///
+/// A bracketed list of comma-separated rows of hex data results in an array
+/// of arrays (`[[u8; N]; M]`). All rows must decode to the same number of
+/// bytes, the expected length and the case mode apply to each row.
 #[proc_macro]
 pub fn hex(input: TokenStream) -> TokenStream {
+    decode_hex(input, false)
+}
+
+/// Same as the `hex!` macro, but the resulting array contains bytes in
+/// reversed order.
+///
+/// This is useful for constants specified in big-endian hex notation, but
+/// used in little-endian byte order.
+///
+/// # Examples
+/// ```
+/// # use hex_literal::hex_le;
+/// assert_eq!(hex_le!("01020304"), [4, 3, 2, 1]);
+/// assert_eq!(hex_le!("0102" "0304"), [4, 3, 2, 1]);
+/// ```
+#[proc_macro]
+pub fn hex_le(input: TokenStream) -> TokenStream {
+    decode_hex(input, true)
+}
+
+fn decode_hex(input: TokenStream, reverse: bool) -> TokenStream {
+    let mut tokens = tokens(input);
+    let options = Options::parse(&mut tokens, reverse);
+    match tokens.peek() {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Bracket => {
+            let rows = group.stream();
+            tokens.next();
+            if let Some(unexpected) = tokens.next() {
+                panic!("unexpected token after array of rows: `{}`", unexpected);
+            }
+            decode_rows(rows, options)
+        }
+        _ => decode_row(tokens, options).0,
+    }
+}
+
+/// Decodes comma-separated rows of inputs into an array of arrays.
+///
+/// # Panics
+/// This panics if rows decoded during macro expansion have different lengths.
+fn decode_rows(input: TokenStream, options: Options) -> TokenStream {
+    let mut rows = vec![vec![]];
+    for tt in input {
+        match tt {
+            TokenTree::Punct(p) if p.as_char() == ',' => rows.push(vec![]),
+            tt => rows.last_mut().unwrap().push(tt),
+        }
+    }
+    if matches!(rows.last(), Some(row) if row.is_empty()) {
+        rows.pop();
+    }
+
     let mut out_ts = TokenStream::new();
-    for tt in ignore_groups(input) {
-        let iter = match tt {
-            TokenTree::Literal(literal) => TokenTreeIter::new(literal),
-            unexpected => panic!("expected string literal, got `{}`", unexpected),
-        };
-        out_ts.extend(iter);
+    let mut row_len = None;
+    for (i, row) in rows.into_iter().enumerate() {
+        if row.is_empty() {
+            panic!("expected hex data in row {}", i);
+        }
+        let (ts, len) = decode_row(row.into_iter().peekable(), options);
+        match (row_len, len) {
+            (Some(expected), Some(len)) if expected != len => panic!(
+                "expected rows of equal length, row {} has {} bytes instead of {}",
+                i, len, expected
+            ),
+            (None, Some(len)) => row_len = Some(len),
+            _ => {}
+        }
+        out_ts.extend(ts);
+        out_ts.extend(Some(TokenTree::Punct(Punct::new(',', Spacing::Alone))));
     }
     TokenStream::from(TokenTree::Group(Group::new(Delimiter::Bracket, out_ts)))
 }
+
+/// Decodes inputs into an array of bytes, returning the array expression and
+/// its length, if it is known during macro expansion.
+fn decode_row(tokens: Tokens, options: Options) -> (TokenStream, Option<usize>) {
+    let inputs = parse_inputs(tokens);
+    if inputs.iter().any(|input| matches!(input, Input::Macro(_))) {
+        let inputs = inputs
+            .into_iter()
+            .map(|input| match input {
+                Input::Literal(literal) => TokenStream::from(TokenTree::Literal(literal)),
+                Input::Macro(ts) => ts,
+            })
+            .collect();
+        return (const_eval::expand(inputs, options), options.len);
+    }
+
+    let mut bytes = Vec::new();
+    for input in inputs {
+        if let Input::Literal(literal) = input {
+            bytes.extend(HexIter::new(literal, options.case));
+        }
+    }
+    if let Some(len) = options.len {
+        if bytes.len() != len {
+            panic!("expected {} bytes, got {}", len, bytes.len());
+        }
+    }
+    if options.reverse {
+        bytes.reverse();
+    }
+    (to_array(&bytes), Some(bytes.len()))
+}
+
+/// Macro for converting sequence of string literals containing a decimal
+/// number of arbitrary size into an array of bytes.
+///
+/// By default the number is encoded in big-endian byte order, the `le:`
+/// prefix selects little-endian order (`be:` is accepted for explicitness).
+/// The result uses the smallest number of bytes needed to represent the
+/// number, unless the array length followed by `;` is specified before
+/// the byte order, in which case the result is zero-padded to that length.
+/// Numbers which do not fit into the specified length result in
+/// a compilation error.
+///
+/// # Examples
+/// ```
+/// # use hex_literal::dec_bytes;
+/// assert_eq!(dec_bytes!("65537"), [0x01, 0x00, 0x01]);
+/// assert_eq!(dec_bytes!(le: "258"), [0x02, 0x01]);
+/// assert_eq!(dec_bytes!(4; "258"), [0x00, 0x00, 0x01, 0x02]);
+/// assert_eq!(dec_bytes!(4; le: "258"), [0x02, 0x01, 0x00, 0x00]);
+/// ```
+#[proc_macro]
+pub fn dec_bytes(input: TokenStream) -> TokenStream {
+    let mut tokens = tokens(input);
+    let len = parse_expected_len(&mut tokens);
+    let little_endian = match parse_mode(&mut tokens).as_deref() {
+        None | Some("be") => false,
+        Some("le") => true,
+        Some(unexpected) => panic!("expected `be` or `le` byte order, got `{}`", unexpected),
+    };
+
+    let mut digits = Vec::new();
+    for tt in tokens {
+        match tt {
+            TokenTree::Literal(literal) => {
+                digits.extend(dec::DecIter::new(string_content(literal)))
+            }
+            unexpected => panic!("expected string literal, got `{}`", unexpected),
+        }
+    }
+    let mut bytes = dec::to_be_bytes(&digits);
+    if let Some(len) = len {
+        if bytes.len() > len {
+            panic!("number does not fit into {} bytes", len);
+        }
+        let mut padded = vec![0; len - bytes.len()];
+        padded.append(&mut bytes);
+        bytes = padded;
+    }
+    if little_endian {
+        bytes.reverse();
+    }
+    to_array(&bytes)
+}
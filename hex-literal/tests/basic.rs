@@ -81,3 +81,20 @@ fn allows_various_spacing() {
 fn can_use_const() {
     const _: [u8; 4] = hex!("ff d3 01 7f");
 }
+
+#[test]
+fn expected_len() {
+    assert_eq!(hex!(2; "ff e4"), [0xff, 0xe4]);
+    assert_eq!(hex!(3usize; "01" "02 03"), [0x01, 0x02, 0x03]);
+    let empty: [u8; 0] = hex!(0;);
+    assert_eq!(empty, []);
+    const _: [u8; 4] = hex!(4; "ff d3 01 7f");
+}
+
+#[test]
+fn strict_case() {
+    assert_eq!(hex!(upper: "AE DF 04 B2"), [0xae, 0xdf, 0x04, 0xb2]);
+    assert_eq!(hex!(lower: "bf dd" "e4 cd"), [0xbf, 0xdd, 0xe4, 0xcd]);
+    assert_eq!(hex!(upper: "0123 4567 89"), [0x01, 0x23, 0x45, 0x67, 0x89]);
+    assert_eq!(hex!(3; lower: "ff 00 a1"), [0xff, 0x00, 0xa1]);
+}
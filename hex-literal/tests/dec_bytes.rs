@@ -0,0 +1,46 @@
+use hex_literal::{dec_bytes, hex};
+
+#[test]
+fn minimal_length() {
+    assert_eq!(dec_bytes!("0"), [0x00]);
+    assert_eq!(dec_bytes!("255"), [0xff]);
+    assert_eq!(dec_bytes!("256"), [0x01, 0x00]);
+    assert_eq!(dec_bytes!("0065537"), [0x01, 0x00, 0x01]);
+}
+
+#[test]
+fn big_integer() {
+    // 2^255 - 19
+    const P: [u8; 32] =
+        dec_bytes!("57896044618658097711785492504343953926634992332820282019728792003956564819949");
+    assert_eq!(
+        P,
+        hex!("7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffed")
+    );
+}
+
+#[test]
+fn byte_order() {
+    assert_eq!(dec_bytes!(be: "4660"), [0x12, 0x34]);
+    assert_eq!(dec_bytes!(le: "4660"), [0x34, 0x12]);
+    assert_eq!(dec_bytes!(le: "1"), [0x01]);
+}
+
+#[test]
+fn padded_length() {
+    assert_eq!(dec_bytes!(4; "4660"), [0x00, 0x00, 0x12, 0x34]);
+    assert_eq!(dec_bytes!(4; le: "4660"), [0x34, 0x12, 0x00, 0x00]);
+    assert_eq!(dec_bytes!(2; "4660"), [0x12, 0x34]);
+    assert_eq!(dec_bytes!(3; "0"), [0x00, 0x00, 0x00]);
+}
+
+#[test]
+fn formatting() {
+    assert_eq!(
+        dec_bytes!(
+            "12345 67890" // first half
+            "1234_567890" /* second half */
+        ),
+        [0xab, 0x54, 0xa9, 0x8c, 0xeb, 0x1f, 0x0a, 0xd2]
+    );
+}
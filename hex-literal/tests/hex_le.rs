@@ -0,0 +1,30 @@
+use hex_literal::{hex, hex_le};
+
+#[test]
+fn reversed() {
+    assert_eq!(hex_le!("ff e4"), [0xe4, 0xff]);
+    assert_eq!(hex_le!("01 dd f7" "7f ee"), [0xee, 0x7f, 0xf7, 0xdd, 0x01]);
+    let empty: [u8; 0] = hex_le!();
+    assert_eq!(empty, []);
+}
+
+#[test]
+fn field_constant() {
+    // 2^255 - 19 as little-endian bytes
+    let mut expected = hex!("7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffed");
+    expected.reverse();
+    assert_eq!(
+        hex_le!("7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffed"),
+        expected
+    );
+}
+
+#[test]
+fn with_options() {
+    assert_eq!(hex_le!(3; lower: "0a 0b 0c"), [0x0c, 0x0b, 0x0a]);
+}
+
+#[test]
+fn can_use_const() {
+    const _: [u8; 4] = hex_le!("ff d3 01 7f");
+}
@@ -0,0 +1,65 @@
+//! Tests for macro-expanded inputs, which require Rust 1.57
+use hex_literal::{hex, hex_le};
+
+macro_rules! key {
+    () => {
+        "00112233 44556677"
+    };
+}
+
+#[test]
+fn concat() {
+    assert_eq!(hex!(concat!("ff", "e4")), [0xff, 0xe4]);
+    assert_eq!(
+        hex!(concat!("01 dd", " f7 7f") "ee f0 d8"),
+        [0x01, 0xdd, 0xf7, 0x7f, 0xee, 0xf0, 0xd8]
+    );
+    assert_eq!(
+        hex!(core::concat!("00 01 // comment\n", "02 /* comment */ 03")),
+        [0x00, 0x01, 0x02, 0x03]
+    );
+}
+
+#[test]
+fn include_str() {
+    assert_eq!(
+        hex!(include_str!("data/vector.hex")),
+        [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]
+    );
+}
+
+#[test]
+fn user_macro() {
+    assert_eq!(
+        hex!(key!()),
+        [0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77]
+    );
+}
+
+#[test]
+fn with_options() {
+    assert_eq!(hex!(2; concat!("ff", "e4")), [0xff, 0xe4]);
+    assert_eq!(hex!(upper: concat!("AE", "DF")), [0xae, 0xdf]);
+    assert_eq!(hex!(2; lower: concat!("ae", "df")), [0xae, 0xdf]);
+}
+
+#[test]
+fn hex_le() {
+    assert_eq!(hex_le!(concat!("0a", "0b") "0c"), [0x0c, 0x0b, 0x0a]);
+    assert_eq!(hex_le!(3; upper: concat!("0A0B0C")), [0x0c, 0x0b, 0x0a]);
+}
+
+#[test]
+fn rows() {
+    assert_eq!(
+        hex!(["0a0b", concat!("0c", "0d")]),
+        [[0x0a, 0x0b], [0x0c, 0x0d]]
+    );
+}
+
+#[test]
+fn can_use_const() {
+    const DATA: [u8; 16] = hex!(include_str!("data/vector.hex"));
+    assert_eq!(DATA[15], 0x0f);
+    const _: [u8; 2] = hex_le!(concat!("ff", "d3"));
+}
@@ -0,0 +1,41 @@
+use hex_literal::{hex, hex_le};
+
+#[test]
+fn rows() {
+    assert_eq!(
+        hex!(["00 01 02", "03 04 05"]),
+        [[0x00, 0x01, 0x02], [0x03, 0x04, 0x05]]
+    );
+    assert_eq!(hex!(["ff",]), [[0xff]]);
+    let empty: [[u8; 0]; 2] = hex!(["", ""]);
+    assert_eq!(empty, [[], []]);
+}
+
+#[test]
+fn multi_literal_rows() {
+    assert_eq!(
+        hex!([
+            // first round key
+            "0001 0203"
+            "0405 0607",
+            // second round key
+            "0809 0a0b" /* comment */ "0c0d 0e0f",
+        ]),
+        [
+            [0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07],
+            [0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f],
+        ]
+    );
+}
+
+#[test]
+fn with_options() {
+    assert_eq!(hex!(2; ["0a0b", "0c0d"]), [[0x0a, 0x0b], [0x0c, 0x0d]]);
+    assert_eq!(hex!(lower: ["0a0b", "0c0d"]), [[0x0a, 0x0b], [0x0c, 0x0d]]);
+    assert_eq!(hex_le!(["0a0b", "0c0d"]), [[0x0b, 0x0a], [0x0d, 0x0c]]);
+}
+
+#[test]
+fn can_use_const() {
+    const _: [[u8; 2]; 3] = hex!(["0001", "0203", "0405"]);
+}
@@ -0,0 +1,194 @@
+use crate::{errors::AlignmentError, InOutBuf, InOutBufRawParts};
+use core::{marker::PhantomData, mem, ptr::NonNull};
+use generic_array::{ArrayLength, GenericArray};
+
+/// Types for which any bit pattern is a valid value.
+///
+/// It's used by [`InOutBuf::align_to`] to reinterpret bytes as words.
+///
+/// # Safety
+/// Implementors must not have padding bytes and any bit pattern must be
+/// a valid value of the type.
+pub unsafe trait AnyBitPattern: Copy {}
+
+macro_rules! impl_any_bit_pattern {
+    ($($ty:ty),*) => {
+        $(unsafe impl AnyBitPattern for $ty {})*
+    };
+}
+
+impl_any_bit_pattern!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+unsafe impl<T: AnyBitPattern, const N: usize> AnyBitPattern for [T; N] {}
+
+unsafe impl<T: AnyBitPattern, N: ArrayLength<T>> AnyBitPattern for GenericArray<T, N> where
+    N::ArrayType: Copy
+{
+}
+
+impl<'inp, 'out> InOutBuf<'inp, 'out, u8> {
+    /// Split byte buffer into an unaligned head, a middle buffer of words
+    /// of type `W` and a tail, analogous to [`slice::align_to`].
+    ///
+    /// Input and output pointers of the middle buffer are aligned for `W`.
+    /// The head and the tail are shorter than `W`, unless input and output
+    /// pointers have different misalignment, in which case the whole buffer
+    /// is returned as the head.
+    #[inline(always)]
+    #[allow(clippy::type_complexity)]
+    pub fn align_to<W: AnyBitPattern>(
+        self,
+    ) -> (
+        InOutBuf<'inp, 'out, u8>,
+        InOutBuf<'inp, 'out, W>,
+        InOutBuf<'inp, 'out, u8>,
+    ) {
+        let (size, align) = (mem::size_of::<W>(), mem::align_of::<W>());
+        let head_len = self.in_ptr.as_ptr().align_offset(align);
+        let out_head_len = self.out_ptr.as_ptr().align_offset(align);
+        if size == 0 || head_len != out_head_len || head_len > self.len {
+            let len = self.len;
+            let (head, tail) = self.split_at(len);
+            let body = InOutBuf {
+                in_ptr: NonNull::dangling(),
+                out_ptr: NonNull::dangling(),
+                len: 0,
+                _pd: PhantomData,
+            };
+            return (head, body, tail);
+        }
+        let (head, rest) = self.split_at(head_len);
+        let words = rest.len() / size;
+        let (body, tail) = rest.split_at(size * words);
+        let body = InOutBuf {
+            in_ptr: body.in_ptr.cast(),
+            out_ptr: body.out_ptr.cast(),
+            len: words,
+            _pd: PhantomData,
+        };
+        (head, body, tail)
+    }
+}
+
+/// [`InOutBuf`] which input and output pointers are aligned to `A` bytes.
+///
+/// It allows to hand buffers to hardware with alignment requirements, e.g.
+/// DMA engines of crypto accelerators, while the alignment is encoded in
+/// the type.
+///
+/// # Examples
+/// ```
+/// #[repr(align(16))]
+/// struct Buf([u8; 32]);
+///
+/// let mut buf = Buf([0; 32]);
+/// let buf = inout::InOutBuf::from(&mut buf.0[..]);
+/// let aligned = inout::AlignedInOutBuf::<'_, '_, u8, 16>::new(buf).unwrap();
+/// let parts = aligned.into_raw_parts();
+/// assert_eq!(parts.in_ptr as usize % 16, 0);
+/// assert_eq!(parts.len, 32);
+/// ```
+pub struct AlignedInOutBuf<'inp, 'out, T, const A: usize> {
+    buf: InOutBuf<'inp, 'out, T>,
+}
+
+impl<'inp, 'out, T, const A: usize> AlignedInOutBuf<'inp, 'out, T, A> {
+    /// Wrap `buf` checking that its input and output pointers are aligned
+    /// to `A` bytes.
+    ///
+    /// Returns an error if any of the pointers is not aligned.
+    ///
+    /// # Panics
+    /// If `A` is not a power of two.
+    #[inline(always)]
+    pub fn new(buf: InOutBuf<'inp, 'out, T>) -> Result<Self, AlignmentError> {
+        assert!(A.is_power_of_two(), "alignment must be a power of two");
+        let addrs = buf.in_ptr.as_ptr() as usize | buf.out_ptr.as_ptr() as usize;
+        if addrs % A == 0 {
+            Ok(Self { buf })
+        } else {
+            Err(AlignmentError)
+        }
+    }
+
+    /// Get length of the inner buffers.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Returns `true` if the buffer has a length of 0.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    /// Reborrow `self` as [`InOutBuf`].
+    #[inline(always)]
+    pub fn reborrow<'a>(&'a mut self) -> InOutBuf<'a, 'a, T> {
+        self.buf.reborrow()
+    }
+
+    /// Convert `self` into the inner [`InOutBuf`].
+    #[inline(always)]
+    pub fn into_buf(self) -> InOutBuf<'inp, 'out, T> {
+        self.buf
+    }
+
+    /// Get raw input and output pointers aligned to `A` bytes together with
+    /// the buffer length.
+    #[inline(always)]
+    pub fn into_raw_parts(self) -> InOutBufRawParts<T> {
+        self.buf.into_raw_parts()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aligned() {
+        #[repr(align(8))]
+        struct Buf([u8; 16]);
+
+        let input = Buf([1; 16]);
+        let mut output = Buf([0; 16]);
+        let buf = InOutBuf::new(&input.0, &mut output.0).unwrap();
+        let mut aligned = AlignedInOutBuf::<'_, '_, u8, 8>::new(buf).unwrap();
+        aligned.reborrow().copy_in2out();
+        let parts = aligned.into_raw_parts();
+        assert_eq!(parts.in_ptr, input.0.as_ptr());
+        assert_eq!((parts.len, parts.byte_len()), (16, 16));
+        assert_eq!(output.0, [1; 16]);
+
+        let buf = InOutBuf::new(&input.0[1..], &mut output.0[1..]).unwrap();
+        assert!(AlignedInOutBuf::<'_, '_, u8, 8>::new(buf).is_err());
+        let buf = InOutBuf::new(&input.0[8..], &mut output.0[8..]).unwrap();
+        assert_eq!(AlignedInOutBuf::<'_, '_, u8, 8>::new(buf).unwrap().len(), 8);
+    }
+
+    #[test]
+    fn align_to() {
+        #[repr(align(8))]
+        struct Buf([u8; 24]);
+
+        let input = Buf([1; 24]);
+        let mut output = Buf([0; 24]);
+        let buf = InOutBuf::new(&input.0[3..22], &mut output.0[3..22]).unwrap();
+        let (head, mut body, tail) = buf.align_to::<u64>();
+        assert_eq!((head.len(), body.len(), tail.len()), (5, 1, 6));
+        assert_eq!(body.get_in()[0], 0x0101_0101_0101_0101);
+        body.get(0).copy_in2out();
+        assert_eq!(output.0[8..16], [1; 8]);
+        assert_eq!(output.0[..8], [0; 8]);
+
+        let buf = InOutBuf::new(&input.0[1..20], &mut output.0[2..21]).unwrap();
+        let (head, body, tail) = buf.align_to::<u32>();
+        assert_eq!((head.len(), body.len(), tail.len()), (19, 0, 0));
+
+        let buf = InOutBuf::new(&input.0[1..4], &mut output.0[1..4]).unwrap();
+        let (head, body, tail) = buf.align_to::<u64>();
+        assert_eq!((head.len(), body.len(), tail.len()), (3, 0, 0));
+    }
+}
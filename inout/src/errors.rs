@@ -29,6 +29,48 @@ impl fmt::Display for NotEqualError {
 #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
 impl std::error::Error for NotEqualError {}
 
+/// The error returned when index or split position is out of range.
+#[derive(Copy, Clone, Debug)]
+pub struct OutOfRangeError;
+
+impl fmt::Display for OutOfRangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str("Index is out of range")
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl std::error::Error for OutOfRangeError {}
+
+/// The error returned when input and output buffers partially overlap.
+#[derive(Copy, Clone, Debug)]
+pub struct OverlapError;
+
+impl fmt::Display for OverlapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str("Input and output buffers partially overlap")
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl std::error::Error for OverlapError {}
+
+/// Buffers are not aligned to the requested alignment.
+#[derive(Clone, Copy, Debug)]
+pub struct AlignmentError;
+
+impl fmt::Display for AlignmentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str("Buffers are not aligned to the requested alignment")
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl std::error::Error for AlignmentError {}
+
 /// Padding error. Usually emitted when size of output buffer is insufficient.
 #[cfg(feature = "block-padding")]
 #[cfg_attr(docsrs, doc(cfg(feature = "block-padding")))]
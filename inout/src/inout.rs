@@ -1,12 +1,19 @@
-use crate::InOutBuf;
-use core::{marker::PhantomData, ptr};
+use crate::{
+    errors::OutOfRangeError,
+    inout_buf::partially_overlap,
+    xor::{copy_bytes, xor3_bytes, xor_bytes},
+    InOutBuf,
+};
+#[cfg(feature = "cmov")]
+use crate::{xor::cmov_bytes, AnyBitPattern};
+use core::{marker::PhantomData, mem, ptr::NonNull, slice};
 use generic_array::{ArrayLength, GenericArray};
 
 /// Custom pointer type which contains one immutable (input) and one mutable
 /// (output) pointer, which are either equal or non-overlapping.
 pub struct InOut<'inp, 'out, T> {
-    pub(crate) in_ptr: *const T,
-    pub(crate) out_ptr: *mut T,
+    pub(crate) in_ptr: NonNull<T>,
+    pub(crate) out_ptr: NonNull<T>,
     pub(crate) _pd: PhantomData<(&'inp T, &'out mut T)>,
 }
 
@@ -24,19 +31,35 @@ impl<'inp, 'out, T> InOut<'inp, 'out, T> {
     /// Get immutable reference to the input value.
     #[inline(always)]
     pub fn get_in<'a>(&'a self) -> &'a T {
-        unsafe { &*self.in_ptr }
+        unsafe { &*self.in_ptr.as_ptr() }
     }
 
     /// Get mutable reference to the output value.
     #[inline(always)]
     pub fn get_out<'a>(&'a mut self) -> &'a mut T {
-        unsafe { &mut *self.out_ptr }
+        unsafe { &mut *self.out_ptr.as_ptr() }
+    }
+
+    /// Consume `self` and return output reference with lifetime `'out`.
+    #[inline(always)]
+    pub fn into_out(self) -> &'out mut T {
+        unsafe { &mut *self.out_ptr.as_ptr() }
     }
 
     /// Convert `self` to a pair of raw input and output pointers.
     #[inline(always)]
     pub fn into_raw(self) -> (*const T, *mut T) {
-        (self.in_ptr, self.out_ptr)
+        (self.in_ptr.as_ptr(), self.out_ptr.as_ptr())
+    }
+
+    /// Apply `f` to the input value and write result to the output.
+    ///
+    /// The input value is read before the output is written, so it's
+    /// correct for equal input and output pointers.
+    #[inline(always)]
+    pub fn map_in2out(&mut self, f: impl FnOnce(&T) -> T) {
+        let res = f(self.get_in());
+        *self.get_out() = res;
     }
 
     /// Create `InOut` from raw input and output pointers.
@@ -59,26 +82,80 @@ impl<'inp, 'out, T> InOut<'inp, 'out, T> {
     /// mutated for the duration of lifetime `'a`, except inside an `UnsafeCell`.
     #[inline(always)]
     pub unsafe fn from_raw(in_ptr: *const T, out_ptr: *mut T) -> InOut<'inp, 'out, T> {
+        debug_assert!(!partially_overlap(in_ptr, 1, out_ptr, 1));
         Self {
-            in_ptr,
-            out_ptr,
+            in_ptr: NonNull::new_unchecked(in_ptr as *mut T),
+            out_ptr: NonNull::new_unchecked(out_ptr),
             _pd: PhantomData,
         }
     }
+
+    /// Create `InOut` from non-null input and output pointers.
+    ///
+    /// # Safety
+    /// All conditions of [`InOut::from_raw`] must be satisfied.
+    #[inline(always)]
+    pub unsafe fn from_raw_nonnull(
+        in_ptr: NonNull<T>,
+        out_ptr: NonNull<T>,
+    ) -> InOut<'inp, 'out, T> {
+        Self::from_raw(in_ptr.as_ptr(), out_ptr.as_ptr())
+    }
 }
 
 impl<'inp, 'out, T: Clone> InOut<'inp, 'out, T> {
     /// Clone input value and return it.
     #[inline(always)]
     pub fn clone_in(&self) -> T {
-        unsafe { (&*self.in_ptr).clone() }
+        unsafe { (&*self.in_ptr.as_ptr()).clone() }
+    }
+}
+
+impl<'inp, 'out, T: Copy> InOut<'inp, 'out, T> {
+    /// Copy input value to the output.
+    ///
+    /// It's a no-op if input and output pointers are equal.
+    #[inline(always)]
+    pub fn copy_in2out(&mut self) {
+        if self.in_ptr != self.out_ptr {
+            let len = mem::size_of::<T>();
+            unsafe {
+                copy_bytes(
+                    self.in_ptr.as_ptr() as *const u8,
+                    self.out_ptr.as_ptr() as *mut u8,
+                    len,
+                )
+            }
+        }
+    }
+}
+
+#[cfg(feature = "cmov")]
+#[cfg_attr(docsrs, doc(cfg(feature = "cmov")))]
+impl<'inp, 'out, T: AnyBitPattern> InOut<'inp, 'out, T> {
+    /// Copy input value to the output in constant time if `condition`
+    /// is not equal to zero.
+    ///
+    /// Values are processed as raw bytes, so types with padding bytes
+    /// are not supported.
+    #[inline(always)]
+    pub fn cmov_in2out(&mut self, condition: u8) {
+        let len = mem::size_of::<T>();
+        unsafe {
+            cmov_bytes(
+                condition,
+                self.in_ptr.as_ptr() as *const u8,
+                self.out_ptr.as_ptr() as *mut u8,
+                len,
+            )
+        }
     }
 }
 
 impl<'a, T> From<&'a mut T> for InOut<'a, 'a, T> {
     #[inline(always)]
     fn from(val: &'a mut T) -> Self {
-        let p = val as *mut T;
+        let p = NonNull::from(val);
         Self {
             in_ptr: p,
             out_ptr: p,
@@ -91,8 +168,8 @@ impl<'inp, 'out, T> From<(&'inp T, &'out mut T)> for InOut<'inp, 'out, T> {
     #[inline(always)]
     fn from((in_val, out_val): (&'inp T, &'out mut T)) -> Self {
         Self {
-            in_ptr: in_val as *const T,
-            out_ptr: out_val as *mut T,
+            in_ptr: NonNull::from(in_val),
+            out_ptr: NonNull::from(out_val),
             _pd: Default::default(),
         }
     }
@@ -106,12 +183,32 @@ impl<'inp, 'out, T, N: ArrayLength<T>> InOut<'inp, 'out, GenericArray<T, N>> {
     #[inline(always)]
     pub fn get<'a>(&'a mut self, pos: usize) -> InOut<'a, 'a, T> {
         assert!(pos < N::USIZE);
-        unsafe {
-            InOut {
-                in_ptr: (self.in_ptr as *const T).add(pos),
-                out_ptr: (self.out_ptr as *mut T).add(pos),
-                _pd: PhantomData,
-            }
+        unsafe { self.get_unchecked(pos) }
+    }
+
+    /// Returns `InOut` for the given position without bounds checking.
+    ///
+    /// # Safety
+    /// Calling this method with `pos` greater or equal to array length
+    /// is undefined behavior.
+    #[inline(always)]
+    pub unsafe fn get_unchecked<'a>(&'a mut self, pos: usize) -> InOut<'a, 'a, T> {
+        debug_assert!(pos < N::USIZE);
+        InOut {
+            in_ptr: NonNull::new_unchecked(self.in_ptr.cast::<T>().as_ptr().add(pos)),
+            out_ptr: NonNull::new_unchecked(self.out_ptr.cast::<T>().as_ptr().add(pos)),
+            _pd: PhantomData,
+        }
+    }
+
+    /// Returns `InOut` for the given position or an error if `pos` is
+    /// greater or equal to array length.
+    #[inline(always)]
+    pub fn try_get<'a>(&'a mut self, pos: usize) -> Result<InOut<'a, 'a, T>, OutOfRangeError> {
+        if pos < N::USIZE {
+            Ok(self.get(pos))
+        } else {
+            Err(OutOfRangeError)
         }
     }
 
@@ -119,8 +216,8 @@ impl<'inp, 'out, T, N: ArrayLength<T>> InOut<'inp, 'out, GenericArray<T, N>> {
     #[inline(always)]
     pub fn into_buf(self) -> InOutBuf<'inp, 'out, T> {
         InOutBuf {
-            in_ptr: self.in_ptr as *const T,
-            out_ptr: self.out_ptr as *mut T,
+            in_ptr: self.in_ptr.cast(),
+            out_ptr: self.out_ptr.cast(),
             len: N::USIZE,
             _pd: PhantomData,
         }
@@ -134,37 +231,147 @@ impl<'inp, 'out, N: ArrayLength<u8>> InOut<'inp, 'out, GenericArray<u8, N>> {
     /// # Panics
     /// If `data` length is not equal to the buffer length.
     #[inline(always)]
-    #[allow(clippy::needless_range_loop)]
     pub fn xor_in2out(&mut self, data: &GenericArray<u8, N>) {
         unsafe {
-            assert_eq!(N::USIZE & 7, 0);
-            unsafe {
-                // t0 / t1 data unroll
-                // t2 / t3 input unroll
-                core::arch::asm!(
-                    asm_macros::lp_setup!(0, t4, 16),
-                    asm_macros::lw_pi!(t0, 4(a0!)),
-                    asm_macros::lw_pi!(t1, 4(a0!)),
-                    asm_macros::lw_pi!(t2, 4(a1!)),
-                    asm_macros::lw_pi!(t3, 4(a1!)),
-                    asm_macros::xor!(t0, t0, t2),
-                    asm_macros::xor!(t1, t1, t3),
-                    asm_macros::sw_pi!(t0, 4(a2!)),
-                    asm_macros::sw_pi!(t1, 4(a2!)),
-                    in("a0") self.in_ptr as *const u32,
-                    in("a1") data.as_ptr() as *const u32,
-                    in("a2") self.out_ptr as *const u32,
-                    out("t0") _,
-                    out("t1") _,
-                    out("t2") _,
-                    out("t3") _,
-                    in("t4") N::USIZE / 8,
-                )
-            }
+            xor_bytes(
+                self.in_ptr.as_ptr() as *const u8,
+                self.out_ptr.as_ptr() as *mut u8,
+                data,
+            )
+        }
+    }
+
+    /// XOR `data` with values behind the input slice and write
+    /// result to the output slice.
+    ///
+    /// # Panics
+    /// If `data` length is not equal to the array length.
+    #[inline(always)]
+    pub fn xor_in2out_slice(&mut self, data: &[u8]) {
+        assert_eq!(data.len(), N::USIZE);
+        unsafe {
+            xor_bytes(
+                self.in_ptr.as_ptr() as *const u8,
+                self.out_ptr.as_ptr() as *mut u8,
+                data,
+            )
+        }
+    }
+
+    /// XOR `a` and `b` with values behind the input slice and write
+    /// result to the output slice in a single pass.
+    #[inline(always)]
+    pub fn xor3_in2out(&mut self, a: &GenericArray<u8, N>, b: &GenericArray<u8, N>) {
+        unsafe {
+            xor3_bytes(
+                self.in_ptr.as_ptr() as *const u8,
+                self.out_ptr.as_ptr() as *mut u8,
+                a,
+                b,
+            )
         }
     }
 }
 
+macro_rules! impl_words {
+    ($read:ident, $write:ident, $ty:ty, $from:ident, $to:ident, $endian:literal) => {
+        #[doc = concat!(
+                    "Read input block as an array of ", $endian, " `", stringify!($ty), "` words.",
+                )]
+        ///
+        /// # Panics
+        /// If size of the word array is not equal to the block size.
+        #[inline(always)]
+        pub fn $read<const W: usize>(&self) -> [$ty; W] {
+            const SIZE: usize = mem::size_of::<$ty>();
+            assert_eq!(SIZE * W, N::USIZE);
+            let mut res = [0; W];
+            for (w, chunk) in res.iter_mut().zip(self.get_in().chunks_exact(SIZE)) {
+                *w = <$ty>::$from(chunk.try_into().unwrap());
+            }
+            res
+        }
+
+        #[doc = concat!(
+                    "Write array of `", stringify!($ty), "` words to output block using ",
+                    $endian, " byte order.",
+                )]
+        ///
+        /// # Panics
+        /// If size of the word array is not equal to the block size.
+        #[inline(always)]
+        pub fn $write<const W: usize>(&mut self, words: &[$ty; W]) {
+            const SIZE: usize = mem::size_of::<$ty>();
+            assert_eq!(SIZE * W, N::USIZE);
+            for (w, chunk) in words.iter().zip(self.get_out().chunks_exact_mut(SIZE)) {
+                chunk.copy_from_slice(&w.$to());
+            }
+        }
+    };
+}
+
+impl<'inp, 'out, N: ArrayLength<u8>> InOut<'inp, 'out, GenericArray<u8, N>> {
+    impl_words!(
+        read_u32s_le,
+        write_u32s_le,
+        u32,
+        from_le_bytes,
+        to_le_bytes,
+        "little-endian"
+    );
+    impl_words!(
+        read_u32s_be,
+        write_u32s_be,
+        u32,
+        from_be_bytes,
+        to_be_bytes,
+        "big-endian"
+    );
+    impl_words!(
+        read_u64s_le,
+        write_u64s_le,
+        u64,
+        from_le_bytes,
+        to_le_bytes,
+        "little-endian"
+    );
+    impl_words!(
+        read_u64s_be,
+        write_u64s_be,
+        u64,
+        from_be_bytes,
+        to_be_bytes,
+        "big-endian"
+    );
+}
+
+macro_rules! impl_xor_words {
+    ($($ty:ty),*) => {
+        $(
+            impl<'inp, 'out, N: ArrayLength<$ty>> InOut<'inp, 'out, GenericArray<$ty, N>> {
+                #[doc = concat!(
+                    "XOR `data` with input `", stringify!($ty), "` words and write ",
+                    "result to the output array.",
+                )]
+                ///
+                /// Words are processed using native-width operations.
+                #[inline(always)]
+                pub fn xor_in2out(&mut self, data: &GenericArray<$ty, N>) {
+                    let in_ptr = self.in_ptr.cast::<$ty>().as_ptr();
+                    let out_ptr = self.out_ptr.cast::<$ty>().as_ptr();
+                    for (i, d) in data.iter().enumerate() {
+                        // input is read before the output is written, so it's
+                        // correct for equal input and output pointers
+                        unsafe { *out_ptr.add(i) = *in_ptr.add(i) ^ d }
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_xor_words!(u32, u64);
+
 impl<'inp, 'out, N, M> InOut<'inp, 'out, GenericArray<GenericArray<u8, N>, M>>
 where
     N: ArrayLength<u8>,
@@ -176,28 +383,357 @@ where
     /// # Panics
     /// If `data` length is not equal to the buffer length.
     #[inline(always)]
-    #[allow(clippy::needless_range_loop)]
     pub fn xor_in2out(&mut self, data: &GenericArray<GenericArray<u8, N>, M>) {
+        let len = N::USIZE * M::USIZE;
         unsafe {
-            let input = ptr::read(self.in_ptr);
-            let mut temp = GenericArray::<GenericArray<u8, N>, M>::default();
-            for i in 0..M::USIZE {
-                for j in 0..N::USIZE {
-                    temp[i][j] = input[i][j] ^ data[i][j];
-                }
-            }
-            ptr::write(self.out_ptr, temp);
+            let data = slice::from_raw_parts(data.as_ptr() as *const u8, len);
+            xor_bytes(
+                self.in_ptr.as_ptr() as *const u8,
+                self.out_ptr.as_ptr() as *mut u8,
+                data,
+            )
         }
     }
 }
 
-#[test]
-fn testlol() {
-    let slice = [1u8; 513];
-    let xor: &GenericArray<u8, generic_array::typenum::U513> = GenericArray::from_slice(&slice);
-    let mut buf = [0u8; 513];
-    let ar = GenericArray::from_mut_slice(&mut buf);
-    let mut inout = InOut::from(ar);
-    inout.xor_in2out(xor);
-    assert!(inout.get_out()[0] == 1);
+impl<'inp, 'out, T, const N: usize> InOut<'inp, 'out, [T; N]> {
+    /// Returns `InOut` for the given position.
+    ///
+    /// # Panics
+    /// If `pos` greater or equal to array length.
+    #[inline(always)]
+    pub fn get<'a>(&'a mut self, pos: usize) -> InOut<'a, 'a, T> {
+        assert!(pos < N);
+        unsafe { self.get_unchecked(pos) }
+    }
+
+    /// Returns `InOut` for the given position without bounds checking.
+    ///
+    /// # Safety
+    /// Calling this method with `pos` greater or equal to array length
+    /// is undefined behavior.
+    #[inline(always)]
+    pub unsafe fn get_unchecked<'a>(&'a mut self, pos: usize) -> InOut<'a, 'a, T> {
+        debug_assert!(pos < N);
+        InOut {
+            in_ptr: NonNull::new_unchecked(self.in_ptr.cast::<T>().as_ptr().add(pos)),
+            out_ptr: NonNull::new_unchecked(self.out_ptr.cast::<T>().as_ptr().add(pos)),
+            _pd: PhantomData,
+        }
+    }
+
+    /// Returns `InOut` for the given position or an error if `pos` is
+    /// greater or equal to array length.
+    #[inline(always)]
+    pub fn try_get<'a>(&'a mut self, pos: usize) -> Result<InOut<'a, 'a, T>, OutOfRangeError> {
+        if pos < N {
+            Ok(self.get(pos))
+        } else {
+            Err(OutOfRangeError)
+        }
+    }
+
+    /// Convert `InOut` array to `InOutBuf`.
+    #[inline(always)]
+    pub fn into_buf(self) -> InOutBuf<'inp, 'out, T> {
+        InOutBuf {
+            in_ptr: self.in_ptr.cast(),
+            out_ptr: self.out_ptr.cast(),
+            len: N,
+            _pd: PhantomData,
+        }
+    }
+}
+
+impl<'inp, 'out, const N: usize> InOut<'inp, 'out, [u8; N]> {
+    /// XOR `data` with values behind the input slice and write
+    /// result to the output slice.
+    #[inline(always)]
+    pub fn xor_in2out(&mut self, data: &[u8; N]) {
+        unsafe {
+            xor_bytes(
+                self.in_ptr.as_ptr() as *const u8,
+                self.out_ptr.as_ptr() as *mut u8,
+                data,
+            )
+        }
+    }
+
+    /// XOR `data` with values behind the input slice and write
+    /// result to the output slice.
+    ///
+    /// # Panics
+    /// If `data` length is not equal to the array length.
+    #[inline(always)]
+    pub fn xor_in2out_slice(&mut self, data: &[u8]) {
+        assert_eq!(data.len(), N);
+        unsafe {
+            xor_bytes(
+                self.in_ptr.as_ptr() as *const u8,
+                self.out_ptr.as_ptr() as *mut u8,
+                data,
+            )
+        }
+    }
+
+    /// XOR `a` and `b` with values behind the input slice and write
+    /// result to the output slice in a single pass.
+    #[inline(always)]
+    pub fn xor3_in2out(&mut self, a: &[u8; N], b: &[u8; N]) {
+        unsafe {
+            xor3_bytes(
+                self.in_ptr.as_ptr() as *const u8,
+                self.out_ptr.as_ptr() as *mut u8,
+                a,
+                b,
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn testlol() {
+        let slice = [1u8; 513];
+        let xor: &GenericArray<u8, generic_array::typenum::U513> = GenericArray::from_slice(&slice);
+        let mut buf = [0u8; 513];
+        let ar = GenericArray::from_mut_slice(&mut buf);
+        let mut inout = InOut::from(ar);
+        inout.xor_in2out(xor);
+        assert!(inout.get_out()[0] == 1);
+    }
+
+    #[test]
+    fn xor_in2out_tail() {
+        fn check<N: ArrayLength<u8>>() {
+            let data: GenericArray<u8, N> = GenericArray::generate(|i| i as u8);
+            let input: GenericArray<u8, N> = GenericArray::generate(|i| 0xA5 ^ (3 * i) as u8);
+            let mut output: GenericArray<u8, N> = Default::default();
+            InOut::from((&input, &mut output)).xor_in2out(&data);
+            for i in 0..N::USIZE {
+                assert_eq!(output[i], input[i] ^ data[i]);
+            }
+        }
+        use generic_array::typenum::{U1, U13, U16, U17, U513, U7};
+        check::<U1>();
+        check::<U7>();
+        check::<U13>();
+        check::<U16>();
+        check::<U17>();
+        check::<U513>();
+    }
+
+    #[test]
+    fn xor_in2out_unaligned() {
+        use generic_array::typenum::U16;
+        let data = [0x5Au8; 17];
+        let input = [0x0Fu8; 18];
+        let mut output = [0u8; 19];
+        let data = GenericArray::<u8, U16>::from_slice(&data[1..]);
+        let input = GenericArray::from_slice(&input[2..]);
+        let output = GenericArray::from_mut_slice(&mut output[3..]);
+        InOut::from((input, &mut *output)).xor_in2out(data);
+        assert_eq!(output[..], [0x55; 16]);
+    }
+
+    #[test]
+    fn array_xor_in2out() {
+        let mut buf = [0x0Fu8; 13];
+        let mut inout = InOut::from(&mut buf);
+        inout.xor_in2out(&[0x5A; 13]);
+        assert_eq!(*inout.get(12).get_in(), 0x55);
+        assert_eq!(inout.into_buf().get_out(), [0x55; 13]);
+    }
+
+    #[test]
+    fn copy_in2out() {
+        let input = [1u16, 2, 3];
+        let mut output = [0u16; 3];
+        InOut::from((&input, &mut output)).copy_in2out();
+        assert_eq!(output, input);
+
+        let mut val = 42u64;
+        InOut::from(&mut val).copy_in2out();
+        assert_eq!(val, 42);
+    }
+
+    #[test]
+    fn xor3_in2out() {
+        use generic_array::typenum::U17;
+        let a = GenericArray::<u8, U17>::generate(|i| i as u8);
+        let b = GenericArray::<u8, U17>::generate(|i| 0x80 | i as u8);
+        let mut buf = GenericArray::<u8, U17>::default();
+        InOut::from(&mut buf).xor3_in2out(&a, &b);
+        assert!(buf.iter().all(|&v| v == 0x80));
+
+        let mut buf = [0x0Fu8; 7];
+        InOut::from(&mut buf).xor3_in2out(&[0xF0; 7], &[0x11; 7]);
+        assert_eq!(buf, [0xEE; 7]);
+    }
+
+    #[test]
+    fn xor_in2out_slice() {
+        use generic_array::typenum::U5;
+        let mut buf = GenericArray::<u8, U5>::default();
+        InOut::from(&mut buf).xor_in2out_slice(&[1, 2, 3, 4, 5]);
+        assert_eq!(buf[..], [1, 2, 3, 4, 5]);
+
+        let mut buf = [1u8; 3];
+        InOut::from(&mut buf).xor_in2out_slice(&[1, 1, 1]);
+        assert_eq!(buf, [0; 3]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn xor_in2out_slice_len_mismatch() {
+        let mut buf = [0u8; 3];
+        InOut::from(&mut buf).xor_in2out_slice(&[0; 4]);
+    }
+
+    #[test]
+    fn map_in2out() {
+        let mut val = 21u32;
+        InOut::from(&mut val).map_in2out(|v| 2 * v);
+        assert_eq!(val, 42);
+
+        let mut out = 0u32;
+        InOut::from((&val, &mut out)).map_in2out(|v| v + 1);
+        assert_eq!((val, out), (42, 43));
+    }
+
+    #[test]
+    fn try_get() {
+        use generic_array::typenum::U2;
+        let mut arr = GenericArray::<u8, U2>::default();
+        let mut inout = InOut::from(&mut arr);
+        assert!(inout.try_get(1).is_ok());
+        assert!(inout.try_get(2).is_err());
+
+        let mut arr = [1u8, 2];
+        let mut inout = InOut::from(&mut arr);
+        assert_eq!(*inout.try_get(1).unwrap().get_in(), 2);
+        assert!(inout.try_get(2).is_err());
+    }
+
+    #[test]
+    fn xor_words() {
+        use generic_array::typenum::U3;
+        let input = GenericArray::<u32, U3>::from([1, 2, 3]);
+        let mut output = GenericArray::<u32, U3>::default();
+        let data = GenericArray::from([0xFF00_0000, 0, 3]);
+        InOut::from((&input, &mut output)).xor_in2out(&data);
+        assert_eq!(output.as_slice(), [0xFF00_0001, 2, 0]);
+
+        let mut buf = GenericArray::<u64, U3>::from([u64::MAX, 1, 2]);
+        InOut::from(&mut buf).xor_in2out(&GenericArray::from([u64::MAX; 3]));
+        assert_eq!(buf.as_slice(), [0, u64::MAX - 1, u64::MAX - 2]);
+    }
+
+    #[test]
+    fn into_out() {
+        fn process<'a>(mut inout: InOut<'_, 'a, u32>) -> &'a mut u32 {
+            *inout.get_out() = inout.get_in() + 1;
+            inout.into_out()
+        }
+
+        let mut out = 0;
+        *process(InOut::from((&1, &mut out))) += 1;
+        assert_eq!(out, 3);
+
+        let mut buf = [1u8, 2];
+        let out = InOutBuf::from(&mut buf[..]).into_out();
+        out[0] = 3;
+        assert_eq!(buf, [3, 2]);
+    }
+
+    #[test]
+    fn get_unchecked() {
+        use generic_array::typenum::U2;
+        let mut arr = GenericArray::<u8, U2>::default();
+        let mut inout = InOut::from(&mut arr);
+        *unsafe { inout.get_unchecked(1) }.get_out() = 3;
+        assert_eq!(arr[1], 3);
+
+        let mut arr = [1u8, 2];
+        let mut inout = InOut::from(&mut arr);
+        assert_eq!(*unsafe { inout.get_unchecked(1) }.get_in(), 2);
+
+        let mut buf = InOutBuf::from(&mut arr[..]);
+        *unsafe { buf.get_unchecked(0) }.get_out() = 5;
+        assert_eq!(arr, [5, 2]);
+    }
+
+    #[cfg(feature = "cmov")]
+    #[test]
+    fn cmov_in2out() {
+        let input = [1u8; 13];
+        let mut output = [0u8; 13];
+        InOut::from((&input, &mut output)).cmov_in2out(0);
+        assert_eq!(output, [0; 13]);
+        InOut::from((&input, &mut output)).cmov_in2out(0x80);
+        assert_eq!(output, input);
+    }
+
+    #[test]
+    fn words() {
+        use generic_array::typenum::U16;
+        let input = GenericArray::<u8, U16>::generate(|i| i as u8);
+        let mut output = GenericArray::<u8, U16>::default();
+        let mut inout = InOut::from((&input, &mut output));
+
+        let w: [u32; 4] = inout.read_u32s_le();
+        assert_eq!(w[1], 0x0706_0504);
+        let w: [u32; 4] = inout.read_u32s_be();
+        assert_eq!(w[1], 0x0405_0607);
+        let w: [u64; 2] = inout.read_u64s_le();
+        assert_eq!(w[1], 0x0F0E_0D0C_0B0A_0908);
+        let w: [u64; 2] = inout.read_u64s_be();
+        assert_eq!(w[0], 0x0001_0203_0405_0607);
+
+        inout.write_u64s_be(&w);
+        assert_eq!(inout.get_out(), &input);
+        inout.write_u32s_le(&[1, 2, 3, 4]);
+        assert_eq!(inout.get_out()[..8], [1, 0, 0, 0, 2, 0, 0, 0]);
+        inout.write_u64s_le(&[0x0102, 0x0304]);
+        assert_eq!(inout.get_out()[..10], [2, 1, 0, 0, 0, 0, 0, 0, 4, 3]);
+        inout.write_u32s_be(&[1, 2, 3, 4]);
+        assert_eq!(output[..8], [0, 0, 0, 1, 0, 0, 0, 2]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn words_size_mismatch() {
+        use generic_array::typenum::U16;
+        let mut block = GenericArray::<u8, U16>::default();
+        let _: [u32; 3] = InOut::from(&mut block).read_u32s_le();
+    }
+
+    #[test]
+    fn niche() {
+        assert_eq!(
+            mem::size_of::<Option<InOut<'_, '_, u8>>>(),
+            mem::size_of::<InOut<'_, '_, u8>>(),
+        );
+        assert_eq!(
+            mem::size_of::<Option<InOutBuf<'_, '_, u8>>>(),
+            mem::size_of::<InOutBuf<'_, '_, u8>>(),
+        );
+
+        let a = 1u32;
+        let mut b = 0u32;
+        let mut inout =
+            unsafe { InOut::from_raw_nonnull(NonNull::from(&a), NonNull::from(&mut b)) };
+        inout.copy_in2out();
+        assert_eq!(b, 1);
+
+        let a = [1u8, 2, 3];
+        let mut b = [0u8; 3];
+        let (in_ptr, out_ptr) = (NonNull::from(&a).cast::<u8>(), NonNull::from(&mut b).cast());
+        let mut buf = unsafe { InOutBuf::from_raw_nonnull(in_ptr, out_ptr, 3) };
+        buf.copy_in2out();
+        assert_eq!(b, a);
+    }
 }
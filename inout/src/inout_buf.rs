@@ -1,16 +1,22 @@
 use crate::{
-    errors::{IntoArrayError, NotEqualError},
+    errors::{IntoArrayError, NotEqualError, OutOfRangeError, OverlapError},
+    xor::{copy_bytes, xor_bytes},
     InOut,
 };
-use core::{marker::PhantomData, slice};
+use core::{cmp, marker::PhantomData, mem, ptr::NonNull, slice};
+
+#[cfg(feature = "cmov")]
+use crate::{xor::cmov_bytes, AnyBitPattern};
 use generic_array::{ArrayLength, GenericArray};
+#[cfg(feature = "zeroize")]
+use zeroize::{DefaultIsZeroes, Zeroize};
 
 /// Custom slice type which references one immutable (input) slice and one
 /// mutable (output) slice of equal length. Input and output slices are
 /// either the same or do not overlap.
 pub struct InOutBuf<'inp, 'out, T> {
-    pub(crate) in_ptr: *const T,
-    pub(crate) out_ptr: *mut T,
+    pub(crate) in_ptr: NonNull<T>,
+    pub(crate) out_ptr: NonNull<T>,
     pub(crate) len: usize,
     pub(crate) _pd: PhantomData<(&'inp T, &'out mut T)>,
 }
@@ -18,11 +24,12 @@ pub struct InOutBuf<'inp, 'out, T> {
 impl<'a, T> From<&'a mut [T]> for InOutBuf<'a, 'a, T> {
     #[inline(always)]
     fn from(buf: &'a mut [T]) -> Self {
-        let p = buf.as_mut_ptr();
+        let len = buf.len();
+        let p = NonNull::from(buf).cast();
         Self {
             in_ptr: p,
             out_ptr: p,
-            len: buf.len(),
+            len,
             _pd: PhantomData,
         }
     }
@@ -32,7 +39,7 @@ impl<'a, T> InOutBuf<'a, 'a, T> {
     /// Create `InOutBuf` from a single mutable reference.
     #[inline(always)]
     pub fn from_mut(val: &'a mut T) -> InOutBuf<'a, 'a, T> {
-        let p = val as *mut T;
+        let p = NonNull::from(val);
         Self {
             in_ptr: p,
             out_ptr: p,
@@ -52,13 +59,23 @@ impl<'inp, 'out, T> IntoIterator for InOutBuf<'inp, 'out, T> {
     }
 }
 
+impl<'a, 'inp, 'out, T> IntoIterator for &'a mut InOutBuf<'inp, 'out, T> {
+    type Item = InOut<'a, 'a, T>;
+    type IntoIter = InOutBufIter<'a, 'a, T>;
+
+    #[inline(always)]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
 impl<'inp, 'out, T> InOutBuf<'inp, 'out, T> {
     /// Create `InOutBuf` from a pair of immutable and mutable references.
     #[inline(always)]
     pub fn from_ref_mut(in_val: &'inp T, out_val: &'out mut T) -> Self {
         Self {
-            in_ptr: in_val as *const T,
-            out_ptr: out_val as *mut T,
+            in_ptr: NonNull::from(in_val),
+            out_ptr: NonNull::from(out_val),
             len: 1,
             _pd: PhantomData,
         }
@@ -73,8 +90,8 @@ impl<'inp, 'out, T> InOutBuf<'inp, 'out, T> {
             Err(NotEqualError)
         } else {
             Ok(Self {
-                in_ptr: in_buf.as_ptr(),
-                out_ptr: out_buf.as_mut_ptr(),
+                in_ptr: NonNull::from(in_buf).cast(),
+                out_ptr: NonNull::from(out_buf).cast(),
                 len: in_buf.len(),
                 _pd: Default::default(),
             })
@@ -100,37 +117,87 @@ impl<'inp, 'out, T> InOutBuf<'inp, 'out, T> {
     #[inline(always)]
     pub fn get<'a>(&'a mut self, pos: usize) -> InOut<'a, 'a, T> {
         assert!(pos < self.len);
-        unsafe {
-            InOut {
-                in_ptr: self.in_ptr.add(pos),
-                out_ptr: self.out_ptr.add(pos),
-                _pd: PhantomData,
-            }
+        unsafe { self.get_unchecked(pos) }
+    }
+
+    /// Returns `InOut` for the given position without bounds checking.
+    ///
+    /// # Safety
+    /// Calling this method with `pos` greater or equal to buffer length
+    /// is undefined behavior.
+    #[inline(always)]
+    pub unsafe fn get_unchecked<'a>(&'a mut self, pos: usize) -> InOut<'a, 'a, T> {
+        debug_assert!(pos < self.len);
+        InOut {
+            in_ptr: NonNull::new_unchecked(self.in_ptr.as_ptr().add(pos)),
+            out_ptr: NonNull::new_unchecked(self.out_ptr.as_ptr().add(pos)),
+            _pd: PhantomData,
+        }
+    }
+
+    /// Returns `InOut` for given position or an error if `pos` is greater
+    /// or equal to buffer length.
+    #[inline(always)]
+    pub fn try_get<'a>(&'a mut self, pos: usize) -> Result<InOut<'a, 'a, T>, OutOfRangeError> {
+        if pos < self.len {
+            Ok(self.get(pos))
+        } else {
+            Err(OutOfRangeError)
+        }
+    }
+
+    /// Returns an iterator over `InOut` pairs of the buffer elements.
+    #[inline(always)]
+    pub fn iter_mut<'a>(&'a mut self) -> InOutBufIter<'a, 'a, T> {
+        self.reborrow().into_iter()
+    }
+
+    /// Apply `f` to every input element and write results to the output
+    /// elements with the same index.
+    ///
+    /// Every input element is read before the output element with the same
+    /// index is written, so it's correct for in-place buffers.
+    #[inline(always)]
+    pub fn map_in2out(&mut self, mut f: impl FnMut(&T) -> T) {
+        for mut io in self.iter_mut() {
+            io.map_in2out(&mut f);
         }
     }
 
     /// Get input slice.
     #[inline(always)]
     pub fn get_in<'a>(&'a self) -> &'a [T] {
-        unsafe { slice::from_raw_parts(self.in_ptr, self.len) }
+        unsafe { slice::from_raw_parts(self.in_ptr.as_ptr(), self.len) }
     }
 
     /// Get output slice.
     #[inline(always)]
     pub fn get_out<'a>(&'a mut self) -> &'a mut [T] {
-        unsafe { slice::from_raw_parts_mut(self.out_ptr, self.len) }
+        unsafe { slice::from_raw_parts_mut(self.out_ptr.as_ptr(), self.len) }
     }
 
-    /// Consume self and return output slice with lifetime `'a`.
+    /// Consume self and return output slice with lifetime `'out`.
     #[inline(always)]
     pub fn into_out(self) -> &'out mut [T] {
-        unsafe { slice::from_raw_parts_mut(self.out_ptr, self.len) }
+        unsafe { slice::from_raw_parts_mut(self.out_ptr.as_ptr(), self.len) }
     }
 
     /// Get raw input and output pointers.
     #[inline(always)]
     pub fn into_raw(self) -> (*const T, *mut T) {
-        (self.in_ptr, self.out_ptr)
+        (self.in_ptr.as_ptr(), self.out_ptr.as_ptr())
+    }
+
+    /// Get raw input and output pointers together with the buffer length.
+    ///
+    /// It can be used to hand the buffer to a DMA engine.
+    #[inline(always)]
+    pub fn into_raw_parts(self) -> InOutBufRawParts<T> {
+        InOutBufRawParts {
+            in_ptr: self.in_ptr.as_ptr(),
+            out_ptr: self.out_ptr.as_ptr(),
+            len: self.len,
+        }
     }
 
     /// Reborrow `self`.
@@ -170,14 +237,49 @@ impl<'inp, 'out, T> InOutBuf<'inp, 'out, T> {
         out_ptr: *mut T,
         len: usize,
     ) -> InOutBuf<'inp, 'out, T> {
+        debug_assert!(!partially_overlap(in_ptr, len, out_ptr, len));
         Self {
-            in_ptr,
-            out_ptr,
+            in_ptr: NonNull::new_unchecked(in_ptr as *mut T),
+            out_ptr: NonNull::new_unchecked(out_ptr),
             len,
             _pd: PhantomData,
         }
     }
 
+    /// Create [`InOutBuf`] from non-null input and output pointers.
+    ///
+    /// # Safety
+    /// All conditions of [`InOutBuf::from_raw`] must be satisfied.
+    #[inline(always)]
+    pub unsafe fn from_raw_nonnull(
+        in_ptr: NonNull<T>,
+        out_ptr: NonNull<T>,
+        len: usize,
+    ) -> InOutBuf<'inp, 'out, T> {
+        Self::from_raw(in_ptr.as_ptr(), out_ptr.as_ptr(), len)
+    }
+
+    /// Create [`InOutBuf`] from raw input and output pointers, checking
+    /// that the buffers are either equal or non-overlapping.
+    ///
+    /// Returns an error if the input and output buffers partially overlap.
+    ///
+    /// # Safety
+    /// All conditions of [`InOutBuf::from_raw`], except the overlap
+    /// requirement, must be satisfied.
+    #[inline(always)]
+    pub unsafe fn from_raw_checked(
+        in_ptr: *const T,
+        out_ptr: *mut T,
+        len: usize,
+    ) -> Result<InOutBuf<'inp, 'out, T>, OverlapError> {
+        if partially_overlap(in_ptr, len, out_ptr, len) {
+            Err(OverlapError)
+        } else {
+            Ok(Self::from_raw(in_ptr, out_ptr, len))
+        }
+    }
+
     /// Divides one buffer into two at `mid` index.
     ///
     /// The first will contain all indices from `[0, mid)` (excluding
@@ -190,7 +292,12 @@ impl<'inp, 'out, T> InOutBuf<'inp, 'out, T> {
     #[inline(always)]
     pub fn split_at(self, mid: usize) -> (InOutBuf<'inp, 'out, T>, InOutBuf<'inp, 'out, T>) {
         assert!(mid <= self.len);
-        let (tail_in_ptr, tail_out_ptr) = unsafe { (self.in_ptr.add(mid), self.out_ptr.add(mid)) };
+        let (tail_in_ptr, tail_out_ptr) = unsafe {
+            (
+                NonNull::new_unchecked(self.in_ptr.as_ptr().add(mid)),
+                NonNull::new_unchecked(self.out_ptr.as_ptr().add(mid)),
+            )
+        };
         (
             InOutBuf {
                 in_ptr: self.in_ptr,
@@ -207,7 +314,87 @@ impl<'inp, 'out, T> InOutBuf<'inp, 'out, T> {
         )
     }
 
+    /// Divides one buffer into two at `mid` index.
+    ///
+    /// Returns an error if `mid > len`, see [`InOutBuf::split_at`] for more
+    /// information.
+    #[inline(always)]
+    pub fn try_split_at(
+        self,
+        mid: usize,
+    ) -> Result<(InOutBuf<'inp, 'out, T>, InOutBuf<'inp, 'out, T>), OutOfRangeError> {
+        if mid <= self.len {
+            Ok(self.split_at(mid))
+        } else {
+            Err(OutOfRangeError)
+        }
+    }
+
+    /// Returns an iterator over `chunk_size` elements of the buffer at
+    /// a time, starting at the beginning of the buffer.
+    ///
+    /// The chunks do not overlap. If `chunk_size` does not divide the length
+    /// of the buffer, then the last chunk will not have length `chunk_size`.
+    ///
+    /// # Panics
+    /// If `chunk_size` is equal to zero.
+    #[inline(always)]
+    pub fn chunks(self, chunk_size: usize) -> InOutBufChunks<'inp, 'out, T> {
+        assert_ne!(chunk_size, 0, "chunk size must be non-zero");
+        InOutBufChunks {
+            buf: self,
+            chunk_size,
+        }
+    }
+
+    /// Returns an iterator over `chunk_size` elements of the buffer at
+    /// a time, starting at the beginning of the buffer.
+    ///
+    /// The chunks do not overlap. If `chunk_size` does not divide the length
+    /// of the buffer, then the last up to `chunk_size - 1` elements will be
+    /// omitted and can be retrieved using [`InOutBufChunksExact::into_remainder`].
+    ///
+    /// # Panics
+    /// If `chunk_size` is equal to zero.
+    #[inline(always)]
+    pub fn chunks_exact(self, chunk_size: usize) -> InOutBufChunksExact<'inp, 'out, T> {
+        assert_ne!(chunk_size, 0, "chunk size must be non-zero");
+        let mid = self.len - self.len % chunk_size;
+        let (buf, rem) = self.split_at(mid);
+        InOutBufChunksExact {
+            buf,
+            rem,
+            chunk_size,
+        }
+    }
+
+    /// Split off first `n` elements of the buffer.
+    #[inline(always)]
+    fn take_front(&mut self, n: usize) -> InOutBuf<'inp, 'out, T> {
+        debug_assert!(n <= self.len);
+        let res = InOutBuf {
+            in_ptr: self.in_ptr,
+            out_ptr: self.out_ptr,
+            len: n,
+            _pd: PhantomData,
+        };
+        unsafe {
+            self.in_ptr = NonNull::new_unchecked(self.in_ptr.as_ptr().add(n));
+            self.out_ptr = NonNull::new_unchecked(self.out_ptr.as_ptr().add(n));
+        }
+        self.len -= n;
+        res
+    }
+
     /// Partition buffer into 2 parts: buffer of arrays and tail.
+    ///
+    /// The arrays consist of `N` consecutive elements of the buffer, while
+    /// the tail contains the remaining elements and is always shorter than
+    /// `N`. It's commonly used by block modes to process whole blocks
+    /// separately from the trailing partial block.
+    ///
+    /// # Panics
+    /// If `N` is equal to zero.
     #[inline(always)]
     pub fn into_chunks<N: ArrayLength<T>>(
         self,
@@ -220,20 +407,106 @@ impl<'inp, 'out, T> InOutBuf<'inp, 'out, T> {
         let tail_len = self.len() - tail_pos;
         unsafe {
             let chunks = InOutBuf {
-                in_ptr: self.in_ptr as *const GenericArray<T, N>,
-                out_ptr: self.out_ptr as *mut GenericArray<T, N>,
+                in_ptr: self.in_ptr.cast(),
+                out_ptr: self.out_ptr.cast(),
                 len: chunks,
                 _pd: PhantomData,
             };
             let tail = InOutBuf {
-                in_ptr: self.in_ptr.add(tail_pos),
-                out_ptr: self.out_ptr.add(tail_pos),
+                in_ptr: NonNull::new_unchecked(self.in_ptr.as_ptr().add(tail_pos)),
+                out_ptr: NonNull::new_unchecked(self.out_ptr.as_ptr().add(tail_pos)),
                 len: tail_len,
                 _pd: PhantomData,
             };
             (chunks, tail)
         }
     }
+
+    /// Split buffer into blocks of `N` elements and the tail, then call
+    /// `blocks_fn` for every block and `tail_fn` for the tail.
+    ///
+    /// `tail_fn` is called only if the tail is not empty. Batches of blocks
+    /// can be processed by using `GenericArray` blocks as elements, see
+    /// [`InOutBuf::get_par_blocks`].
+    ///
+    /// # Panics
+    /// If `N` is equal to zero.
+    #[inline(always)]
+    pub fn process_blocks<N: ArrayLength<T>>(
+        self,
+        mut blocks_fn: impl FnMut(InOut<'_, '_, GenericArray<T, N>>),
+        tail_fn: impl FnOnce(InOutBuf<'_, '_, T>),
+    ) {
+        let (blocks, tail) = self.into_chunks::<N>();
+        for block in blocks {
+            blocks_fn(block);
+        }
+        if !tail.is_empty() {
+            tail_fn(tail);
+        }
+    }
+}
+
+impl<'inp, 'out, T: Copy> InOutBuf<'inp, 'out, T> {
+    /// Copy values from the input slice to the output slice.
+    ///
+    /// It's a no-op if input and output slices are the same.
+    #[inline(always)]
+    pub fn copy_in2out(&mut self) {
+        if self.in_ptr != self.out_ptr {
+            let len = self.len * mem::size_of::<T>();
+            unsafe {
+                copy_bytes(
+                    self.in_ptr.as_ptr() as *const u8,
+                    self.out_ptr.as_ptr() as *mut u8,
+                    len,
+                )
+            }
+        }
+    }
+}
+
+#[cfg(feature = "cmov")]
+#[cfg_attr(docsrs, doc(cfg(feature = "cmov")))]
+impl<'inp, 'out, T: AnyBitPattern> InOutBuf<'inp, 'out, T> {
+    /// Copy values from the input slice to the output slice in constant time
+    /// if `condition` is not equal to zero.
+    ///
+    /// Values are processed as raw bytes, so types with padding bytes
+    /// are not supported.
+    #[inline(always)]
+    pub fn cmov_in2out(&mut self, condition: u8) {
+        let len = self.len * mem::size_of::<T>();
+        unsafe {
+            cmov_bytes(
+                condition,
+                self.in_ptr.as_ptr() as *const u8,
+                self.out_ptr.as_ptr() as *mut u8,
+                len,
+            )
+        }
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<'inp, 'out, T: DefaultIsZeroes> InOutBuf<'inp, 'out, T> {
+    /// Zeroize the output slice using volatile writes.
+    ///
+    /// If input and output slices are the same, it also wipes the input.
+    #[cfg_attr(docsrs, doc(cfg(feature = "zeroize")))]
+    #[inline(always)]
+    pub fn zeroize_out(&mut self) {
+        self.get_out().zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+#[cfg_attr(docsrs, doc(cfg(feature = "zeroize")))]
+impl<'inp, 'out, T: DefaultIsZeroes> Zeroize for InOutBuf<'inp, 'out, T> {
+    #[inline(always)]
+    fn zeroize(&mut self) {
+        self.zeroize_out();
+    }
 }
 
 impl<'inp, 'out> InOutBuf<'inp, 'out, u8> {
@@ -243,16 +516,29 @@ impl<'inp, 'out> InOutBuf<'inp, 'out, u8> {
     /// # Panics
     /// If `data` length is not equal to the buffer length.
     #[inline(always)]
-    #[allow(clippy::needless_range_loop)]
-    pub fn xor_in2out(&mut self, _data: &[u8]) {
-        assert_eq!(self.len(), _data.len());
-        unsafe {
-            for i in 0.._data.len() {
-                let in_ptr = self.in_ptr.add(i);
-                let out_ptr = self.out_ptr.add(i);
-                *out_ptr = *in_ptr ^ _data[i];
-            }
-        }
+    pub fn xor_in2out(&mut self, data: &[u8]) {
+        assert_eq!(self.len(), data.len());
+        unsafe { xor_bytes(self.in_ptr.as_ptr(), self.out_ptr.as_ptr(), data) }
+    }
+}
+
+impl<'inp, 'out, N: ArrayLength<u8>> InOutBuf<'inp, 'out, GenericArray<u8, N>> {
+    /// Partition buffer of blocks into groups of `M` blocks for parallel
+    /// processing and the remaining blocks.
+    ///
+    /// The remaining buffer is always shorter than `M`.
+    ///
+    /// # Panics
+    /// If `M` is equal to zero.
+    #[inline(always)]
+    #[allow(clippy::type_complexity)]
+    pub fn get_par_blocks<'a, M: ArrayLength<GenericArray<u8, N>>>(
+        &'a mut self,
+    ) -> (
+        InOutBuf<'a, 'a, GenericArray<GenericArray<u8, N>, M>>,
+        InOutBuf<'a, 'a, GenericArray<u8, N>>,
+    ) {
+        self.reborrow().into_chunks::<M>()
     }
 }
 
@@ -266,8 +552,8 @@ where
     fn try_into(self) -> Result<InOut<'inp, 'out, GenericArray<T, N>>, Self::Error> {
         if self.len() == N::USIZE {
             Ok(InOut {
-                in_ptr: self.in_ptr as *const _,
-                out_ptr: self.out_ptr as *mut _,
+                in_ptr: self.in_ptr.cast(),
+                out_ptr: self.out_ptr.cast(),
                 _pd: PhantomData,
             })
         } else {
@@ -276,6 +562,48 @@ where
     }
 }
 
+/// Raw parts of [`InOutBuf`] returned by [`InOutBuf::into_raw_parts`].
+#[derive(Debug)]
+pub struct InOutBufRawParts<T> {
+    /// Pointer to the input buffer
+    pub in_ptr: *const T,
+    /// Pointer to the output buffer
+    pub out_ptr: *mut T,
+    /// Number of elements in the buffers
+    pub len: usize,
+}
+
+impl<T> InOutBufRawParts<T> {
+    /// Length of the buffers in bytes.
+    #[inline(always)]
+    pub fn byte_len(&self) -> usize {
+        self.len * mem::size_of::<T>()
+    }
+}
+
+impl<T> Clone for InOutBufRawParts<T> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for InOutBufRawParts<T> {}
+
+/// Check whether buffers of `in_len` and `out_len` elements partially overlap,
+/// i.e. overlap without having equal start pointers.
+#[inline(always)]
+pub(crate) fn partially_overlap<T>(
+    in_ptr: *const T,
+    in_len: usize,
+    out_ptr: *const T,
+    out_len: usize,
+) -> bool {
+    let (a, b) = (in_ptr as usize, out_ptr as usize);
+    let size = mem::size_of::<T>();
+    a != b && a < b + size * out_len && b < a + size * in_len
+}
+
 /// Iterator over [`InOutBuf`].
 pub struct InOutBufIter<'inp, 'out, T> {
     buf: InOutBuf<'inp, 'out, T>,
@@ -292,12 +620,353 @@ impl<'inp, 'out, T> Iterator for InOutBufIter<'inp, 'out, T> {
         }
         let res = unsafe {
             InOut {
-                in_ptr: self.buf.in_ptr.add(self.pos),
-                out_ptr: self.buf.out_ptr.add(self.pos),
+                in_ptr: NonNull::new_unchecked(self.buf.in_ptr.as_ptr().add(self.pos)),
+                out_ptr: NonNull::new_unchecked(self.buf.out_ptr.as_ptr().add(self.pos)),
                 _pd: PhantomData,
             }
         };
         self.pos += 1;
         Some(res)
     }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.buf.len() - self.pos;
+        (n, Some(n))
+    }
+}
+
+impl<'inp, 'out, T> DoubleEndedIterator for InOutBufIter<'inp, 'out, T> {
+    #[inline(always)]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.buf.len() == self.pos {
+            return None;
+        }
+        self.buf.len -= 1;
+        let res = unsafe {
+            InOut {
+                in_ptr: NonNull::new_unchecked(self.buf.in_ptr.as_ptr().add(self.buf.len)),
+                out_ptr: NonNull::new_unchecked(self.buf.out_ptr.as_ptr().add(self.buf.len)),
+                _pd: PhantomData,
+            }
+        };
+        Some(res)
+    }
+}
+
+impl<'inp, 'out, T> ExactSizeIterator for InOutBufIter<'inp, 'out, T> {}
+
+/// Iterator over [`InOutBuf`] chunks.
+///
+/// This struct is created by the [`InOutBuf::chunks`] method.
+pub struct InOutBufChunks<'inp, 'out, T> {
+    buf: InOutBuf<'inp, 'out, T>,
+    chunk_size: usize,
+}
+
+impl<'inp, 'out, T> Iterator for InOutBufChunks<'inp, 'out, T> {
+    type Item = InOutBuf<'inp, 'out, T>;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buf.is_empty() {
+            return None;
+        }
+        let n = cmp::min(self.chunk_size, self.buf.len());
+        Some(self.buf.take_front(n))
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.buf.len();
+        let n = len / self.chunk_size + (len % self.chunk_size != 0) as usize;
+        (n, Some(n))
+    }
+}
+
+impl<'inp, 'out, T> ExactSizeIterator for InOutBufChunks<'inp, 'out, T> {}
+
+/// Iterator over [`InOutBuf`] chunks of equal length.
+///
+/// This struct is created by the [`InOutBuf::chunks_exact`] method.
+pub struct InOutBufChunksExact<'inp, 'out, T> {
+    buf: InOutBuf<'inp, 'out, T>,
+    rem: InOutBuf<'inp, 'out, T>,
+    chunk_size: usize,
+}
+
+impl<'inp, 'out, T> InOutBufChunksExact<'inp, 'out, T> {
+    /// Returns the remainder of the original buffer that is not going to be
+    /// returned by the iterator. The returned buffer has at most
+    /// `chunk_size - 1` elements.
+    #[inline(always)]
+    pub fn into_remainder(self) -> InOutBuf<'inp, 'out, T> {
+        self.rem
+    }
+}
+
+impl<'inp, 'out, T> Iterator for InOutBufChunksExact<'inp, 'out, T> {
+    type Item = InOutBuf<'inp, 'out, T>;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buf.is_empty() {
+            return None;
+        }
+        Some(self.buf.take_front(self.chunk_size))
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.buf.len() / self.chunk_size;
+        (n, Some(n))
+    }
+}
+
+impl<'inp, 'out, T> ExactSizeIterator for InOutBufChunksExact<'inp, 'out, T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn into_chunks_tail() {
+        use generic_array::typenum::{U1, U4};
+
+        let input = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+        let mut output = [0u8; 10];
+        let buf = InOutBuf::new(&input, &mut output).unwrap();
+        let (mut blocks, mut tail) = buf.into_chunks::<U4>();
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks.get_in()[1][..], [4, 5, 6, 7]);
+        assert_eq!(tail.get_in(), [8, 9]);
+        blocks.get_out()[1][0] = 0xFF;
+        tail.get_out()[1] = 0xEE;
+        assert_eq!(output, [0, 0, 0, 0, 0xFF, 0, 0, 0, 0, 0xEE]);
+
+        let mut buf = [0u8; 3];
+        let (blocks, tail) = InOutBuf::from(&mut buf[..]).into_chunks::<U1>();
+        assert_eq!((blocks.len(), tail.len()), (3, 0));
+    }
+
+    #[test]
+    fn process_blocks() {
+        use generic_array::typenum::U4;
+
+        let input = [1u8; 10];
+        let mut output = [0u8; 10];
+        let mut n = 0;
+        let buf = InOutBuf::new(&input, &mut output).unwrap();
+        buf.process_blocks::<U4>(
+            |mut block| {
+                n += 1;
+                block.xor_in2out(&GenericArray::from([n; 4]));
+            },
+            |mut tail| tail.xor_in2out(&[0xFF; 2]),
+        );
+        assert_eq!(output, [0, 0, 0, 0, 3, 3, 3, 3, 0xFE, 0xFE]);
+
+        let mut buf = [0u8; 8];
+        InOutBuf::from(&mut buf[..]).process_blocks::<U4>(|_| {}, |_| panic!("empty tail"));
+    }
+
+    #[test]
+    fn chunks() {
+        let input = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+        let mut output = [0u8; 10];
+
+        let buf = InOutBuf::new(&input, &mut output).unwrap();
+        let iter = buf.chunks(4);
+        assert_eq!(iter.len(), 3);
+        let lens: [usize; 3] = [4, 4, 2];
+        for (i, (mut chunk, len)) in iter.zip(lens.iter()).enumerate() {
+            assert_eq!(chunk.len(), *len);
+            assert_eq!(chunk.get_in()[0], 4 * i as u8);
+            chunk.get_out()[0] = 0xFF;
+        }
+        assert_eq!(output, [0xFF, 0, 0, 0, 0xFF, 0, 0, 0, 0xFF, 0]);
+
+        let buf = InOutBuf::new(&input, &mut output).unwrap();
+        let mut iter = buf.chunks_exact(3);
+        assert_eq!(iter.len(), 3);
+        for mut chunk in &mut iter {
+            assert_eq!(chunk.len(), 3);
+            chunk.get_out()[2] = 0xEE;
+        }
+        assert_eq!(iter.into_remainder().get_in(), [9]);
+        assert_eq!(output, [0xFF, 0, 0xEE, 0, 0xFF, 0xEE, 0, 0, 0xEE, 0]);
+
+        let mut buf = [0u8; 4];
+        assert_eq!(InOutBuf::from(&mut buf[..]).chunks_exact(2).count(), 2);
+        assert_eq!(InOutBuf::from(&mut buf[..0]).chunks(2).count(), 0);
+    }
+
+    #[test]
+    fn chunks_max_size() {
+        let mut buf = [0u8; 4];
+        let iter = InOutBuf::from(&mut buf[..]).chunks(usize::MAX);
+        assert_eq!(iter.len(), 1);
+        assert_eq!(iter.map(|chunk| chunk.len()).sum::<usize>(), 4);
+        assert_eq!(InOutBuf::from(&mut buf[..0]).chunks(usize::MAX).len(), 0);
+    }
+
+    #[test]
+    fn copy_in2out() {
+        let input = [1u32, 2, 3, 4, 5];
+        let mut output = [0u32; 5];
+        let mut buf = InOutBuf::new(&input, &mut output).unwrap();
+        buf.copy_in2out();
+        assert_eq!(buf.get_out(), input);
+
+        let mut buf = [1u8, 2, 3];
+        InOutBuf::from(&mut buf[..]).copy_in2out();
+        assert_eq!(buf, [1, 2, 3]);
+    }
+
+    #[test]
+    fn xor_in2out() {
+        let input = [0x0Fu8; 37];
+        let mut output = [0u8; 37];
+        let mut buf = InOutBuf::new(&input, &mut output).unwrap();
+        buf.xor_in2out(&[0x5A; 37]);
+        assert_eq!(buf.get_out(), [0x55; 37]);
+
+        let mut buf = [0x0Fu8; 5];
+        InOutBuf::from(&mut buf[..]).xor_in2out(&[0xF0; 5]);
+        assert_eq!(buf, [0xFF; 5]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn xor_in2out_len_mismatch() {
+        let mut buf = [0u8; 5];
+        InOutBuf::from(&mut buf[..]).xor_in2out(&[0; 4]);
+    }
+
+    #[test]
+    fn split_at() {
+        let input = [1u8, 2, 3, 4, 5];
+        let mut output = [0u8; 5];
+        let buf = InOutBuf::new(&input, &mut output).unwrap();
+        let (mut head, mut tail) = buf.split_at(2);
+        assert_eq!(
+            (head.get_in(), tail.get_in()),
+            (&[1, 2][..], &[3, 4, 5][..])
+        );
+        head.copy_in2out();
+        tail.xor_in2out(&[0xF0; 3]);
+        assert_eq!(output, [1, 2, 0xF3, 0xF4, 0xF5]);
+
+        let mut buf = [0u8; 2];
+        let (head, tail) = InOutBuf::from(&mut buf[..]).split_at(2);
+        assert_eq!((head.len(), tail.len()), (2, 0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn split_at_out_of_range() {
+        let mut buf = [0u8; 2];
+        let _ = InOutBuf::from(&mut buf[..]).split_at(3);
+    }
+
+    #[test]
+    fn iter() {
+        let input = [1u8, 2, 3, 4];
+        let mut output = [0u8; 4];
+        let mut buf = InOutBuf::new(&input, &mut output).unwrap();
+        for mut io in buf.iter_mut() {
+            *io.get_out() = 2 * io.get_in();
+        }
+        let mut iter = (&mut buf).into_iter();
+        assert_eq!(iter.len(), 4);
+        assert_eq!(*iter.next_back().unwrap().get_in(), 4);
+        assert_eq!(*iter.next().unwrap().get_in(), 1);
+        assert_eq!(iter.len(), 2);
+        for mut io in buf.into_iter().rev() {
+            *io.get_out() += 1;
+        }
+        assert_eq!(output, [3, 5, 7, 9]);
+    }
+
+    #[test]
+    fn get_par_blocks() {
+        use generic_array::typenum::{U2, U4};
+
+        let mut blocks = [GenericArray::<u8, U2>::default(); 11];
+        let mut buf = InOutBuf::from(&mut blocks[..]);
+        let (par_blocks, tail) = buf.get_par_blocks::<U4>();
+        assert_eq!((par_blocks.len(), tail.len()), (2, 3));
+        for mut par_block in par_blocks {
+            par_block.xor_in2out(&GenericArray::generate(|_| GenericArray::generate(|_| 1)));
+        }
+        for mut block in tail {
+            block.xor_in2out(&GenericArray::generate(|_| 2));
+        }
+        assert!(blocks[..8].iter().all(|b| b[..] == [1, 1]));
+        assert!(blocks[8..].iter().all(|b| b[..] == [2, 2]));
+    }
+
+    #[test]
+    fn map_in2out() {
+        let mut buf = [1u8, 2, 3];
+        InOutBuf::from(&mut buf[..]).map_in2out(|v| v ^ 0xFF);
+        assert_eq!(buf, [0xFE, 0xFD, 0xFC]);
+
+        let mut output = [0u8; 3];
+        let mut n = 0;
+        InOutBuf::new(&buf, &mut output).unwrap().map_in2out(|v| {
+            n += 1;
+            v.wrapping_add(n)
+        });
+        assert_eq!(output, [0xFF, 0xFF, 0xFF]);
+    }
+
+    #[test]
+    fn fallible_accessors() {
+        let mut buf = [1u8, 2, 3];
+        let mut buf = InOutBuf::from(&mut buf[..]);
+        assert_eq!(*buf.try_get(2).unwrap().get_in(), 3);
+        assert!(buf.try_get(3).is_err());
+        let (head, tail) = buf.reborrow().try_split_at(3).unwrap();
+        assert_eq!((head.len(), tail.len()), (3, 0));
+        assert!(buf.try_split_at(4).is_err());
+    }
+
+    #[test]
+    fn from_raw_checked() {
+        let mut buf = [0u16; 8];
+        let p = buf.as_mut_ptr();
+        unsafe {
+            assert!(InOutBuf::from_raw_checked(p, p, 8).is_ok());
+            assert!(InOutBuf::from_raw_checked(p, p.add(4), 4).is_ok());
+            assert!(InOutBuf::from_raw_checked(p.add(4), p, 4).is_ok());
+            assert!(InOutBuf::from_raw_checked(p, p.add(3), 4).is_err());
+            assert!(InOutBuf::from_raw_checked(p.add(3), p, 4).is_err());
+        }
+    }
+
+    #[cfg(feature = "cmov")]
+    #[test]
+    fn cmov_in2out() {
+        let input = [1u32, 2, 3];
+        let mut output = [0u32; 3];
+        let mut buf = InOutBuf::new(&input, &mut output).unwrap();
+        buf.cmov_in2out(0);
+        assert_eq!(buf.get_out(), [0; 3]);
+        buf.cmov_in2out(1);
+        assert_eq!(buf.get_out(), input);
+    }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn zeroize_out() {
+        let input = [1u8; 4];
+        let mut output = [2u8; 4];
+        InOutBuf::new(&input, &mut output).unwrap().zeroize_out();
+        assert_eq!((input, output), ([1; 4], [0; 4]));
+
+        let mut buf = [3u16; 4];
+        InOutBuf::from(&mut buf[..]).zeroize();
+        assert_eq!(buf, [0; 4]);
+    }
 }
@@ -0,0 +1,158 @@
+use crate::{InOutBuf, InOutBufChunks};
+use alloc::vec::Vec;
+use core::{marker::PhantomData, ptr::NonNull};
+use generic_array::{ArrayLength, GenericArray};
+
+/// Custom buffer type which references an immutable (input) slice and
+/// appends output values to an owned vector.
+///
+/// On creation input values are copied to the end of the vector, after
+/// which the appended part can be processed as an [`InOutBuf`] with
+/// non-overlapping input and output slices.
+///
+/// # Examples
+/// ```
+/// let mut buf = inout::InOutVec::new(b"hello");
+/// buf.xor_in2out(&[1; 5]);
+/// assert_eq!(buf.into_vec(), b"idmmn");
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub struct InOutVec<'inp, T> {
+    in_buf: &'inp [T],
+    out: Vec<T>,
+    pos: usize,
+}
+
+impl<'inp, T: Clone> InOutVec<'inp, T> {
+    /// Create `InOutVec` which writes output values into a new vector.
+    #[inline]
+    pub fn new(in_buf: &'inp [T]) -> Self {
+        Self::with_vec(in_buf, Vec::with_capacity(in_buf.len()))
+    }
+
+    /// Create `InOutVec` which appends output values to `out`.
+    ///
+    /// Existing elements of `out` are left untouched.
+    #[inline]
+    pub fn with_vec(in_buf: &'inp [T], mut out: Vec<T>) -> Self {
+        let pos = out.len();
+        out.extend_from_slice(in_buf);
+        Self { in_buf, out, pos }
+    }
+}
+
+impl<'inp, T> InOutVec<'inp, T> {
+    /// Get length of the input slice.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.in_buf.len()
+    }
+
+    /// Returns `true` if the input slice has a length of 0.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.in_buf.is_empty()
+    }
+
+    /// Get input slice.
+    #[inline(always)]
+    pub fn get_in(&self) -> &'inp [T] {
+        self.in_buf
+    }
+
+    /// Get appended part of the output vector.
+    #[inline(always)]
+    pub fn get_out<'a>(&'a mut self) -> &'a mut [T] {
+        &mut self.out[self.pos..]
+    }
+
+    /// Get [`InOutBuf`] over the input slice and the appended part of
+    /// the output vector.
+    #[inline(always)]
+    pub fn reborrow<'a>(&'a mut self) -> InOutBuf<'inp, 'a, T> {
+        let out = &mut self.out[self.pos..];
+        InOutBuf {
+            in_ptr: NonNull::from(self.in_buf).cast(),
+            len: out.len(),
+            out_ptr: NonNull::from(out).cast(),
+            _pd: PhantomData,
+        }
+    }
+
+    /// Returns an iterator over `chunk_size` elements of the buffer at
+    /// a time, see [`InOutBuf::chunks`] for more information.
+    ///
+    /// # Panics
+    /// If `chunk_size` is equal to zero.
+    #[inline(always)]
+    pub fn chunks<'a>(&'a mut self, chunk_size: usize) -> InOutBufChunks<'inp, 'a, T> {
+        self.reborrow().chunks(chunk_size)
+    }
+
+    /// Divide buffer into chunks of `N` elements and the remaining tail,
+    /// see [`InOutBuf::into_chunks`] for more information.
+    ///
+    /// # Panics
+    /// If `N` is equal to zero.
+    #[inline(always)]
+    #[allow(clippy::type_complexity)]
+    pub fn get_chunks<'a, N: ArrayLength<T>>(
+        &'a mut self,
+    ) -> (
+        InOutBuf<'inp, 'a, GenericArray<T, N>>,
+        InOutBuf<'inp, 'a, T>,
+    ) {
+        self.reborrow().into_chunks()
+    }
+
+    /// Consume `self` and return the output vector.
+    #[inline(always)]
+    pub fn into_vec(self) -> Vec<T> {
+        self.out
+    }
+}
+
+impl<'inp> InOutVec<'inp, u8> {
+    /// XORs `data` with values behind the input slice and write
+    /// result to the output vector.
+    ///
+    /// # Panics
+    /// If `data` length is not equal to the input slice length.
+    #[inline(always)]
+    pub fn xor_in2out(&mut self, data: &[u8]) {
+        self.reborrow().xor_in2out(data)
+    }
+}
+
+impl<'inp, T> From<InOutVec<'inp, T>> for Vec<T> {
+    #[inline(always)]
+    fn from(buf: InOutVec<'inp, T>) -> Self {
+        buf.into_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inout_vec() {
+        use generic_array::typenum::U2;
+
+        let input = [1u8, 2, 3, 4, 5];
+        let mut buf = InOutVec::with_vec(&input, alloc::vec![0xAA]);
+        assert_eq!(buf.len(), 5);
+        assert_eq!(buf.get_out(), input);
+        buf.xor_in2out(&[0xFF; 5]);
+        assert_eq!(buf.get_in(), input);
+
+        let (mut blocks, mut tail) = buf.get_chunks::<U2>();
+        assert_eq!((blocks.len(), tail.len()), (2, 1));
+        blocks.get(1).get_out()[0] = 0;
+        tail.get_out()[0] = 0;
+        assert_eq!(buf.chunks(2).map(|c| c.len()).sum::<usize>(), 5);
+
+        let v: Vec<u8> = buf.into();
+        assert_eq!(v, [0xAA, 0xFE, 0xFD, 0, 0xFB, 0]);
+    }
+}
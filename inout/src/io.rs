@@ -0,0 +1,231 @@
+//! Streaming adapters for [`std::io`] traits.
+use crate::InOutBuf;
+use alloc::vec::Vec;
+use std::io::{self, ErrorKind, Read, Write};
+
+/// Read `buf.len()` bytes from `reader` unless EOF is reached first.
+///
+/// Returns number of read bytes.
+fn read_window<R: Read>(reader: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    let mut n = 0;
+    while n < buf.len() {
+        match reader.read(&mut buf[n..]) {
+            Ok(0) => break,
+            Ok(k) => n += k,
+            Err(e) if e.kind() == ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(n)
+}
+
+/// Reader which processes data pulled from the inner reader by windows
+/// of fixed size.
+///
+/// Data is passed to the closure as [`InOutBuf`] which input slice contains
+/// read data and output slice is returned to the caller. All windows except
+/// the last one have length equal to the window size.
+///
+/// # Examples
+/// ```
+/// use std::io::Read;
+///
+/// let data = [1u8; 10];
+/// let mut reader = inout::InOutReader::new(&data[..], 4, |mut buf| {
+///     let len = buf.len();
+///     buf.xor_in2out(&[0xFF; 4][..len]);
+/// });
+/// let mut res = Vec::new();
+/// reader.read_to_end(&mut res).unwrap();
+/// assert_eq!(res, [0xFE; 10]);
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub struct InOutReader<R, F> {
+    inner: R,
+    f: F,
+    in_buf: Vec<u8>,
+    out_buf: Vec<u8>,
+    pos: usize,
+    filled: usize,
+}
+
+impl<R: Read, F: FnMut(InOutBuf<'_, '_, u8>)> InOutReader<R, F> {
+    /// Create new reader which processes data by windows of `window` bytes.
+    ///
+    /// # Panics
+    /// If `window` is equal to zero.
+    pub fn new(inner: R, window: usize, f: F) -> Self {
+        assert_ne!(window, 0, "window size must be non-zero");
+        Self {
+            inner,
+            f,
+            in_buf: alloc::vec![0; window],
+            out_buf: alloc::vec![0; window],
+            pos: 0,
+            filled: 0,
+        }
+    }
+
+    /// Get reference to the inner reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Consume `self` and return the inner reader.
+    ///
+    /// Processed data which was not read yet is lost.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read, F: FnMut(InOutBuf<'_, '_, u8>)> Read for InOutReader<R, F> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pos == self.filled {
+            let n = read_window(&mut self.inner, &mut self.in_buf)?;
+            if n == 0 {
+                return Ok(0);
+            }
+            let (in_buf, out_buf) = (&self.in_buf[..n], &mut self.out_buf[..n]);
+            (self.f)(InOutBuf::new(in_buf, out_buf).unwrap());
+            self.pos = 0;
+            self.filled = n;
+        }
+        let data = &self.out_buf[self.pos..self.filled];
+        let n = core::cmp::min(buf.len(), data.len());
+        buf[..n].copy_from_slice(&data[..n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+/// Writer which processes data by windows of fixed size before pushing it
+/// to the inner writer.
+///
+/// Data is passed to the closure as [`InOutBuf`] which input slice contains
+/// written data and output slice is written to the inner writer. All windows
+/// except the last one have length equal to the window size. The last
+/// partial window is processed only by [`InOutWriter::finish`].
+///
+/// # Examples
+/// ```
+/// use std::io::Write;
+///
+/// let mut writer = inout::InOutWriter::new(Vec::new(), 4, |mut buf| {
+///     let len = buf.len();
+///     buf.xor_in2out(&[0xFF; 4][..len]);
+/// });
+/// writer.write_all(&[1u8; 10]).unwrap();
+/// assert_eq!(writer.finish().unwrap(), [0xFE; 10]);
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub struct InOutWriter<W, F> {
+    inner: W,
+    f: F,
+    in_buf: Vec<u8>,
+    out_buf: Vec<u8>,
+    pos: usize,
+}
+
+impl<W: Write, F: FnMut(InOutBuf<'_, '_, u8>)> InOutWriter<W, F> {
+    /// Create new writer which processes data by windows of `window` bytes.
+    ///
+    /// # Panics
+    /// If `window` is equal to zero.
+    pub fn new(inner: W, window: usize, f: F) -> Self {
+        assert_ne!(window, 0, "window size must be non-zero");
+        Self {
+            inner,
+            f,
+            in_buf: alloc::vec![0; window],
+            out_buf: alloc::vec![0; window],
+            pos: 0,
+        }
+    }
+
+    /// Get reference to the inner writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Process the buffered partial window, flush the inner writer and
+    /// return it.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.process()?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+
+    fn process(&mut self) -> io::Result<()> {
+        let n = self.pos;
+        if n == 0 {
+            return Ok(());
+        }
+        let (in_buf, out_buf) = (&self.in_buf[..n], &mut self.out_buf[..n]);
+        (self.f)(InOutBuf::new(in_buf, out_buf).unwrap());
+        self.pos = 0;
+        self.inner.write_all(&self.out_buf[..n])
+    }
+}
+
+impl<W: Write, F: FnMut(InOutBuf<'_, '_, u8>)> Write for InOutWriter<W, F> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = core::cmp::min(buf.len(), self.in_buf.len() - self.pos);
+        self.in_buf[self.pos..][..n].copy_from_slice(&buf[..n]);
+        self.pos += n;
+        if self.pos == self.in_buf.len() {
+            self.process()?;
+        }
+        Ok(n)
+    }
+
+    /// Flush the inner writer.
+    ///
+    /// Buffered partial window is not processed, use [`InOutWriter::finish`]
+    /// to process it.
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reader_windows() {
+        let data: Vec<u8> = (0..10).collect();
+        let mut lens = Vec::new();
+        let mut reader = InOutReader::new(&data[..], 4, |mut buf| {
+            lens.push(buf.len());
+            for mut v in buf.reborrow() {
+                *v.get_out() = v.get_in() + 1;
+            }
+        });
+        let mut res = [0u8; 3];
+        reader.read_exact(&mut res).unwrap();
+        assert_eq!(res, [1, 2, 3]);
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, [4, 5, 6, 7, 8, 9, 10]);
+        drop(reader);
+        assert_eq!(lens, [4, 4, 2]);
+    }
+
+    #[test]
+    fn writer_windows() {
+        let mut lens = Vec::new();
+        let mut writer = InOutWriter::new(Vec::new(), 4, |mut buf| {
+            lens.push(buf.len());
+            for mut v in buf.reborrow() {
+                *v.get_out() = v.get_in() + 1;
+            }
+        });
+        writer.write_all(&[0, 1, 2]).unwrap();
+        assert!(writer.get_ref().is_empty());
+        writer.write_all(&[3, 4, 5]).unwrap();
+        assert_eq!(writer.get_ref(), &[1, 2, 3, 4]);
+        assert_eq!(writer.finish().unwrap(), [1, 2, 3, 4, 5, 6]);
+        assert_eq!(lens, [4, 2]);
+    }
+}
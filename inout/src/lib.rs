@@ -10,6 +10,8 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
 #![warn(missing_docs, rust_2018_idioms)]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
 #[cfg(feature = "std")]
 extern crate std;
 
@@ -17,9 +19,32 @@ extern crate std;
 #[cfg_attr(docsrs, doc(cfg(feature = "block-padding")))]
 pub use block_padding;
 
+mod aligned;
 mod errors;
 mod inout;
 mod inout_buf;
+#[cfg(feature = "alloc")]
+mod inout_vec;
+#[cfg(feature = "std")]
+mod io;
 mod reserved;
+mod strided;
+mod tmp;
+mod uninit;
+mod xor;
+
+pub use crate::{
+    aligned::{AlignedInOutBuf, AnyBitPattern},
+    errors::*,
+    inout::*,
+    inout_buf::*,
+    reserved::*,
+    strided::*,
+    tmp::InTmpOutBuf,
+    uninit::*,
+};
 
-pub use crate::{errors::*, inout::*, inout_buf::*, reserved::*};
+#[cfg(feature = "alloc")]
+pub use crate::inout_vec::InOutVec;
+#[cfg(feature = "std")]
+pub use crate::io::{InOutReader, InOutWriter};
@@ -1,10 +1,10 @@
-use crate::errors::OutIsTooSmallError;
+use crate::{errors::OutIsTooSmallError, inout_buf::partially_overlap, InOutBuf};
 use core::{marker::PhantomData, slice};
 
 #[cfg(feature = "block-padding")]
 use crate::errors::PadError;
 #[cfg(feature = "block-padding")]
-use crate::{InOut, InOutBuf};
+use crate::InOut;
 #[cfg(feature = "block-padding")]
 use block_padding::{PadType, Padding};
 #[cfg(feature = "block-padding")]
@@ -67,6 +67,7 @@ impl<'a, T> InOutBufReserved<'a, 'a, T> {
         out_ptr: *mut T,
         out_len: usize,
     ) -> Self {
+        debug_assert!(!partially_overlap(in_ptr, in_len, out_ptr, out_len));
         Self {
             in_ptr,
             out_ptr,
@@ -91,7 +92,7 @@ impl<'a, T> InOutBufReserved<'a, 'a, T> {
     /// Get output buffer length.
     #[inline(always)]
     pub fn get_out_len(&self) -> usize {
-        self.in_len
+        self.out_len
     }
 }
 
@@ -124,6 +125,23 @@ impl<'inp, 'out, T> InOutBufReserved<'inp, 'out, T> {
     pub fn get_out<'a>(&'a mut self) -> &'a mut [T] {
         unsafe { slice::from_raw_parts_mut(self.out_ptr, self.out_len) }
     }
+
+    /// Get [`InOutBuf`] which references the input slice and the first
+    /// `in_len` elements of the output slice.
+    #[inline(always)]
+    pub fn get_msg<'a>(&'a mut self) -> InOutBuf<'a, 'a, T> {
+        unsafe { InOutBuf::from_raw(self.in_ptr, self.out_ptr, self.in_len) }
+    }
+
+    /// Get reserved part of the output slice, i.e. elements which follow
+    /// the first `in_len` elements.
+    #[inline(always)]
+    pub fn get_reserved<'a>(&'a mut self) -> &'a mut [T] {
+        unsafe {
+            let ptr = self.out_ptr.add(self.in_len);
+            slice::from_raw_parts_mut(ptr, self.out_len - self.in_len)
+        }
+    }
 }
 
 impl<'inp, 'out> InOutBufReserved<'inp, 'out, u8> {
@@ -225,3 +243,27 @@ impl<'inp, 'out, BS: ArrayLength<u8>> PaddedInOutBuf<'inp, 'out, BS> {
         unsafe { slice::from_raw_parts(out_ptr as *const u8, res_len) }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reserved_parts() {
+        let mut buf = [1u8, 2, 3, 0, 0];
+        let mut res = InOutBufReserved::from_mut_slice(&mut buf, 3).unwrap();
+        assert_eq!((res.get_in_len(), res.get_out_len()), (3, 5));
+        res.get_msg().xor_in2out(&[1, 1, 1]);
+        res.get_reserved().copy_from_slice(&[4, 5]);
+        assert_eq!(res.get_in(), [0, 3, 2]);
+        assert_eq!(buf, [0, 3, 2, 4, 5]);
+
+        let input = [1u8, 2];
+        let mut output = [0u8; 4];
+        let mut res = InOutBufReserved::from_slices(&input, &mut output).unwrap();
+        assert_eq!(res.get_msg().get_in(), [1, 2]);
+        assert_eq!(res.get_reserved().len(), 2);
+        assert!(InOutBufReserved::from_slices(&output, &mut [0u8; 3]).is_err());
+        assert!(InOutBufReserved::from_mut_slice(&mut output, 5).is_err());
+    }
+}
@@ -0,0 +1,184 @@
+use crate::{InOut, InOutBuf};
+use core::{marker::PhantomData, ptr::NonNull};
+
+/// Strided view into [`InOutBuf`] which contains every `stride`-th element
+/// of the buffer.
+///
+/// This struct is created by the [`InOutBufStrideIter`] iterator.
+pub struct InOutBufStrided<'inp, 'out, T> {
+    in_ptr: NonNull<T>,
+    out_ptr: NonNull<T>,
+    len: usize,
+    stride: usize,
+    _pd: PhantomData<(&'inp T, &'out mut T)>,
+}
+
+impl<'inp, 'out, T> InOutBufStrided<'inp, 'out, T> {
+    /// Get number of elements in the view.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the view has a length of 0.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `InOut` for given position in the view.
+    ///
+    /// # Panics
+    /// If `pos` greater or equal to the view length.
+    #[inline(always)]
+    pub fn get<'a>(&'a mut self, pos: usize) -> InOut<'a, 'a, T> {
+        assert!(pos < self.len);
+        unsafe { self.get_raw(pos) }
+    }
+
+    /// Returns `InOut` for position `pos`, which must be smaller than
+    /// the view length.
+    #[inline(always)]
+    unsafe fn get_raw<'i, 'o>(&self, pos: usize) -> InOut<'i, 'o, T> {
+        let offset = pos * self.stride;
+        InOut {
+            in_ptr: NonNull::new_unchecked(self.in_ptr.as_ptr().add(offset)),
+            out_ptr: NonNull::new_unchecked(self.out_ptr.as_ptr().add(offset)),
+            _pd: PhantomData,
+        }
+    }
+}
+
+impl<'inp, 'out, T> IntoIterator for InOutBufStrided<'inp, 'out, T> {
+    type Item = InOut<'inp, 'out, T>;
+    type IntoIter = InOutBufStridedIter<'inp, 'out, T>;
+
+    #[inline(always)]
+    fn into_iter(self) -> Self::IntoIter {
+        InOutBufStridedIter { view: self, pos: 0 }
+    }
+}
+
+/// Iterator over elements of [`InOutBufStrided`].
+pub struct InOutBufStridedIter<'inp, 'out, T> {
+    view: InOutBufStrided<'inp, 'out, T>,
+    pos: usize,
+}
+
+impl<'inp, 'out, T> Iterator for InOutBufStridedIter<'inp, 'out, T> {
+    type Item = InOut<'inp, 'out, T>;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos == self.view.len {
+            return None;
+        }
+        let res = unsafe { self.view.get_raw(self.pos) };
+        self.pos += 1;
+        Some(res)
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.view.len - self.pos;
+        (n, Some(n))
+    }
+}
+
+impl<'inp, 'out, T> ExactSizeIterator for InOutBufStridedIter<'inp, 'out, T> {}
+
+/// Iterator over interleaved lanes of [`InOutBuf`].
+///
+/// This struct is created by the [`InOutBuf::stride_iter`] method.
+pub struct InOutBufStrideIter<'inp, 'out, T> {
+    buf: InOutBuf<'inp, 'out, T>,
+    lanes: usize,
+    lane: usize,
+}
+
+impl<'inp, 'out, T> Iterator for InOutBufStrideIter<'inp, 'out, T> {
+    type Item = InOutBufStrided<'inp, 'out, T>;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (lane, lanes, len) = (self.lane, self.lanes, self.buf.len);
+        if lane == lanes {
+            return None;
+        }
+        self.lane += 1;
+        // empty lanes point to the start of the buffer to stay in bounds
+        let (offset, lane_len) = if lane < len {
+            (lane, (len - lane - 1) / lanes + 1)
+        } else {
+            (0, 0)
+        };
+        let res = unsafe {
+            InOutBufStrided {
+                in_ptr: NonNull::new_unchecked(self.buf.in_ptr.as_ptr().add(offset)),
+                out_ptr: NonNull::new_unchecked(self.buf.out_ptr.as_ptr().add(offset)),
+                len: lane_len,
+                stride: lanes,
+                _pd: PhantomData,
+            }
+        };
+        Some(res)
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.lanes - self.lane;
+        (n, Some(n))
+    }
+}
+
+impl<'inp, 'out, T> ExactSizeIterator for InOutBufStrideIter<'inp, 'out, T> {}
+
+impl<'inp, 'out, T> InOutBuf<'inp, 'out, T> {
+    /// Returns an iterator over `lanes` interleaved views of the buffer.
+    ///
+    /// The `i`-th view contains elements with indices `i`, `i + lanes`,
+    /// `i + 2 * lanes`, and so on. Views do not overlap, so they can be
+    /// processed independently, e.g. as parallel lanes of a SIMD backend.
+    ///
+    /// # Panics
+    /// If `lanes` is equal to zero.
+    #[inline(always)]
+    pub fn stride_iter(self, lanes: usize) -> InOutBufStrideIter<'inp, 'out, T> {
+        assert_ne!(lanes, 0, "number of lanes must be non-zero");
+        InOutBufStrideIter {
+            buf: self,
+            lanes,
+            lane: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stride_iter() {
+        let input: [u8; 10] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+        let mut output = [0u8; 10];
+        let buf = InOutBuf::new(&input, &mut output).unwrap();
+        let mut lanes = buf.stride_iter(4);
+        assert_eq!(lanes.len(), 4);
+        for (i, lane) in lanes.by_ref().take(3).enumerate() {
+            assert_eq!(lane.len(), if i < 2 { 3 } else { 2 });
+            for mut v in lane {
+                *v.get_out() = *v.get_in() + 10 * i as u8;
+            }
+        }
+        let mut lane = lanes.next().unwrap();
+        assert_eq!(*lane.get(1).get_in(), 7);
+        assert!(lanes.next().is_none());
+        assert_eq!(output, [0, 11, 22, 0, 4, 15, 26, 0, 8, 19]);
+
+        let input = [1u8, 2];
+        let mut output = [0u8; 2];
+        let buf = InOutBuf::new(&input, &mut output).unwrap();
+        let lens: [usize; 3] = [1, 1, 0];
+        assert!(buf.stride_iter(3).map(|l| l.len()).eq(lens.iter().copied()));
+    }
+}
@@ -0,0 +1,135 @@
+use crate::{errors::NotEqualError, xor::xor_bytes, InOutBuf};
+
+/// Custom buffer type which references input and output slices together
+/// with a temporary (scratch) slice of equal length.
+///
+/// It's used by modes which have to stash input values before overwriting
+/// the output, e.g. in-place CBC decryption. The temporary slice never
+/// overlaps with the input and output slices.
+pub struct InTmpOutBuf<'inp, 'tmp, 'out, T> {
+    buf: InOutBuf<'inp, 'out, T>,
+    tmp: &'tmp mut [T],
+}
+
+impl<'inp, 'tmp, 'out, T> InTmpOutBuf<'inp, 'tmp, 'out, T> {
+    /// Create `InTmpOutBuf` from [`InOutBuf`] and a temporary slice.
+    ///
+    /// Returns an error if length of the temporary slice is not equal to
+    /// the buffer length.
+    #[inline(always)]
+    pub fn new(buf: InOutBuf<'inp, 'out, T>, tmp: &'tmp mut [T]) -> Result<Self, NotEqualError> {
+        if buf.len() != tmp.len() {
+            Err(NotEqualError)
+        } else {
+            Ok(Self { buf, tmp })
+        }
+    }
+
+    /// Get length of the inner buffers.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.tmp.len()
+    }
+
+    /// Returns `true` if the buffer has a length of 0.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.tmp.is_empty()
+    }
+
+    /// Get input slice.
+    #[inline(always)]
+    pub fn get_in<'a>(&'a self) -> &'a [T] {
+        self.buf.get_in()
+    }
+
+    /// Get temporary slice.
+    #[inline(always)]
+    pub fn get_tmp<'a>(&'a mut self) -> &'a mut [T] {
+        self.tmp
+    }
+
+    /// Get output slice.
+    #[inline(always)]
+    pub fn get_out<'a>(&'a mut self) -> &'a mut [T] {
+        self.buf.get_out()
+    }
+
+    /// Get [`InOutBuf`] over the input and output slices.
+    #[inline(always)]
+    pub fn get_inout<'a>(&'a mut self) -> InOutBuf<'a, 'a, T> {
+        self.buf.reborrow()
+    }
+
+    /// Get the temporary slice and [`InOutBuf`] over the input and output
+    /// slices at the same time.
+    #[inline(always)]
+    pub fn get_tmp_inout<'a>(&'a mut self) -> (&'a mut [T], InOutBuf<'a, 'a, T>) {
+        (self.tmp, self.buf.reborrow())
+    }
+
+    /// Convert `self` into [`InOutBuf`] and the temporary slice.
+    #[inline(always)]
+    pub fn into_parts(self) -> (InOutBuf<'inp, 'out, T>, &'tmp mut [T]) {
+        (self.buf, self.tmp)
+    }
+}
+
+impl<'inp, 'tmp, 'out, T: Copy> InTmpOutBuf<'inp, 'tmp, 'out, T> {
+    /// Copy input values to the temporary slice.
+    #[inline(always)]
+    pub fn copy_in_to_tmp(&mut self) {
+        self.tmp.copy_from_slice(self.buf.get_in());
+    }
+
+    /// Copy values of the temporary slice to the output.
+    #[inline(always)]
+    pub fn copy_tmp2out(&mut self) {
+        self.buf.get_out().copy_from_slice(self.tmp);
+    }
+}
+
+impl<'inp, 'tmp, 'out> InTmpOutBuf<'inp, 'tmp, 'out, u8> {
+    /// XOR values of the temporary slice with the output values and write
+    /// result to the output slice.
+    #[inline(always)]
+    pub fn xor_tmp2out(&mut self) {
+        let out_ptr = self.buf.get_out().as_mut_ptr();
+        unsafe { xor_bytes(out_ptr, out_ptr, self.tmp) }
+    }
+
+    /// XOR values of the temporary slice with the input values and write
+    /// result to the output slice.
+    #[inline(always)]
+    pub fn xor_in_tmp2out(&mut self) {
+        self.buf.xor_in2out(self.tmp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn in_tmp_out() {
+        // in-place CBC-like decryption: out = D(c) ^ c, where D is XOR with 0x0F
+        let mut data = [0x10u8, 0x20, 0x30];
+        let mut tmp = [0u8; 3];
+        let mut buf = InTmpOutBuf::new(InOutBuf::from(&mut data[..]), &mut tmp).unwrap();
+        assert_eq!(buf.len(), 3);
+        buf.copy_in_to_tmp();
+        buf.get_inout().xor_in2out(&[0x0F; 3]);
+        buf.xor_tmp2out();
+        assert_eq!(buf.get_out(), [0x0F; 3]);
+
+        buf.get_tmp().copy_from_slice(&[0xFF, 0, 0]);
+        buf.xor_in_tmp2out();
+        assert_eq!(buf.get_out(), [0xF0, 0x0F, 0x0F]);
+        buf.copy_tmp2out();
+        let (mut inout, tmp) = buf.into_parts();
+        assert_eq!(inout.get_out(), tmp);
+
+        let mut tmp = [0u8; 2];
+        assert!(InTmpOutBuf::new(InOutBuf::from(&mut data[..]), &mut tmp).is_err());
+    }
+}
@@ -0,0 +1,137 @@
+use crate::{errors::NotEqualError, InOut, InOutBuf};
+use core::{marker::PhantomData, mem::MaybeUninit, slice};
+
+/// Variant of [`InOut`] with potentially uninitialized output value.
+///
+/// Input and output values are always non-overlapping.
+pub struct InOutMaybeUninit<'inp, 'out, T> {
+    in_ptr: *const T,
+    out_ptr: *mut MaybeUninit<T>,
+    _pd: PhantomData<(&'inp T, &'out mut MaybeUninit<T>)>,
+}
+
+impl<'inp, 'out, T> InOutMaybeUninit<'inp, 'out, T> {
+    /// Get immutable reference to the input value.
+    #[inline(always)]
+    pub fn get_in<'a>(&'a self) -> &'a T {
+        unsafe { &*self.in_ptr }
+    }
+
+    /// Get mutable reference to the potentially uninitialized output value.
+    #[inline(always)]
+    pub fn get_out<'a>(&'a mut self) -> &'a mut MaybeUninit<T> {
+        unsafe { &mut *self.out_ptr }
+    }
+
+    /// Convert `self` into [`InOut`] with initialized output value.
+    ///
+    /// # Safety
+    /// The output value must be properly initialized, e.g. by writing
+    /// to it using [`InOutMaybeUninit::get_out`].
+    #[inline(always)]
+    pub unsafe fn assume_init(self) -> InOut<'inp, 'out, T> {
+        InOut::from_raw(self.in_ptr, self.out_ptr as *mut T)
+    }
+}
+
+impl<'inp, 'out, T> From<(&'inp T, &'out mut MaybeUninit<T>)> for InOutMaybeUninit<'inp, 'out, T> {
+    #[inline(always)]
+    fn from((in_val, out_val): (&'inp T, &'out mut MaybeUninit<T>)) -> Self {
+        Self {
+            in_ptr: in_val as *const T,
+            out_ptr: out_val as *mut MaybeUninit<T>,
+            _pd: PhantomData,
+        }
+    }
+}
+
+/// Variant of [`InOutBuf`] with potentially uninitialized output slice.
+///
+/// Input and output slices have equal length and are always non-overlapping.
+pub struct InOutBufMaybeUninit<'inp, 'out, T> {
+    in_ptr: *const T,
+    out_ptr: *mut MaybeUninit<T>,
+    len: usize,
+    _pd: PhantomData<(&'inp T, &'out mut MaybeUninit<T>)>,
+}
+
+impl<'inp, 'out, T> InOutBufMaybeUninit<'inp, 'out, T> {
+    /// Create `InOutBufMaybeUninit` from immutable input slice and mutable
+    /// slice of potentially uninitialized output values.
+    ///
+    /// Returns an error if length of slices is not equal to each other.
+    #[inline(always)]
+    pub fn new(
+        in_buf: &'inp [T],
+        out_buf: &'out mut [MaybeUninit<T>],
+    ) -> Result<Self, NotEqualError> {
+        if in_buf.len() != out_buf.len() {
+            Err(NotEqualError)
+        } else {
+            Ok(Self {
+                in_ptr: in_buf.as_ptr(),
+                out_ptr: out_buf.as_mut_ptr(),
+                len: in_buf.len(),
+                _pd: PhantomData,
+            })
+        }
+    }
+
+    /// Get length of the inner buffers.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the buffer has a length of 0.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get input slice.
+    #[inline(always)]
+    pub fn get_in<'a>(&'a self) -> &'a [T] {
+        unsafe { slice::from_raw_parts(self.in_ptr, self.len) }
+    }
+
+    /// Get potentially uninitialized output slice.
+    #[inline(always)]
+    pub fn get_out<'a>(&'a mut self) -> &'a mut [MaybeUninit<T>] {
+        unsafe { slice::from_raw_parts_mut(self.out_ptr, self.len) }
+    }
+
+    /// Convert `self` into [`InOutBuf`] with initialized output slice.
+    ///
+    /// # Safety
+    /// All elements of the output slice must be properly initialized, e.g.
+    /// by writing to them using [`InOutBufMaybeUninit::get_out`].
+    #[inline(always)]
+    pub unsafe fn assume_init(self) -> InOutBuf<'inp, 'out, T> {
+        InOutBuf::from_raw(self.in_ptr, self.out_ptr as *mut T, self.len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn assume_init() {
+        let input = [1u8, 2, 3];
+        let mut output = [MaybeUninit::<u8>::uninit(); 3];
+        let mut buf = InOutBufMaybeUninit::new(&input, &mut output).unwrap();
+        assert_eq!(buf.len(), 3);
+        for (o, i) in buf.get_out().iter_mut().zip(input.iter()) {
+            *o = MaybeUninit::new(*i ^ 0xFF);
+        }
+        let mut buf = unsafe { buf.assume_init() };
+        assert_eq!(buf.get_out(), [0xFE, 0xFD, 0xFC]);
+        assert!(InOutBufMaybeUninit::new(&input, &mut output[1..]).is_err());
+
+        let mut out = MaybeUninit::uninit();
+        let mut inout = InOutMaybeUninit::from((&5u32, &mut out));
+        *inout.get_out() = MaybeUninit::new(*inout.get_in() + 1);
+        assert_eq!(*unsafe { inout.assume_init() }.get_out(), 6);
+    }
+}
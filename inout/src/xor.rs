@@ -0,0 +1,193 @@
+//! XOR and copy routines used by `xor_in2out`, `xor3_in2out`,
+//! `copy_in2out` and `cmov_in2out` methods.
+//!
+//! On x86 targets SSE2 and AVX2 backends are selected at runtime using
+//! the `cpufeatures` crate, other backends are selected at compile time.
+//! Each backend processes as many bytes as it can and returns their number,
+//! the remaining tail is processed by the portable byte-wise loop.
+use core::ptr;
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod avx2;
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+mod neon;
+#[cfg(all(feature = "pulp", target_arch = "riscv32"))]
+mod pulp;
+#[cfg(target_arch = "riscv64")]
+mod rv64;
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+mod simd128;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod sse2;
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+cpufeatures::new!(avx2_cpuid, "avx2");
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+cpufeatures::new!(sse2_cpuid, "sse2");
+
+/// XOR `data` with bytes behind `in_ptr` and write result to `out_ptr`.
+///
+/// # Safety
+/// `in_ptr` must be valid for reads and `out_ptr` must be valid for writes
+/// of `data.len()` bytes. The pointers must be either equal or non-overlapping.
+#[inline(always)]
+pub(crate) unsafe fn xor_bytes(in_ptr: *const u8, out_ptr: *mut u8, data: &[u8]) {
+    #[allow(unused_mut)]
+    let mut pos = 0;
+    #[cfg(all(feature = "pulp", target_arch = "riscv32"))]
+    {
+        pos += pulp::xor(in_ptr, out_ptr, data);
+    }
+    #[cfg(target_arch = "riscv64")]
+    {
+        pos += rv64::xor(in_ptr, out_ptr, data);
+    }
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if avx2_cpuid::get() {
+            pos += avx2::xor(in_ptr, out_ptr, data);
+        }
+        if sse2_cpuid::get() {
+            pos += sse2::xor(in_ptr.add(pos), out_ptr.add(pos), &data[pos..]);
+        }
+    }
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    {
+        pos += neon::xor(in_ptr, out_ptr, data);
+    }
+    #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+    {
+        pos += simd128::xor(in_ptr, out_ptr, data);
+    }
+    soft_xor(in_ptr.add(pos), out_ptr.add(pos), &data[pos..]);
+}
+
+/// XOR `a` and `b` with bytes behind `in_ptr` and write result to `out_ptr`.
+///
+/// # Safety
+/// `a` and `b` must have equal length. `in_ptr` must be valid for reads
+/// and `out_ptr` must be valid for writes of `a.len()` bytes. The pointers
+/// must be either equal or non-overlapping.
+#[inline(always)]
+pub(crate) unsafe fn xor3_bytes(in_ptr: *const u8, out_ptr: *mut u8, a: &[u8], b: &[u8]) {
+    debug_assert_eq!(a.len(), b.len());
+    #[allow(unused_mut)]
+    let mut pos = 0;
+    #[cfg(all(feature = "pulp", target_arch = "riscv32"))]
+    {
+        pos += pulp::xor3(in_ptr, out_ptr, a, b);
+    }
+    #[cfg(target_arch = "riscv64")]
+    {
+        pos += rv64::xor3(in_ptr, out_ptr, a, b);
+    }
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if avx2_cpuid::get() {
+            pos += avx2::xor3(in_ptr, out_ptr, a, b);
+        }
+        if sse2_cpuid::get() {
+            let (in_ptr, out_ptr) = (in_ptr.add(pos), out_ptr.add(pos));
+            pos += sse2::xor3(in_ptr, out_ptr, &a[pos..], &b[pos..]);
+        }
+    }
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    {
+        pos += neon::xor3(in_ptr, out_ptr, a, b);
+    }
+    #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+    {
+        pos += simd128::xor3(in_ptr, out_ptr, a, b);
+    }
+    for i in pos..a.len() {
+        ptr::write(out_ptr.add(i), ptr::read(in_ptr.add(i)) ^ a[i] ^ b[i]);
+    }
+}
+
+/// Copy `len` bytes from `in_ptr` to `out_ptr`.
+///
+/// # Safety
+/// `in_ptr` must be valid for reads and `out_ptr` must be valid for writes
+/// of `len` bytes. The pointers must be non-overlapping.
+#[inline(always)]
+pub(crate) unsafe fn copy_bytes(in_ptr: *const u8, out_ptr: *mut u8, len: usize) {
+    #[allow(unused_mut)]
+    let mut pos = 0;
+    #[cfg(all(feature = "pulp", target_arch = "riscv32"))]
+    {
+        pos += pulp::copy(in_ptr, out_ptr, len);
+    }
+    ptr::copy_nonoverlapping(in_ptr.add(pos), out_ptr.add(pos), len - pos);
+}
+
+/// Copy `len` bytes from `in_ptr` to `out_ptr` in constant time if
+/// `condition` is not equal to zero.
+///
+/// # Safety
+/// `in_ptr` must be valid for reads and `out_ptr` must be valid for both
+/// reads and writes of `len` bytes. The pointers must be either equal or
+/// non-overlapping.
+#[cfg(feature = "cmov")]
+#[inline(always)]
+pub(crate) unsafe fn cmov_bytes(condition: u8, in_ptr: *const u8, out_ptr: *mut u8, len: usize) {
+    const WORD: usize = core::mem::size_of::<usize>();
+    let condition = condition as usize;
+    let words = len / WORD;
+    for i in 0..words {
+        let src = ptr::read_unaligned((in_ptr as *const usize).add(i));
+        let dst = (out_ptr as *mut usize).add(i);
+        let mut val = ptr::read_unaligned(dst);
+        cmov::cmovnz(condition, src, &mut val);
+        ptr::write_unaligned(dst, val);
+    }
+    for i in WORD * words..len {
+        let mut val = ptr::read(out_ptr.add(i)) as usize;
+        cmov::cmovnz(condition, ptr::read(in_ptr.add(i)) as usize, &mut val);
+        ptr::write(out_ptr.add(i), val as u8);
+    }
+}
+
+/// Portable byte-wise XOR.
+#[inline(always)]
+#[allow(clippy::needless_range_loop)]
+unsafe fn soft_xor(in_ptr: *const u8, out_ptr: *mut u8, data: &[u8]) {
+    for i in 0..data.len() {
+        ptr::write(out_ptr.add(i), ptr::read(in_ptr.add(i)) ^ data[i]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn xor_lengths() {
+        let data: [u8; 100] = [0x3C; 100];
+        for len in 0..data.len() {
+            let input = [0xA5u8; 100];
+            let mut output = [0u8; 100];
+            unsafe { xor_bytes(input.as_ptr(), output.as_mut_ptr(), &data[..len]) };
+            assert!(output[..len].iter().all(|&b| b == 0x99));
+            assert!(output[len..].iter().all(|&b| b == 0));
+
+            let mut buf = [0xA5u8; 100];
+            let p = buf.as_mut_ptr();
+            unsafe { xor_bytes(p, p, &data[..len]) };
+            assert!(buf[..len].iter().all(|&b| b == 0x99));
+            assert!(buf[len..].iter().all(|&b| b == 0xA5));
+        }
+    }
+
+    #[test]
+    fn xor3_lengths() {
+        let a: [u8; 100] = [0x3C; 100];
+        let b: [u8; 100] = [0x0F; 100];
+        for len in 0..a.len() {
+            let input = [0xA5u8; 100];
+            let mut output = [0u8; 100];
+            unsafe { xor3_bytes(input.as_ptr(), output.as_mut_ptr(), &a[..len], &b[..len]) };
+            assert!(output[..len].iter().all(|&v| v == 0x96));
+            assert!(output[len..].iter().all(|&v| v == 0));
+        }
+    }
+}
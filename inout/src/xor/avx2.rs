@@ -0,0 +1,40 @@
+//! AVX2 backend, processes 32 bytes per iteration.
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+const BLOCK: usize = 32;
+
+/// Process 32-byte chunks, returns number of processed bytes.
+#[inline]
+#[target_feature(enable = "avx2")]
+pub(super) unsafe fn xor(in_ptr: *const u8, out_ptr: *mut u8, data: &[u8]) -> usize {
+    let n = data.len() / BLOCK;
+    for i in 0..n {
+        let pos = BLOCK * i;
+        let a = _mm256_loadu_si256(in_ptr.add(pos) as *const __m256i);
+        let b = _mm256_loadu_si256(data.as_ptr().add(pos) as *const __m256i);
+        _mm256_storeu_si256(out_ptr.add(pos) as *mut __m256i, _mm256_xor_si256(a, b));
+    }
+    BLOCK * n
+}
+
+/// Process 32-byte chunks of XOR of `a` and `b`, returns number of processed
+/// bytes.
+#[inline]
+#[target_feature(enable = "avx2")]
+pub(super) unsafe fn xor3(in_ptr: *const u8, out_ptr: *mut u8, a: &[u8], b: &[u8]) -> usize {
+    let n = a.len() / BLOCK;
+    for i in 0..n {
+        let pos = BLOCK * i;
+        let x = _mm256_loadu_si256(in_ptr.add(pos) as *const __m256i);
+        let y = _mm256_loadu_si256(a.as_ptr().add(pos) as *const __m256i);
+        let z = _mm256_loadu_si256(b.as_ptr().add(pos) as *const __m256i);
+        _mm256_storeu_si256(
+            out_ptr.add(pos) as *mut __m256i,
+            _mm256_xor_si256(_mm256_xor_si256(x, y), z),
+        );
+    }
+    BLOCK * n
+}
@@ -0,0 +1,34 @@
+//! NEON backend, processes 16 bytes per iteration.
+use core::arch::aarch64::*;
+
+const BLOCK: usize = 16;
+
+/// Process 16-byte chunks, returns number of processed bytes.
+#[inline]
+#[target_feature(enable = "neon")]
+pub(super) unsafe fn xor(in_ptr: *const u8, out_ptr: *mut u8, data: &[u8]) -> usize {
+    let n = data.len() / BLOCK;
+    for i in 0..n {
+        let pos = BLOCK * i;
+        let a = vld1q_u8(in_ptr.add(pos));
+        let b = vld1q_u8(data.as_ptr().add(pos));
+        vst1q_u8(out_ptr.add(pos), veorq_u8(a, b));
+    }
+    BLOCK * n
+}
+
+/// Process 16-byte chunks of XOR of `a` and `b`, returns number of processed
+/// bytes.
+#[inline]
+#[target_feature(enable = "neon")]
+pub(super) unsafe fn xor3(in_ptr: *const u8, out_ptr: *mut u8, a: &[u8], b: &[u8]) -> usize {
+    let n = a.len() / BLOCK;
+    for i in 0..n {
+        let pos = BLOCK * i;
+        let x = vld1q_u8(in_ptr.add(pos));
+        let y = vld1q_u8(a.as_ptr().add(pos));
+        let z = vld1q_u8(b.as_ptr().add(pos));
+        vst1q_u8(out_ptr.add(pos), veorq_u8(veorq_u8(x, y), z));
+    }
+    BLOCK * n
+}
@@ -0,0 +1,110 @@
+//! Backend for PULP cores, uses hardware loops and post-increment
+//! loads and stores.
+
+/// Process 8 bytes per loop iteration, returns number of processed bytes.
+///
+/// Buffers which are not 4-byte aligned are not processed.
+#[inline(always)]
+pub(super) unsafe fn xor(in_ptr: *const u8, out_ptr: *mut u8, data: &[u8]) -> usize {
+    // word loads and stores require 4-byte aligned pointers
+    let addrs = in_ptr as usize | out_ptr as usize | data.as_ptr() as usize;
+    let aligned = addrs % core::mem::align_of::<u32>() == 0;
+    let words = if aligned { data.len() / 8 } else { 0 };
+    if words != 0 {
+        // t0 / t1 data unroll
+        // t2 / t3 input unroll
+        // Note that packed-SIMD `pv.xor` would not be faster here: XOR is
+        // a bitwise operation, so the scalar `xor` already processes
+        // 4 bytes per instruction and the number of loads stays the same.
+        core::arch::asm!(
+            asm_macros::lp_setup!(0, t4, 16),
+            asm_macros::lw_pi!(t0, 4(a0!)),
+            asm_macros::lw_pi!(t1, 4(a0!)),
+            asm_macros::lw_pi!(t2, 4(a1!)),
+            asm_macros::lw_pi!(t3, 4(a1!)),
+            asm_macros::xor!(t0, t0, t2),
+            asm_macros::xor!(t1, t1, t3),
+            asm_macros::sw_pi!(t0, 4(a2!)),
+            asm_macros::sw_pi!(t1, 4(a2!)),
+            // pointers are post-incremented by the loop
+            inout("a0") in_ptr as *const u32 => _,
+            inout("a1") data.as_ptr() as *const u32 => _,
+            inout("a2") out_ptr as *const u32 => _,
+            out("t0") _,
+            out("t1") _,
+            out("t2") _,
+            out("t3") _,
+            in("t4") words,
+        )
+    }
+    8 * words
+}
+
+/// Copy 8 bytes per loop iteration, returns number of copied bytes.
+///
+/// Buffers which are not 4-byte aligned are not processed.
+#[inline(always)]
+pub(super) unsafe fn copy(in_ptr: *const u8, out_ptr: *mut u8, len: usize) -> usize {
+    let aligned = (in_ptr as usize | out_ptr as usize) % core::mem::align_of::<u32>() == 0;
+    let words = if aligned { len / 8 } else { 0 };
+    if words != 0 {
+        core::arch::asm!(
+            asm_macros::lp_setup!(0, t2, 8),
+            asm_macros::lw_pi!(t0, 4(a0!)),
+            asm_macros::lw_pi!(t1, 4(a0!)),
+            asm_macros::sw_pi!(t0, 4(a1!)),
+            asm_macros::sw_pi!(t1, 4(a1!)),
+            // pointers are post-incremented by the loop
+            inout("a0") in_ptr as *const u32 => _,
+            inout("a1") out_ptr as *const u32 => _,
+            out("t0") _,
+            out("t1") _,
+            in("t2") words,
+        )
+    }
+    8 * words
+}
+
+/// XOR 8 bytes of `a` and `b` per loop iteration, returns number of processed
+/// bytes.
+///
+/// Buffers which are not 4-byte aligned are not processed.
+#[inline(always)]
+pub(super) unsafe fn xor3(in_ptr: *const u8, out_ptr: *mut u8, a: &[u8], b: &[u8]) -> usize {
+    let addrs = in_ptr as usize | out_ptr as usize | a.as_ptr() as usize | b.as_ptr() as usize;
+    let aligned = addrs % core::mem::align_of::<u32>() == 0;
+    let words = if aligned { a.len() / 8 } else { 0 };
+    if words != 0 {
+        // t0 / t1 input unroll
+        // t2 / t3 `a` unroll
+        // t4 / t5 `b` unroll
+        core::arch::asm!(
+            asm_macros::lp_setup!(0, t6, 24),
+            asm_macros::lw_pi!(t0, 4(a0!)),
+            asm_macros::lw_pi!(t1, 4(a0!)),
+            asm_macros::lw_pi!(t2, 4(a1!)),
+            asm_macros::lw_pi!(t3, 4(a1!)),
+            asm_macros::lw_pi!(t4, 4(a2!)),
+            asm_macros::lw_pi!(t5, 4(a2!)),
+            asm_macros::xor!(t0, t0, t2),
+            asm_macros::xor!(t1, t1, t3),
+            asm_macros::xor!(t0, t0, t4),
+            asm_macros::xor!(t1, t1, t5),
+            asm_macros::sw_pi!(t0, 4(a3!)),
+            asm_macros::sw_pi!(t1, 4(a3!)),
+            // pointers are post-incremented by the loop
+            inout("a0") in_ptr as *const u32 => _,
+            inout("a1") a.as_ptr() as *const u32 => _,
+            inout("a2") b.as_ptr() as *const u32 => _,
+            inout("a3") out_ptr as *const u32 => _,
+            out("t0") _,
+            out("t1") _,
+            out("t2") _,
+            out("t3") _,
+            out("t4") _,
+            out("t5") _,
+            in("t6") words,
+        )
+    }
+    8 * words
+}
@@ -0,0 +1,46 @@
+//! Backend for 64-bit RISC-V targets, uses double-word loads and stores.
+//!
+//! RV64 cores do not have PULP hardware loops and post-increment memory
+//! operations, so the loop is written in Rust and compiles to `ld`/`sd`.
+use core::{mem, ptr};
+
+const WORD: usize = mem::size_of::<u64>();
+
+/// Process 8 bytes per iteration, returns number of processed bytes.
+///
+/// Buffers which are not 8-byte aligned are not processed.
+#[inline(always)]
+pub(super) unsafe fn xor(in_ptr: *const u8, out_ptr: *mut u8, data: &[u8]) -> usize {
+    let addrs = in_ptr as usize | out_ptr as usize | data.as_ptr() as usize;
+    if addrs % mem::align_of::<u64>() != 0 {
+        return 0;
+    }
+    let words = data.len() / WORD;
+    let (in_ptr, out_ptr) = (in_ptr as *const u64, out_ptr as *mut u64);
+    let data_ptr = data.as_ptr() as *const u64;
+    for i in 0..words {
+        let v = ptr::read(in_ptr.add(i)) ^ ptr::read(data_ptr.add(i));
+        ptr::write(out_ptr.add(i), v);
+    }
+    WORD * words
+}
+
+/// XOR 8 bytes of `a` and `b` per iteration, returns number of processed
+/// bytes.
+///
+/// Buffers which are not 8-byte aligned are not processed.
+#[inline(always)]
+pub(super) unsafe fn xor3(in_ptr: *const u8, out_ptr: *mut u8, a: &[u8], b: &[u8]) -> usize {
+    let addrs = in_ptr as usize | out_ptr as usize | a.as_ptr() as usize | b.as_ptr() as usize;
+    if addrs % mem::align_of::<u64>() != 0 {
+        return 0;
+    }
+    let words = a.len() / WORD;
+    let (in_ptr, out_ptr) = (in_ptr as *const u64, out_ptr as *mut u64);
+    let (a_ptr, b_ptr) = (a.as_ptr() as *const u64, b.as_ptr() as *const u64);
+    for i in 0..words {
+        let v = ptr::read(in_ptr.add(i)) ^ ptr::read(a_ptr.add(i)) ^ ptr::read(b_ptr.add(i));
+        ptr::write(out_ptr.add(i), v);
+    }
+    WORD * words
+}
@@ -0,0 +1,34 @@
+//! WebAssembly `simd128` backend, processes 16 bytes per iteration.
+use core::arch::wasm32::*;
+
+const BLOCK: usize = 16;
+
+/// Process 16-byte chunks, returns number of processed bytes.
+#[inline]
+#[target_feature(enable = "simd128")]
+pub(super) unsafe fn xor(in_ptr: *const u8, out_ptr: *mut u8, data: &[u8]) -> usize {
+    let n = data.len() / BLOCK;
+    for i in 0..n {
+        let pos = BLOCK * i;
+        let a = v128_load(in_ptr.add(pos) as *const v128);
+        let b = v128_load(data.as_ptr().add(pos) as *const v128);
+        v128_store(out_ptr.add(pos) as *mut v128, v128_xor(a, b));
+    }
+    BLOCK * n
+}
+
+/// Process 16-byte chunks of XOR of `a` and `b`, returns number of processed
+/// bytes.
+#[inline]
+#[target_feature(enable = "simd128")]
+pub(super) unsafe fn xor3(in_ptr: *const u8, out_ptr: *mut u8, a: &[u8], b: &[u8]) -> usize {
+    let n = a.len() / BLOCK;
+    for i in 0..n {
+        let pos = BLOCK * i;
+        let x = v128_load(in_ptr.add(pos) as *const v128);
+        let y = v128_load(a.as_ptr().add(pos) as *const v128);
+        let z = v128_load(b.as_ptr().add(pos) as *const v128);
+        v128_store(out_ptr.add(pos) as *mut v128, v128_xor(v128_xor(x, y), z));
+    }
+    BLOCK * n
+}
@@ -0,0 +1,40 @@
+//! SSE2 backend, processes 16 bytes per iteration.
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+const BLOCK: usize = 16;
+
+/// Process 16-byte chunks, returns number of processed bytes.
+#[inline]
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn xor(in_ptr: *const u8, out_ptr: *mut u8, data: &[u8]) -> usize {
+    let n = data.len() / BLOCK;
+    for i in 0..n {
+        let pos = BLOCK * i;
+        let a = _mm_loadu_si128(in_ptr.add(pos) as *const __m128i);
+        let b = _mm_loadu_si128(data.as_ptr().add(pos) as *const __m128i);
+        _mm_storeu_si128(out_ptr.add(pos) as *mut __m128i, _mm_xor_si128(a, b));
+    }
+    BLOCK * n
+}
+
+/// Process 16-byte chunks of XOR of `a` and `b`, returns number of processed
+/// bytes.
+#[inline]
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn xor3(in_ptr: *const u8, out_ptr: *mut u8, a: &[u8], b: &[u8]) -> usize {
+    let n = a.len() / BLOCK;
+    for i in 0..n {
+        let pos = BLOCK * i;
+        let x = _mm_loadu_si128(in_ptr.add(pos) as *const __m128i);
+        let y = _mm_loadu_si128(a.as_ptr().add(pos) as *const __m128i);
+        let z = _mm_loadu_si128(b.as_ptr().add(pos) as *const __m128i);
+        _mm_storeu_si128(
+            out_ptr.add(pos) as *mut __m128i,
+            _mm_xor_si128(_mm_xor_si128(x, y), z),
+        );
+    }
+    BLOCK * n
+}
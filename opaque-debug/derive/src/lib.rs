@@ -0,0 +1,409 @@
+//! Custom derive support for `opaque-debug`
+
+#![crate_type = "proc-macro"]
+#![forbid(unsafe_code)]
+#![warn(rust_2018_idioms, trivial_casts, unused_qualifications)]
+
+use proc_macro2::{TokenStream, TokenTree};
+use quote::quote;
+use syn::{
+    parse_macro_input, parse_quote, Attribute, Data, DeriveInput, Fields, GenericParam, Ident, Lit,
+    Meta, NestedMeta, Type,
+};
+
+/// Derive opaque `Debug` implementation.
+///
+/// The generated implementation uses the "StructName { ... }" format. In
+/// contrast to `#[derive(Debug)]` it does not require generic parameters
+/// to implement `Debug`.
+///
+/// Supports the following attributes:
+///
+/// On the item level:
+/// - `#[opaque_debug(format = "...")]`: use custom format of the output,
+///   e.g. `#[opaque_debug(format = "{name}([REDACTED; {len}])")]`. The
+///   `{name}` placeholder is replaced with name of the type and `{len}`
+///   with its size in bytes. Can not be combined with exposed fields.
+/// - `#[opaque_debug(display)]`: additionally generate `Display`
+///   implementation with the same output
+///
+/// On the field level:
+/// - `#[opaque_debug(expose)]`: include value of this non-sensitive field
+///   into the output, e.g. "StructName { len: 32, ... }" (structs only)
+#[proc_macro_derive(OpaqueDebug, attributes(opaque_debug))]
+pub fn derive_opaque_debug(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    derive(&input).into()
+}
+
+/// Name of opaque-debug-related attributes
+const OPAQUE_DEBUG_ATTR: &str = "opaque_debug";
+
+/// Custom derive for `OpaqueDebug`
+fn derive(input: &DeriveInput) -> TokenStream {
+    let name = &input.ident;
+    let attrs = ItemAttrs::parse(&input.attrs);
+    let exposed = exposed_fields(input);
+    assert!(
+        attrs.format.is_none() || exposed.is_empty(),
+        "The #[opaque_debug(format)] attribute can not be combined with exposed fields."
+    );
+
+    let mut generics = input.generics.clone();
+    let type_params: Vec<&Ident> = input
+        .generics
+        .params
+        .iter()
+        .filter_map(|p| match p {
+            GenericParam::Type(t) => Some(&t.ident),
+            _ => None,
+        })
+        .collect();
+    for (_, _, ty) in &exposed {
+        if mentions_params(quote!(#ty), &type_params) {
+            generics
+                .make_where_clause()
+                .predicates
+                .push(parse_quote!(#ty: ::opaque_debug::__core::fmt::Debug));
+        }
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let body = if let Some(format) = &attrs.format {
+        let name = name.to_string();
+        quote! {
+            ::opaque_debug::__write_format(
+                f,
+                #format,
+                #name,
+                ::opaque_debug::__core::mem::size_of::<Self>(),
+            )
+        }
+    } else if exposed.is_empty() {
+        let text = format!("{} {{ ... }}", name);
+        quote! { f.write_str(#text) }
+    } else {
+        let prefix = format!("{} {{ ", name);
+        let fields = exposed.iter().map(|(label, member, _)| {
+            let label = format!("{}: ", label);
+            quote! {
+                f.write_str(#label)?;
+                ::opaque_debug::__core::fmt::Debug::fmt(&self.#member, f)?;
+                f.write_str(", ")?;
+            }
+        });
+        quote! {
+            f.write_str(#prefix)?;
+            #(#fields)*
+            f.write_str("... }")
+        }
+    };
+
+    let mut res = quote! {
+        impl #impl_generics ::opaque_debug::__core::fmt::Debug for #name #ty_generics
+        #where_clause
+        {
+            fn fmt(
+                &self,
+                f: &mut ::opaque_debug::__core::fmt::Formatter<'_>,
+            ) -> ::opaque_debug::__core::fmt::Result {
+                #body
+            }
+        }
+    };
+    if attrs.display {
+        res.extend(quote! {
+            impl #impl_generics ::opaque_debug::__core::fmt::Display for #name #ty_generics
+            #where_clause
+            {
+                fn fmt(
+                    &self,
+                    f: &mut ::opaque_debug::__core::fmt::Formatter<'_>,
+                ) -> ::opaque_debug::__core::fmt::Result {
+                    ::opaque_debug::__core::fmt::Debug::fmt(self, f)
+                }
+            }
+        });
+    }
+    res
+}
+
+/// Collect fields marked with `#[opaque_debug(expose)]` as tuples of
+/// label, field accessor and field type.
+fn exposed_fields(input: &DeriveInput) -> Vec<(String, TokenStream, &Type)> {
+    match &input.data {
+        Data::Struct(data) => data
+            .fields
+            .iter()
+            .enumerate()
+            .filter(|(_, field)| has_expose_attr(&field.attrs))
+            .map(|(i, field)| match &field.ident {
+                Some(ident) => (ident.to_string(), quote!(#ident), &field.ty),
+                None => {
+                    let index = syn::Index::from(i);
+                    (i.to_string(), quote!(#index), &field.ty)
+                }
+            })
+            .collect(),
+        Data::Enum(data) => {
+            for variant in &data.variants {
+                let fields = match &variant.fields {
+                    Fields::Named(f) => f.named.iter().collect(),
+                    Fields::Unnamed(f) => f.unnamed.iter().collect(),
+                    Fields::Unit => Vec::new(),
+                };
+                assert!(
+                    !has_expose_attr(&variant.attrs)
+                        && fields.iter().all(|f| !has_expose_attr(&f.attrs)),
+                    "The #[opaque_debug(expose)] attribute is not supported on enums."
+                );
+            }
+            Vec::new()
+        }
+        Data::Union(_) => Vec::new(),
+    }
+}
+
+/// Item-level `#[opaque_debug(...)]` attributes
+#[derive(Default)]
+struct ItemAttrs {
+    /// Custom format of the output
+    format: Option<String>,
+    /// Generate `Display` implementation
+    display: bool,
+}
+
+impl ItemAttrs {
+    /// Parse attributes from the item
+    fn parse(attrs: &[Attribute]) -> Self {
+        let mut res = Self::default();
+        for nested_meta in &nested_metas(attrs) {
+            match nested_meta {
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("format") => {
+                    let lit = match &nv.lit {
+                        Lit::Str(lit) => lit.value(),
+                        _ => panic!("format of #[opaque_debug] attribute must be a string literal"),
+                    };
+                    assert!(
+                        res.format.is_none(),
+                        "duplicate #[opaque_debug] format flags"
+                    );
+                    res.format = Some(lit);
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("display") => {
+                    assert!(!res.display, "duplicate #[opaque_debug] display flags");
+                    res.display = true;
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("expose") => panic!(
+                    "The #[opaque_debug(expose)] attribute is only allowed on struct fields."
+                ),
+                _ => panic!(
+                    "unknown #[opaque_debug] attribute: {}",
+                    quote!(#nested_meta)
+                ),
+            }
+        }
+        res
+    }
+}
+
+/// Check if `attrs` contain the `#[opaque_debug(expose)]` attribute
+fn has_expose_attr(attrs: &[Attribute]) -> bool {
+    let mut exposed = false;
+    for nested_meta in &nested_metas(attrs) {
+        match nested_meta {
+            NestedMeta::Meta(Meta::Path(path)) if path.is_ident("expose") => {
+                assert!(!exposed, "duplicate #[opaque_debug] expose flags");
+                exposed = true;
+            }
+            _ => panic!(
+                "unknown #[opaque_debug] attribute: {}",
+                quote!(#nested_meta)
+            ),
+        }
+    }
+    exposed
+}
+
+/// Collect nested items of all `#[opaque_debug(...)]` attributes
+fn nested_metas(attrs: &[Attribute]) -> Vec<NestedMeta> {
+    let mut res = Vec::new();
+    for attr in attrs {
+        if !attr.path.is_ident(OPAQUE_DEBUG_ATTR) {
+            continue;
+        }
+        match attr
+            .parse_meta()
+            .unwrap_or_else(|e| panic!("error parsing attribute: {} ({})", quote!(#attr), e))
+        {
+            Meta::List(list) => res.extend(list.nested),
+            _ => panic!("malformed #[opaque_debug] attribute: {}", quote!(#attr)),
+        }
+    }
+    res
+}
+
+/// Check if `tokens` mention any of the generic type parameters
+fn mentions_params(tokens: TokenStream, params: &[&Ident]) -> bool {
+    tokens.into_iter().any(|tt| match tt {
+        TokenTree::Ident(ident) => params.iter().any(|&p| *p == ident),
+        TokenTree::Group(group) => mentions_params(group.stream(), params),
+        _ => false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_str;
+
+    fn derive_test(unparsed: &str) -> String {
+        derive(&parse_str(unparsed).expect("Failed to parse test input")).to_string()
+    }
+
+    #[test]
+    fn derive_generics() {
+        let expected = quote! {
+            impl<'a, T: Clone, const N: usize> ::opaque_debug::__core::fmt::Debug for Foo<'a, T, N>
+            where
+                T: Copy,
+            {
+                fn fmt(
+                    &self,
+                    f: &mut ::opaque_debug::__core::fmt::Formatter<'_>,
+                ) -> ::opaque_debug::__core::fmt::Result {
+                    f.write_str("Foo { ... }")
+                }
+            }
+        };
+        let actual = derive_test(stringify!(
+            struct Foo<'a, T: Clone, const N: usize>
+            where
+                T: Copy,
+            {
+                a: &'a [T; N],
+            }
+        ));
+        assert_eq!(actual, expected.to_string());
+    }
+
+    #[test]
+    fn derive_exposed_fields() {
+        let expected = quote! {
+            impl<T> ::opaque_debug::__core::fmt::Debug for Foo<T>
+            where
+                T: ::opaque_debug::__core::fmt::Debug
+            {
+                fn fmt(
+                    &self,
+                    f: &mut ::opaque_debug::__core::fmt::Formatter<'_>,
+                ) -> ::opaque_debug::__core::fmt::Result {
+                    f.write_str("Foo { ")?;
+                    f.write_str("len: ")?;
+                    ::opaque_debug::__core::fmt::Debug::fmt(&self.len, f)?;
+                    f.write_str(", ")?;
+                    f.write_str("alg: ")?;
+                    ::opaque_debug::__core::fmt::Debug::fmt(&self.alg, f)?;
+                    f.write_str(", ")?;
+                    f.write_str("... }")
+                }
+            }
+        };
+        let actual = derive_test(stringify!(
+            struct Foo<T> {
+                key: [u8; 32],
+                #[opaque_debug(expose)]
+                len: usize,
+                #[opaque_debug(expose)]
+                alg: T,
+            }
+        ));
+        assert_eq!(actual, expected.to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "The #[opaque_debug(expose)] attribute is not supported on enums.")]
+    fn expose_enum_field() {
+        derive_test(stringify!(
+            enum Foo {
+                A(#[opaque_debug(expose)] u8),
+            }
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "unknown #[opaque_debug] attribute: show")]
+    fn unknown_attribute() {
+        derive_test(stringify!(
+            struct Foo {
+                #[opaque_debug(show)]
+                len: usize,
+            }
+        ));
+    }
+
+    #[test]
+    fn derive_format() {
+        let expected = quote! {
+            impl ::opaque_debug::__core::fmt::Debug for Key {
+                fn fmt(
+                    &self,
+                    f: &mut ::opaque_debug::__core::fmt::Formatter<'_>,
+                ) -> ::opaque_debug::__core::fmt::Result {
+                    ::opaque_debug::__write_format(
+                        f,
+                        "{name}([REDACTED; {len}])",
+                        "Key",
+                        ::opaque_debug::__core::mem::size_of::<Self>(),
+                    )
+                }
+            }
+        };
+        let actual = derive_test(stringify!(
+            #[opaque_debug(format = "{name}([REDACTED; {len}])")]
+            struct Key([u8; 32]);
+        ));
+        assert_eq!(actual, expected.to_string());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "The #[opaque_debug(format)] attribute can not be combined with exposed fields."
+    )]
+    fn format_with_exposed_fields() {
+        derive_test(stringify!(
+            #[opaque_debug(format = "{name}")]
+            struct Foo {
+                #[opaque_debug(expose)]
+                len: usize,
+            }
+        ));
+    }
+
+    #[test]
+    fn derive_display() {
+        let expected = quote! {
+            impl ::opaque_debug::__core::fmt::Debug for Key {
+                fn fmt(
+                    &self,
+                    f: &mut ::opaque_debug::__core::fmt::Formatter<'_>,
+                ) -> ::opaque_debug::__core::fmt::Result {
+                    f.write_str("Key { ... }")
+                }
+            }
+            impl ::opaque_debug::__core::fmt::Display for Key {
+                fn fmt(
+                    &self,
+                    f: &mut ::opaque_debug::__core::fmt::Formatter<'_>,
+                ) -> ::opaque_debug::__core::fmt::Result {
+                    ::opaque_debug::__core::fmt::Debug::fmt(self, f)
+                }
+            }
+        };
+        let actual = derive_test(stringify!(
+            #[opaque_debug(display)]
+            struct Key([u8; 32]);
+        ));
+        assert_eq!(actual, expected.to_string());
+    }
+}
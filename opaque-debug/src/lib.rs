@@ -1,5 +1,38 @@
 //! Macro for opaque `Debug` trait implementation.
+//!
+//! # Examples
+//! ```
+//! struct Key([u8; 32]);
+//!
+//! opaque_debug::implement!(Key);
+//!
+//! assert_eq!(format!("{:?}", Key([42; 32])), "Key { ... }");
+//! ```
+//!
+//! With the `derive` feature enabled the implementation can be derived,
+//! which also supports generic types:
+//! ```
+//! # #[cfg(feature = "derive")]
+//! # {
+//! use opaque_debug::OpaqueDebug;
+//!
+//! #[derive(OpaqueDebug)]
+//! struct State<'a, T> {
+//!     key: &'a [T],
+//! }
+//!
+//! assert_eq!(format!("{:?}", State { key: &[42u8] }), "State { ... }");
+//! # }
+//! ```
+//!
+//! Values of foreign types can be redacted using the [`Redact`] wrapper:
+//! ```
+//! let password = String::from("hunter2");
+//! let s = format!("login: {}, password: {}", "alice", opaque_debug::Redact(&password));
+//! assert_eq!(s, "login: alice, password: [REDACTED]");
+//! ```
 #![no_std]
+#![cfg_attr(docsrs, feature(doc_cfg))]
 #![doc(
     html_logo_url = "https://raw.githubusercontent.com/RustCrypto/media/6ee8e381/logo.svg",
     html_favicon_url = "https://raw.githubusercontent.com/RustCrypto/media/6ee8e381/logo.svg"
@@ -8,14 +41,157 @@
 #[doc(hidden)]
 pub extern crate core as __core;
 
+#[cfg(feature = "derive")]
+#[cfg_attr(docsrs, doc(cfg(feature = "derive")))]
+pub use opaque_debug_derive::OpaqueDebug;
+
+/// Wrapper which redacts the wrapped value in `Debug` and `Display` output.
+///
+/// It's useful for one-off redaction of values of foreign types, e.g. in log
+/// statements. Both implementations write `[REDACTED]` regardless of the
+/// wrapped value.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Redact<T>(pub T);
+
+impl<T> Redact<T> {
+    /// Unwrap the wrapped value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> From<T> for Redact<T> {
+    fn from(value: T) -> Self {
+        Redact(value)
+    }
+}
+
+impl<T> core::fmt::Debug for Redact<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.write_str("[REDACTED]")
+    }
+}
+
+impl<T> core::fmt::Display for Redact<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.write_str("[REDACTED]")
+    }
+}
+
+/// Write `format` into `f` replacing the `{name}` placeholder with `name`
+/// and the `{len}` placeholder with `len`. The `{{` and `}}` sequences are
+/// replaced with `{` and `}` respectively.
+#[doc(hidden)]
+pub fn __write_format(
+    f: &mut core::fmt::Formatter,
+    format: &str,
+    name: &str,
+    len: usize,
+) -> core::fmt::Result {
+    let mut rest = format;
+    while let Some(i) = rest.find(&['{', '}'][..]) {
+        f.write_str(&rest[..i])?;
+        rest = &rest[i..];
+        let n = if rest.starts_with("{{") || rest.starts_with("}}") {
+            f.write_str(&rest[..1])?;
+            2
+        } else if rest.starts_with("{name}") {
+            f.write_str(name)?;
+            "{name}".len()
+        } else if rest.starts_with("{len}") {
+            write!(f, "{}", len)?;
+            "{len}".len()
+        } else {
+            f.write_str(&rest[..1])?;
+            1
+        };
+        rest = &rest[n..];
+    }
+    f.write_str(rest)
+}
+
 /// Macro for defining opaque `Debug` implementation.
 ///
 /// It will use the following format: "StructName { ... }". While it's
 /// convinient to have it (e.g. for including into other structs), it could be
 /// undesirable to leak internal state, which can happen for example through
 /// uncareful logging.
+///
+/// Generic types are supported by prefixing the type with `impl` and
+/// a list of its generic parameters (lifetimes, type parameters and const
+/// parameters). Bounds of the parameters have to be specified using
+/// the `where` clause:
+///
+/// ```
+/// struct State<'a, T: Copy> {
+///     key: &'a [T],
+/// }
+///
+/// opaque_debug::implement!(impl<'a, T> State<'a, T> where T: Copy);
+///
+/// assert_eq!(format!("{:?}", State { key: &[0u8; 4] }), "State { ... }");
+/// ```
+///
+/// Non-sensitive fields can be included into the output by listing them
+/// in braces after the type. Types of the listed fields must implement `Debug`:
+///
+/// ```
+/// struct Key {
+///     bytes: [u8; 32],
+///     len: usize,
+/// }
+///
+/// opaque_debug::implement!(Key { len });
+///
+/// let key = Key { bytes: [42; 32], len: 32 };
+/// assert_eq!(format!("{:?}", key), "Key { len: 32, ... }");
+/// ```
+///
+/// Alternatively a custom format can be specified after the type. The
+/// `{name}` placeholder is replaced with name of the type and `{len}` with
+/// its size in bytes, which allows to distinguish empty values from redacted
+/// ones in logs:
+///
+/// ```
+/// struct Key([u8; 32]);
+/// struct State<T>(T);
+///
+/// opaque_debug::implement!(Key, "{name}([REDACTED; {len}])");
+/// opaque_debug::implement!(impl<T> State<T>, "{name}(..)" where T: Copy);
+///
+/// assert_eq!(format!("{:?}", Key([42; 32])), "Key([REDACTED; 32])");
+/// assert_eq!(format!("{:?}", State(1u8)), "State(..)");
+/// ```
 #[macro_export]
 macro_rules! implement {
+    // Generic types have to be prefixed with `impl`, so concrete types
+    // like `Foo<u8>` are handled by the `$struct:ty` rules below.
+    (impl < $($rest:tt)*) => {
+        $crate::implement!(@params [] $($rest)*);
+    };
+    ($struct:ty, $format:expr) => {
+        impl $crate::__core::fmt::Debug for $struct {
+            fn fmt(
+                &self,
+                f: &mut $crate::__core::fmt::Formatter,
+            ) -> Result<(), $crate::__core::fmt::Error> {
+                let len = $crate::__core::mem::size_of::<Self>();
+                $crate::__write_format(f, $format, stringify!($struct), len)
+            }
+        }
+    };
+    ($struct:ty { $($field:tt),* $(,)? }) => {
+        impl $crate::__core::fmt::Debug for $struct {
+            fn fmt(
+                &self,
+                f: &mut $crate::__core::fmt::Formatter,
+            ) -> Result<(), $crate::__core::fmt::Error> {
+                write!(f, concat!(stringify!($struct), " {{ "))?;
+                $(write!(f, concat!(stringify!($field), ": {:?}, "), self.$field)?;)*
+                f.write_str("... }")
+            }
+        }
+    };
     ($struct:ty) => {
         impl $crate::__core::fmt::Debug for $struct {
             fn fmt(
@@ -26,4 +202,60 @@ macro_rules! implement {
             }
         }
     };
+    // Internal rules which collect generic parameters of the `impl` block.
+    (@params [$($params:tt)*] > $($rest:tt)*) => {
+        $crate::implement!(@impl [$($params)*] $($rest)*);
+    };
+    (@params [$($params:tt)*] $lt:lifetime, $($rest:tt)*) => {
+        $crate::implement!(@params [$($params)* $lt,] $($rest)*);
+    };
+    (@params [$($params:tt)*] $lt:lifetime > $($rest:tt)*) => {
+        $crate::implement!(@impl [$($params)* $lt] $($rest)*);
+    };
+    (@params [$($params:tt)*] const $name:ident: $ty:ty, $($rest:tt)*) => {
+        $crate::implement!(@params [$($params)* const $name: $ty,] $($rest)*);
+    };
+    (@params [$($params:tt)*] const $name:ident: $ty:ty > $($rest:tt)*) => {
+        $crate::implement!(@impl [$($params)* const $name: $ty] $($rest)*);
+    };
+    (@params [$($params:tt)*] $name:ident, $($rest:tt)*) => {
+        $crate::implement!(@params [$($params)* $name,] $($rest)*);
+    };
+    (@params [$($params:tt)*] $name:ident > $($rest:tt)*) => {
+        $crate::implement!(@impl [$($params)* $name] $($rest)*);
+    };
+    (
+        @impl [$($params:tt)*] $struct:ident < $($args:tt),+ >
+        , $format:literal $(where $($bounds:tt)*)?
+    ) => {
+        impl<$($params)*> $crate::__core::fmt::Debug for $struct<$($args),+>
+        $(where $($bounds)*)?
+        {
+            fn fmt(
+                &self,
+                f: &mut $crate::__core::fmt::Formatter,
+            ) -> Result<(), $crate::__core::fmt::Error> {
+                let len = $crate::__core::mem::size_of::<Self>();
+                $crate::__write_format(f, $format, stringify!($struct), len)
+            }
+        }
+    };
+    (
+        @impl [$($params:tt)*] $struct:ident < $($args:tt),+ >
+        $({ $($field:tt),* $(,)? })?
+        $(where $($bounds:tt)*)?
+    ) => {
+        impl<$($params)*> $crate::__core::fmt::Debug for $struct<$($args),+>
+        $(where $($bounds)*)?
+        {
+            fn fmt(
+                &self,
+                f: &mut $crate::__core::fmt::Formatter,
+            ) -> Result<(), $crate::__core::fmt::Error> {
+                write!(f, concat!(stringify!($struct), " {{ "))?;
+                $($(write!(f, concat!(stringify!($field), ": {:?}, "), self.$field)?;)*)?
+                f.write_str("... }")
+            }
+        }
+    };
 }
@@ -0,0 +1,47 @@
+//! Tests for types with const generic parameters, which require Rust 1.51
+#![allow(dead_code)]
+
+struct Generic<'a, T: Clone + 'a, U, const N: usize>
+where
+    U: Copy,
+{
+    secret: &'a [T; N],
+    other: U,
+}
+
+opaque_debug::implement!(
+    impl<'a, T, U, const N: usize,> Generic<'a, T, U, N> where T: Clone + 'a, U: Copy
+);
+
+struct Exposed<const N: usize> {
+    secret: [u8; N],
+    len: usize,
+}
+
+opaque_debug::implement!(impl<const N: usize> Exposed<N> { len });
+
+struct Redacted<T, const N: usize>([T; N]);
+
+opaque_debug::implement!(impl<T, const N: usize> Redacted<T, N>, "{name}({len})" where T: Copy);
+
+#[test]
+fn const_generics() {
+    let s = format!(
+        "{:?}",
+        Generic {
+            secret: &[1u8, 2],
+            other: 3u32,
+        }
+    );
+    assert_eq!(s, "Generic { ... }");
+    let s = format!(
+        "{:?}",
+        Exposed {
+            secret: [0; 4],
+            len: 4
+        }
+    );
+    assert_eq!(s, "Exposed { len: 4, ... }");
+    let s = format!("{:?}", Redacted([0u16; 3]));
+    assert_eq!(s, "Redacted(6)");
+}
@@ -0,0 +1,90 @@
+//! Integration tests for `opaque_debug_derive` proc macros
+#![cfg(feature = "derive")]
+#![allow(dead_code)]
+
+use opaque_debug::OpaqueDebug;
+
+#[derive(OpaqueDebug)]
+struct Foo {
+    secret: u64,
+}
+
+#[derive(OpaqueDebug)]
+struct Generic<'a, T: Clone, const N: usize>
+where
+    T: Copy,
+{
+    secret: &'a [T; N],
+}
+
+#[derive(OpaqueDebug)]
+struct Exposed<T, U> {
+    secret: T,
+    #[opaque_debug(expose)]
+    len: usize,
+    #[opaque_debug(expose)]
+    alg: U,
+}
+
+#[derive(OpaqueDebug)]
+struct ExposedTuple([u8; 4], #[opaque_debug(expose)] u8);
+
+#[derive(OpaqueDebug)]
+#[opaque_debug(format = "{name}([REDACTED; {len}])")]
+struct Redacted<T>([T; 4]);
+
+#[derive(OpaqueDebug)]
+#[opaque_debug(display, format = "{name}(..)")]
+struct Displayed<T>(T);
+
+#[derive(OpaqueDebug)]
+enum Enum<T> {
+    A(T),
+    B,
+}
+
+#[test]
+fn debug_formatting() {
+    assert_eq!(format!("{:?}", Foo { secret: 42 }), "Foo { ... }");
+    assert_eq!(
+        format!(
+            "{:?}",
+            Generic {
+                secret: &[1u8, 2, 3]
+            }
+        ),
+        "Generic { ... }"
+    );
+    assert_eq!(format!("{:?}", Enum::A(1u8)), "Enum { ... }");
+    assert_eq!(format!("{:?}", Enum::<u8>::B), "Enum { ... }");
+}
+
+#[test]
+fn exposed_fields() {
+    struct NotDebug;
+
+    let v = Exposed {
+        secret: NotDebug,
+        len: 32,
+        alg: "sha256",
+    };
+    assert_eq!(
+        format!("{:?}", v),
+        r#"Exposed { len: 32, alg: "sha256", ... }"#
+    );
+    let v = ExposedTuple([1, 2, 3, 4], 5);
+    assert_eq!(format!("{:?}", v), "ExposedTuple { 1: 5, ... }");
+}
+
+#[test]
+fn custom_format() {
+    let v = Redacted([0u64; 4]);
+    assert_eq!(format!("{:?}", v), "Redacted([REDACTED; 32])");
+}
+
+#[test]
+fn display() {
+    let v = Displayed(42u8);
+    assert_eq!(format!("{}", v), "Displayed(..)");
+    assert_eq!(format!("{}", v), format!("{:?}", v));
+}
@@ -6,8 +6,112 @@ struct Foo {
 
 opaque_debug::implement!(Foo);
 
+struct Lifetime<'a> {
+    secret: &'a [u8],
+}
+
+opaque_debug::implement!(impl<'a> Lifetime<'a>);
+
+struct Generic<'a, T: Clone + 'a, U>
+where
+    U: Copy,
+{
+    secret: &'a [T],
+    other: U,
+}
+
+opaque_debug::implement!(impl<'a, T, U,> Generic<'a, T, U> where T: Clone + 'a, U: Copy);
+
+struct Concrete<T>(T);
+
+opaque_debug::implement!(Concrete<u8>);
+opaque_debug::implement!(Concrete<[u8; 4]>);
+
+struct Exposed {
+    secret: u64,
+    len: usize,
+    name: &'static str,
+}
+
+opaque_debug::implement!(Exposed { len, name });
+
+struct ExposedTuple([u8; 4], u8);
+
+opaque_debug::implement!(ExposedTuple { 1 });
+
+struct ExposedGeneric<T> {
+    secret: T,
+    len: usize,
+}
+
+opaque_debug::implement!(impl<T> ExposedGeneric<T> { len, });
+
+struct Redacted([u8; 32]);
+
+opaque_debug::implement!(Redacted, "{name}([REDACTED; {len}])");
+
+struct RedactedGeneric<T>([T; 3]);
+
+opaque_debug::implement!(impl<T> RedactedGeneric<T>, "{{{name}}} {len}" where T: Copy);
+
 #[test]
 fn debug_formatting() {
     let s = format!("{:?}", Foo { secret: 42 });
     assert_eq!(s, "Foo { ... }");
 }
+
+#[test]
+fn generics() {
+    let s = format!("{:?}", Lifetime { secret: &[42] });
+    assert_eq!(s, "Lifetime { ... }");
+    let s = format!(
+        "{:?}",
+        Generic {
+            secret: &[1u8, 2],
+            other: 3u32,
+        }
+    );
+    assert_eq!(s, "Generic { ... }");
+}
+
+#[test]
+fn concrete_generic_types() {
+    assert_eq!(format!("{:?}", Concrete(1u8)), "Concrete<u8> { ... }");
+    assert_eq!(
+        format!("{:?}", Concrete([1u8; 4])),
+        "Concrete<[u8; 4]> { ... }"
+    );
+}
+
+#[test]
+fn exposed_fields() {
+    let s = format!(
+        "{:?}",
+        Exposed {
+            secret: 42,
+            len: 8,
+            name: "foo",
+        }
+    );
+    assert_eq!(s, r#"Exposed { len: 8, name: "foo", ... }"#);
+    let s = format!("{:?}", ExposedTuple([1, 2, 3, 4], 5));
+    assert_eq!(s, "ExposedTuple { 1: 5, ... }");
+    let s = format!("{:?}", ExposedGeneric { secret: (), len: 3 });
+    assert_eq!(s, "ExposedGeneric { len: 3, ... }");
+}
+
+#[test]
+fn custom_format() {
+    let s = format!("{:?}", Redacted([0; 32]));
+    assert_eq!(s, "Redacted([REDACTED; 32])");
+    let s = format!("{:?}", RedactedGeneric([0u16; 3]));
+    assert_eq!(s, "{RedactedGeneric} 6");
+}
+
+#[test]
+fn redact() {
+    let v = opaque_debug::Redact([1u8, 2, 3]);
+    assert_eq!(format!("{:?}", v), "[REDACTED]");
+    assert_eq!(format!("{}", opaque_debug::Redact("secret")), "[REDACTED]");
+    assert_eq!(v.into_inner(), [1, 2, 3]);
+}
@@ -1,5 +1,5 @@
 use crate::wycheproof;
-use crate::wycheproof::{case_result, description, hex_string};
+use crate::wycheproof::{description, hex_string, CaseFilter};
 use crate::TestInfo;
 use serde::Deserialize;
 
@@ -42,19 +42,54 @@ pub struct TestCase {
     pub tag: Vec<u8>,
 }
 
-pub fn aes_gcm_generator(data: &[u8], algorithm: &str, key_size: u32) -> Vec<TestInfo> {
-    generator(data, algorithm, key_size, 12 * 8)
+pub fn aes_gcm_generator(
+    data: &[u8],
+    algorithm: &str,
+    key_size: u32,
+    filter: &CaseFilter,
+) -> Vec<TestInfo> {
+    generator(data, algorithm, key_size, 12 * 8, filter)
 }
 
-pub fn chacha20_poly1305(data: &[u8], algorithm: &str, _key_size: u32) -> Vec<TestInfo> {
-    generator(data, algorithm, 256, 12 * 8)
+pub fn chacha20_poly1305(
+    data: &[u8],
+    algorithm: &str,
+    _key_size: u32,
+    filter: &CaseFilter,
+) -> Vec<TestInfo> {
+    generator(data, algorithm, 256, 12 * 8, filter)
 }
 
-pub fn xchacha20_poly1305(data: &[u8], algorithm: &str, _key_size: u32) -> Vec<TestInfo> {
-    generator(data, algorithm, 256, 24 * 8)
+pub fn xchacha20_poly1305(
+    data: &[u8],
+    algorithm: &str,
+    _key_size: u32,
+    filter: &CaseFilter,
+) -> Vec<TestInfo> {
+    generator(data, algorithm, 256, 24 * 8, filter)
 }
 
-fn generator(data: &[u8], algorithm: &str, key_size: u32, iv_size: u32) -> Vec<TestInfo> {
+/// Generator for AEADs which support nonces of any size, e.g. AES-CCM or AES-EAX.
+pub fn any_iv_generator(
+    data: &[u8],
+    algorithm: &str,
+    key_size: u32,
+    filter: &CaseFilter,
+) -> Vec<TestInfo> {
+    generator(data, algorithm, key_size, 0, filter)
+}
+
+/// Generate test cases with the given key and IV sizes (in bits, 0 for all sizes).
+///
+/// Only test cases with 128-bit tags are included. Each test case consists of
+/// key, IV, AAD, plaintext, ciphertext with appended tag and result flag.
+fn generator(
+    data: &[u8],
+    algorithm: &str,
+    key_size: u32,
+    iv_size: u32,
+    filter: &CaseFilter,
+) -> Vec<TestInfo> {
     let suite: TestSuite = serde_json::from_slice(data).unwrap();
     assert_eq!(algorithm, suite.suite.algorithm);
 
@@ -64,10 +99,19 @@ fn generator(data: &[u8], algorithm: &str, key_size: u32, iv_size: u32) -> Vec<T
             if key_size != 0 && g.key_size != key_size {
                 continue;
             }
-            if g.iv_size != iv_size {
+            if iv_size != 0 && g.iv_size != iv_size {
                 println!(" skipping tests for iv_size={}", g.iv_size);
                 continue;
             }
+            if g.tag_size != 128 {
+                println!(" skipping tests for tag_size={}", g.tag_size);
+                continue;
+            }
+            let result = match filter.case_result(&tc.case) {
+                Some(result) => result,
+                None => continue,
+            };
+            assert_eq!(tc.iv.len() * 8, g.iv_size as usize);
             let mut combined_ct = Vec::new();
             combined_ct.extend_from_slice(&tc.ct);
             combined_ct.extend_from_slice(&tc.tag);
@@ -79,9 +123,12 @@ fn generator(data: &[u8], algorithm: &str, key_size: u32, iv_size: u32) -> Vec<T
                     tc.aad.clone(),
                     tc.msg.clone(),
                     combined_ct,
-                    vec![case_result(&tc.case)],
+                    vec![result],
                 ],
                 desc: description(&suite.suite, &tc.case),
+                case_id: tc.case.case_id,
+                comment: tc.case.comment.clone(),
+                flags: tc.case.flags.clone(),
             });
         }
     }
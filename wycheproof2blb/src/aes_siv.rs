@@ -1,5 +1,5 @@
 use crate::wycheproof;
-use crate::wycheproof::{case_result, description, hex_string};
+use crate::wycheproof::{description, hex_string, CaseFilter};
 use crate::TestInfo;
 use serde::Deserialize;
 
@@ -35,7 +35,12 @@ struct TestCase {
     pub ct: Vec<u8>,
 }
 
-pub fn generator(data: &[u8], algorithm: &str, key_size: u32) -> Vec<TestInfo> {
+pub fn generator(
+    data: &[u8],
+    algorithm: &str,
+    key_size: u32,
+    filter: &CaseFilter,
+) -> Vec<TestInfo> {
     let suite: TestSuite = serde_json::from_slice(data).unwrap();
     assert_eq!(algorithm, suite.suite.algorithm);
 
@@ -45,15 +50,22 @@ pub fn generator(data: &[u8], algorithm: &str, key_size: u32) -> Vec<TestInfo> {
             continue;
         }
         for tc in &g.tests {
+            let result = match filter.case_result(&tc.case) {
+                Some(result) => result,
+                None => continue,
+            };
             infos.push(TestInfo {
                 data: vec![
                     tc.key.clone(),
                     tc.aad.clone(),
                     tc.msg.clone(),
                     tc.ct.clone(),
-                    vec![case_result(&tc.case)],
+                    vec![result],
                 ],
                 desc: description(&suite.suite, &tc.case),
+                case_id: tc.case.case_id,
+                comment: tc.case.comment.clone(),
+                flags: tc.case.flags.clone(),
             });
         }
     }
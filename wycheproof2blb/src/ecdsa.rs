@@ -1,5 +1,5 @@
 use crate::wycheproof;
-use crate::wycheproof::{case_result, description, hex_string};
+use crate::wycheproof::{description, hex_string, CaseFilter};
 use crate::TestInfo;
 use serde::Deserialize;
 
@@ -49,27 +49,56 @@ struct TestCase {
     pub sig: Vec<u8>,
 }
 
-pub fn generator(data: &[u8], algorithm: &str, _key_size: u32) -> Vec<TestInfo> {
+/// Generate test cases for the curve given by `algorithm`, optionally suffixed
+/// with `-p1363` for files using the IEEE P1363 signature encoding.
+///
+/// Each test case consists of public key coordinates, message, signature and
+/// result flag. Public key is taken from the test group and emitted for each
+/// test case.
+///
+/// For files selected by schema `algorithm` is `ECDSA`. Such files may contain
+/// test groups for different curves and hash functions, so names of the curve
+/// and the hash function of the test group are prepended to each test case.
+pub fn generator(
+    data: &[u8],
+    algorithm: &str,
+    _key_size: u32,
+    filter: &CaseFilter,
+) -> Vec<TestInfo> {
     let suite: TestSuite = serde_json::from_slice(data).unwrap();
+    let curve = match algorithm.trim_end_matches("-p1363") {
+        "ECDSA" => None,
+        curve => Some(curve),
+    };
 
     let mut infos = vec![];
     for g in &suite.test_groups {
-        assert_eq!(g.key.curve, algorithm);
-        assert_eq!(g.sha, "SHA-256");
+        if let Some(curve) = curve {
+            assert_eq!(g.key.curve, curve);
+        }
         for tc in &g.tests {
-            if tc.case.result == crate::wycheproof::CaseResult::Acceptable {
-                // TODO: figure out what to do with test cases that pass but which have weak params
-                continue;
+            let result = match filter.case_result(&tc.case) {
+                Some(result) => result,
+                None => continue,
+            };
+            let mut data = vec![];
+            if curve.is_none() {
+                data.push(g.key.curve.as_bytes().to_vec());
+                data.push(g.sha.as_bytes().to_vec());
             }
+            data.extend(vec![
+                g.key.wx.clone(),
+                g.key.wy.clone(),
+                tc.msg.clone(),
+                tc.sig.clone(),
+                vec![result],
+            ]);
             infos.push(TestInfo {
-                data: vec![
-                    g.key.wx.clone(),
-                    g.key.wy.clone(),
-                    tc.msg.clone(),
-                    tc.sig.clone(),
-                    vec![case_result(&tc.case)],
-                ],
+                data,
                 desc: description(&suite.suite, &tc.case),
+                case_id: tc.case.case_id,
+                comment: tc.case.comment.clone(),
+                flags: tc.case.flags.clone(),
             });
         }
     }
@@ -1,5 +1,5 @@
 use crate::wycheproof;
-use crate::wycheproof::{case_result, description, hex_string};
+use crate::wycheproof::{description, hex_string, CaseFilter};
 use crate::TestInfo;
 use serde::Deserialize;
 
@@ -44,22 +44,50 @@ struct TestCase {
     pub sig: Vec<u8>,
 }
 
-pub fn generator(data: &[u8], algorithm: &str, _key_size: u32) -> Vec<TestInfo> {
+pub fn generator(
+    data: &[u8],
+    algorithm: &str,
+    _key_size: u32,
+    filter: &CaseFilter,
+) -> Vec<TestInfo> {
+    eddsa_generator(data, algorithm, filter)
+}
+
+/// Ed448 test vectors use the same schema and algorithm name as Ed25519 ones.
+pub fn ed448_generator(
+    data: &[u8],
+    _algorithm: &str,
+    _key_size: u32,
+    filter: &CaseFilter,
+) -> Vec<TestInfo> {
+    eddsa_generator(data, "EDDSA", filter)
+}
+
+/// Generate test cases, public and secret keys are taken from the test group
+/// and emitted for each test case.
+fn eddsa_generator(data: &[u8], algorithm: &str, filter: &CaseFilter) -> Vec<TestInfo> {
     let suite: TestSuite = serde_json::from_slice(data).unwrap();
     assert_eq!(algorithm, suite.suite.algorithm);
 
     let mut infos = vec![];
     for g in &suite.test_groups {
         for tc in &g.tests {
+            let result = match filter.case_result(&tc.case) {
+                Some(result) => result,
+                None => continue,
+            };
             infos.push(TestInfo {
                 data: vec![
                     g.key.sk.clone(),
                     g.key.pk.clone(),
                     tc.msg.clone(),
                     tc.sig.clone(),
-                    vec![case_result(&tc.case)],
+                    vec![result],
                 ],
                 desc: description(&suite.suite, &tc.case),
+                case_id: tc.case.case_id,
+                comment: tc.case.comment.clone(),
+                flags: tc.case.flags.clone(),
             });
         }
     }
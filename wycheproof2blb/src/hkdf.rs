@@ -1,5 +1,5 @@
 use crate::wycheproof;
-use crate::wycheproof::{description, hex_string};
+use crate::wycheproof::{description, hex_string, CaseFilter};
 use crate::TestInfo;
 use serde::Deserialize;
 
@@ -37,14 +37,20 @@ struct TestCase {
     pub okm: Vec<u8>,
 }
 
-pub fn generator(data: &[u8], algorithm: &str, _key_size: u32) -> Vec<TestInfo> {
+pub fn generator(
+    data: &[u8],
+    algorithm: &str,
+    _key_size: u32,
+    filter: &CaseFilter,
+) -> Vec<TestInfo> {
     let suite: TestSuite = serde_json::from_slice(data).unwrap();
     assert_eq!(algorithm, suite.suite.algorithm);
 
     let mut infos = vec![];
     for g in &suite.test_groups {
         for tc in &g.tests {
-            if tc.case.result != crate::wycheproof::CaseResult::Valid {
+            // only valid test cases can be represented
+            if filter.case_result(&tc.case) != Some(1) {
                 continue;
             }
             if tc.okm.len() != tc.size {
@@ -63,6 +69,9 @@ pub fn generator(data: &[u8], algorithm: &str, _key_size: u32) -> Vec<TestInfo>
                     tc.okm.clone(),
                 ],
                 desc: description(&suite.suite, &tc.case),
+                case_id: tc.case.case_id,
+                comment: tc.case.comment.clone(),
+                flags: tc.case.flags.clone(),
             });
         }
     }
@@ -1,5 +1,5 @@
 use crate::wycheproof;
-use crate::wycheproof::{description, hex_string, CaseResult};
+use crate::wycheproof::{description, hex_string, CaseFilter};
 use crate::TestInfo;
 use serde::Deserialize;
 
@@ -34,7 +34,12 @@ pub struct TestCase {
     pub tag: Vec<u8>,
 }
 
-pub fn generator(data: &[u8], algorithm: &str, key_size: u32) -> Vec<TestInfo> {
+pub fn generator(
+    data: &[u8],
+    algorithm: &str,
+    key_size: u32,
+    filter: &CaseFilter,
+) -> Vec<TestInfo> {
     let suite: TestSuite = serde_json::from_slice(data).unwrap();
     assert_eq!(algorithm, suite.suite.algorithm);
 
@@ -44,7 +49,7 @@ pub fn generator(data: &[u8], algorithm: &str, key_size: u32) -> Vec<TestInfo> {
             if key_size != 0 && g.key_size != key_size {
                 continue;
             }
-            if tc.case.result != CaseResult::Valid {
+            if filter.case_result(&tc.case) != Some(1) {
                 // TODO: adapt HMAC tests for invalid cases
                 continue;
             }
@@ -59,6 +64,9 @@ pub fn generator(data: &[u8], algorithm: &str, key_size: u32) -> Vec<TestInfo> {
                     tc.tag.clone(),
                 ],
                 desc: description(&suite.suite, &tc.case),
+                case_id: tc.case.case_id,
+                comment: tc.case.comment.clone(),
+                flags: tc.case.flags.clone(),
             });
         }
     }
@@ -10,8 +10,10 @@ mod aes_siv;
 mod ecdsa;
 mod ed25519;
 mod hkdf;
+mod keywrap;
 mod mac;
 mod wycheproof;
+mod xdh;
 
 /// Test information
 pub struct TestInfo {
@@ -19,20 +21,44 @@ pub struct TestInfo {
     pub data: Vec<Vec<u8>>,
     /// Test case description.
     pub desc: String,
+    /// Wycheproof ID of the test case.
+    pub case_id: i32,
+    /// Wycheproof comment of the test case.
+    pub comment: String,
+    /// Wycheproof flags of the test case.
+    pub flags: Vec<String>,
 }
 
 /// Generator function which takes input parameters:
 ///  - contents of Wycheproof test data file
 ///  - algorithm name
 ///  - key size (in bits) to include
+///  - filter which decides how acceptable test cases are handled
 /// and returns the raw contents, together  with a list of test identifiers (one per entry).
-type BlbGenerator = fn(&[u8], &str, u32) -> Vec<TestInfo>;
+type BlbGenerator = fn(&[u8], &str, u32, &wycheproof::CaseFilter) -> Vec<TestInfo>;
 
 struct Algorithm {
     pub file: &'static str,
     pub generator: BlbGenerator,
 }
 
+/// Select generator for the given Wycheproof schema
+fn schema_generator(schema: &str) -> Option<BlbGenerator> {
+    let generator: BlbGenerator = match schema {
+        "aead_test_schema.json" => aead::any_iv_generator,
+        "daead_test_schema.json" => aes_siv::generator,
+        "ecdsa_p1363_verify_schema.json" => ecdsa::generator,
+        "ecdsa_verify_schema.json" => ecdsa::generator,
+        "eddsa_verify_schema.json" => ed25519::generator,
+        "hkdf_test_schema.json" => hkdf::generator,
+        "keywrap_test_schema.json" => keywrap::generator,
+        "mac_test_schema.json" => mac::generator,
+        "xdh_comp_schema.json" => xdh::generator,
+        _ => return None,
+    };
+    Some(generator)
+}
+
 fn main() {
     let args: Vec<String> = std::env::args().collect();
     let wycheproof_dir = args
@@ -46,87 +72,170 @@ fn main() {
         .expect("Key size needs to be a number of bits");
     let out_path = args.get(4).expect("Provide path for output blobby file");
     let descriptions_path = args.get(5).expect("Provide path for descriptions file");
+    let mut filter = wycheproof::CaseFilter::default();
+    let mut metadata_path = None;
+    for arg in &args[6..] {
+        if let Some(path) = arg.strip_prefix("--metadata=") {
+            metadata_path = Some(path);
+        } else if let Some(flag) = arg.strip_prefix("--include-flag=") {
+            filter.include.push(flag.to_string());
+        } else if let Some(flag) = arg.strip_prefix("--exclude-flag=") {
+            filter.exclude.push(flag.to_string());
+        } else if let Some(mode) = arg.strip_prefix("--acceptable=") {
+            filter.acceptable = match mode {
+                "skip" => wycheproof::AcceptableCases::Skip,
+                "valid" => wycheproof::AcceptableCases::Valid,
+                "invalid" => wycheproof::AcceptableCases::Invalid,
+                _ => panic!("Unrecognized handling of acceptable cases '{}'", mode),
+            };
+        } else {
+            panic!("Unrecognized option '{}'", arg);
+        }
+    }
 
-    let algo = match algorithm.as_str() {
-        "AES-GCM" => Algorithm {
-            file: "aes_gcm_test.json",
-            generator: aead::aes_gcm_generator,
-        },
-        "AES-GCM-SIV" => Algorithm {
-            file: "aes_gcm_siv_test.json",
-            generator: aead::aes_gcm_generator,
-        },
-        "CHACHA20-POLY1305" => Algorithm {
-            file: "chacha20_poly1305_test.json",
-            generator: aead::chacha20_poly1305,
-        },
-        "XCHACHA20-POLY1305" => Algorithm {
-            file: "xchacha20_poly1305_test.json",
-            generator: aead::xchacha20_poly1305,
-        },
-        "AES-SIV-CMAC" => Algorithm {
-            file: "aes_siv_cmac_test.json",
-            generator: aes_siv::generator,
-        },
-        "AES-CMAC" => Algorithm {
-            file: "aes_cmac_test.json",
-            generator: mac::generator,
-        },
-        "HKDF-SHA-1" => Algorithm {
-            file: "hkdf_sha1_test.json",
-            generator: hkdf::generator,
-        },
-        "HKDF-SHA-256" => Algorithm {
-            file: "hkdf_sha256_test.json",
-            generator: hkdf::generator,
-        },
-        "HKDF-SHA-384" => Algorithm {
-            file: "hkdf_sha384_test.json",
-            generator: hkdf::generator,
-        },
-        "HKDF-SHA-512" => Algorithm {
-            file: "hkdf_sha512_test.json",
-            generator: hkdf::generator,
-        },
-        "HMACSHA1" => Algorithm {
-            file: "hmac_sha1_test.json",
-            generator: mac::generator,
-        },
-        "HMACSHA224" => Algorithm {
-            file: "hmac_sha224_test.json",
-            generator: mac::generator,
-        },
-        "HMACSHA256" => Algorithm {
-            file: "hmac_sha256_test.json",
-            generator: mac::generator,
-        },
-        "HMACSHA384" => Algorithm {
-            file: "hmac_sha384_test.json",
-            generator: mac::generator,
-        },
-        "HMACSHA512" => Algorithm {
-            file: "hmac_sha512_test.json",
-            generator: mac::generator,
-        },
-        "EDDSA" => Algorithm {
-            file: "eddsa_test.json",
-            generator: ed25519::generator,
-        },
-        "secp256r1" => Algorithm {
-            file: "ecdsa_secp256r1_sha256_test.json",
-            generator: ecdsa::generator,
-        },
-        // There's also "ecdsa_secp256r1_sha256_p1363_test.json" with a different signature encoding.
-        "secp256k1" => Algorithm {
-            file: "ecdsa_secp256k1_sha256_test.json",
-            generator: ecdsa::generator,
-        },
-        _ => panic!("Unrecognized algorithm '{}'", algorithm),
+    // Test vector files can be also passed directly instead of algorithm names,
+    // in which case the generator and so the record arity is selected based on
+    // the schema of the file
+    let (data, generator, algorithm) = if algorithm.ends_with(".json") {
+        let data = wycheproof::data(wycheproof_dir, algorithm);
+        let suite: wycheproof::Suite = serde_json::from_slice(&data).unwrap();
+        let generator = schema_generator(&suite.schema)
+            .unwrap_or_else(|| panic!("Unsupported schema '{}'", suite.schema));
+        (data, generator, suite.algorithm)
+    } else {
+        let algo = match algorithm.as_str() {
+            "AES-GCM" => Algorithm {
+                file: "aes_gcm_test.json",
+                generator: aead::aes_gcm_generator,
+            },
+            "AES-GCM-SIV" => Algorithm {
+                file: "aes_gcm_siv_test.json",
+                generator: aead::aes_gcm_generator,
+            },
+            "AES-CCM" => Algorithm {
+                file: "aes_ccm_test.json",
+                generator: aead::any_iv_generator,
+            },
+            "AES-EAX" => Algorithm {
+                file: "aes_eax_test.json",
+                generator: aead::any_iv_generator,
+            },
+            "CHACHA20-POLY1305" => Algorithm {
+                file: "chacha20_poly1305_test.json",
+                generator: aead::chacha20_poly1305,
+            },
+            "XCHACHA20-POLY1305" => Algorithm {
+                file: "xchacha20_poly1305_test.json",
+                generator: aead::xchacha20_poly1305,
+            },
+            "AES-SIV-CMAC" => Algorithm {
+                file: "aes_siv_cmac_test.json",
+                generator: aes_siv::generator,
+            },
+            "AES-CMAC" => Algorithm {
+                file: "aes_cmac_test.json",
+                generator: mac::generator,
+            },
+            "HKDF-SHA-1" => Algorithm {
+                file: "hkdf_sha1_test.json",
+                generator: hkdf::generator,
+            },
+            "HKDF-SHA-256" => Algorithm {
+                file: "hkdf_sha256_test.json",
+                generator: hkdf::generator,
+            },
+            "HKDF-SHA-384" => Algorithm {
+                file: "hkdf_sha384_test.json",
+                generator: hkdf::generator,
+            },
+            "HKDF-SHA-512" => Algorithm {
+                file: "hkdf_sha512_test.json",
+                generator: hkdf::generator,
+            },
+            "HMACSHA1" => Algorithm {
+                file: "hmac_sha1_test.json",
+                generator: mac::generator,
+            },
+            "HMACSHA224" => Algorithm {
+                file: "hmac_sha224_test.json",
+                generator: mac::generator,
+            },
+            "HMACSHA256" => Algorithm {
+                file: "hmac_sha256_test.json",
+                generator: mac::generator,
+            },
+            "HMACSHA384" => Algorithm {
+                file: "hmac_sha384_test.json",
+                generator: mac::generator,
+            },
+            "HMACSHA512" => Algorithm {
+                file: "hmac_sha512_test.json",
+                generator: mac::generator,
+            },
+            "EDDSA" => Algorithm {
+                file: "eddsa_test.json",
+                generator: ed25519::generator,
+            },
+            "ED448" => Algorithm {
+                file: "ed448_test.json",
+                generator: ed25519::ed448_generator,
+            },
+            "secp256r1" => Algorithm {
+                file: "ecdsa_secp256r1_sha256_test.json",
+                generator: ecdsa::generator,
+            },
+            "secp256r1-p1363" => Algorithm {
+                file: "ecdsa_secp256r1_sha256_p1363_test.json",
+                generator: ecdsa::generator,
+            },
+            "secp256k1" => Algorithm {
+                file: "ecdsa_secp256k1_sha256_test.json",
+                generator: ecdsa::generator,
+            },
+            "secp256k1-p1363" => Algorithm {
+                file: "ecdsa_secp256k1_sha256_p1363_test.json",
+                generator: ecdsa::generator,
+            },
+            "secp384r1" => Algorithm {
+                file: "ecdsa_secp384r1_sha384_test.json",
+                generator: ecdsa::generator,
+            },
+            "secp384r1-p1363" => Algorithm {
+                file: "ecdsa_secp384r1_sha384_p1363_test.json",
+                generator: ecdsa::generator,
+            },
+            "secp521r1" => Algorithm {
+                file: "ecdsa_secp521r1_sha512_test.json",
+                generator: ecdsa::generator,
+            },
+            "secp521r1-p1363" => Algorithm {
+                file: "ecdsa_secp521r1_sha512_p1363_test.json",
+                generator: ecdsa::generator,
+            },
+            "AES-WRAP" => Algorithm {
+                file: "aes_wrap_test.json",
+                generator: keywrap::generator,
+            },
+            "AES-KWP" => Algorithm {
+                file: "aes_kwp_test.json",
+                generator: keywrap::generator,
+            },
+            "X25519" => Algorithm {
+                file: "x25519_test.json",
+                generator: xdh::generator,
+            },
+            "X448" => Algorithm {
+                file: "x448_test.json",
+                generator: xdh::generator,
+            },
+            _ => panic!("Unrecognized algorithm '{}'", algorithm),
+        };
+        let data = wycheproof::data(wycheproof_dir, algo.file);
+        (data, algo.generator, algorithm.clone())
     };
 
-    let data = wycheproof::data(wycheproof_dir, algo.file);
-
-    let infos = (algo.generator)(&data, algorithm, key_size);
+    let mut infos = generator(&data, &algorithm, key_size, &filter);
+    infos.retain(|info| filter.matches(&info.flags));
     println!("Emitting {} test cases", infos.len());
 
     let mut txt_file = std::fs::File::create(descriptions_path).unwrap();
@@ -134,6 +243,18 @@ fn main() {
         writeln!(&mut txt_file, "{}", info.desc).unwrap();
     }
 
+    // Metadata record `i` describes the test case stored in data record `i`
+    if let Some(path) = metadata_path {
+        let mut w = blobby::BlobWriter::new();
+        w.set_fields(&["tc_id", "comment", "flags"]);
+        for info in &infos {
+            w.push(info.case_id.to_string());
+            w.push(&info.comment);
+            w.push(info.flags.join(","));
+        }
+        std::fs::write(path, w.encode()).unwrap();
+    }
+
     let mut out_file = std::fs::File::create(out_path).unwrap();
     let blobs: Vec<Vec<u8>> = infos.into_iter().flat_map(|info| info.data).collect();
     let (blb_data, _) = blobby::encode_blobs(&blobs);
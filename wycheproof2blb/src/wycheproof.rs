@@ -8,6 +8,8 @@ use serde::Deserialize;
 #[derive(Debug, Deserialize)]
 pub struct Suite {
     pub algorithm: String,
+    #[serde(default)]
+    pub schema: String,
     #[serde(rename = "generatorVersion")]
     pub generator_version: String,
     #[serde(rename = "numberOfTests")]
@@ -64,6 +66,59 @@ pub struct Case {
     pub flags: Vec<String>,
 }
 
+/// Handling of test cases with the `acceptable` result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AcceptableCases {
+    /// Acceptable test cases are skipped.
+    Skip,
+    /// Acceptable test cases are emitted as valid ones.
+    Valid,
+    /// Acceptable test cases are emitted as invalid ones.
+    Invalid,
+}
+
+impl Default for AcceptableCases {
+    fn default() -> Self {
+        AcceptableCases::Skip
+    }
+}
+
+/// `CaseFilter` selects test cases based on their Wycheproof flags and results.
+///
+/// A test case is selected if it has at least one of the `include` flags (or `include`
+/// is empty) and none of the `exclude` flags. Acceptable test cases are handled
+/// according to `acceptable`.
+#[derive(Debug, Default)]
+pub struct CaseFilter {
+    /// Flags of which at least one has to be present in the selected test cases.
+    pub include: Vec<String>,
+    /// Flags which must not be present in the selected test cases.
+    pub exclude: Vec<String>,
+    /// Handling of acceptable test cases.
+    pub acceptable: AcceptableCases,
+}
+
+impl CaseFilter {
+    /// Get the result byte of a test case: 1 for valid cases and 0 for invalid ones.
+    ///
+    /// Returns `None` if the test case is skipped according to `acceptable`.
+    pub fn case_result(&self, case: &Case) -> Option<u8> {
+        match (&case.result, self.acceptable) {
+            (CaseResult::Valid, _) | (CaseResult::Acceptable, AcceptableCases::Valid) => Some(1),
+            (CaseResult::Invalid, _) | (CaseResult::Acceptable, AcceptableCases::Invalid) => {
+                Some(0)
+            }
+            (CaseResult::Acceptable, AcceptableCases::Skip) => None,
+        }
+    }
+
+    /// Check whether a test case with the given flags is selected by this filter.
+    pub fn matches(&self, flags: &[String]) -> bool {
+        let included = self.include.is_empty() || flags.iter().any(|f| self.include.contains(f));
+        included && !flags.iter().any(|f| self.exclude.contains(f))
+    }
+}
+
 pub mod hex_string {
     //! Manual JSON deserialization implementation for hex strings.
     use serde::Deserialize;
@@ -96,15 +151,6 @@ pub mod case_result {
     }
 }
 
-/// Convert a `result` enum to a byte.
-pub fn case_result(case: &Case) -> u8 {
-    match case.result {
-        CaseResult::Invalid => 0u8,
-        CaseResult::Valid => 1u8,
-        _ => panic!("Unexpected case result {}", case.result),
-    }
-}
-
 /// Retrieve Wycheproof test vectors from the given filename in a Wycheproof repo.
 pub fn data(wycheproof_dir: &str, filename: &str) -> Vec<u8> {
     let path = std::path::Path::new(&wycheproof_dir)
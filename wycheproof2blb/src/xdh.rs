@@ -0,0 +1,77 @@
+use crate::wycheproof;
+use crate::wycheproof::{description, hex_string, CaseFilter};
+use crate::TestInfo;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+struct TestSuite {
+    #[serde(flatten)]
+    pub suite: wycheproof::Suite,
+    #[serde(rename = "testGroups")]
+    pub test_groups: Vec<TestGroup>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TestGroup {
+    #[allow(dead_code)]
+    #[serde(flatten)]
+    pub group: wycheproof::Group,
+    pub curve: String,
+    pub tests: Vec<TestCase>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TestCase {
+    #[serde(flatten)]
+    pub case: wycheproof::Case,
+    #[serde(with = "hex_string")]
+    pub public: Vec<u8>,
+    #[serde(with = "hex_string")]
+    pub private: Vec<u8>,
+    #[serde(with = "hex_string")]
+    pub shared: Vec<u8>,
+}
+
+/// Generate test cases for X25519 or X448, or for any curve if `algorithm`
+/// is equal to "XDH".
+pub fn generator(
+    data: &[u8],
+    algorithm: &str,
+    _key_size: u32,
+    filter: &CaseFilter,
+) -> Vec<TestInfo> {
+    let suite: TestSuite = serde_json::from_slice(data).unwrap();
+    assert_eq!(suite.suite.algorithm, "XDH");
+    let curve = match algorithm {
+        "X25519" => Some("curve25519"),
+        "X448" => Some("curve448"),
+        "XDH" => None,
+        _ => panic!("Unrecognized algorithm '{}'", algorithm),
+    };
+
+    let mut infos = vec![];
+    for g in &suite.test_groups {
+        if let Some(curve) = curve {
+            assert_eq!(g.curve, curve);
+        }
+        for tc in &g.tests {
+            let result = match filter.case_result(&tc.case) {
+                Some(result) => result,
+                None => continue,
+            };
+            infos.push(TestInfo {
+                data: vec![
+                    tc.private.clone(),
+                    tc.public.clone(),
+                    tc.shared.clone(),
+                    vec![result],
+                ],
+                desc: description(&suite.suite, &tc.case),
+                case_id: tc.case.case_id,
+                comment: tc.case.comment.clone(),
+                flags: tc.case.flags.clone(),
+            });
+        }
+    }
+    infos
+}